@@ -0,0 +1,67 @@
+use ggez::{Context, GameResult};
+
+use crate::balance;
+use crate::scene::draw_centered;
+use crate::team::Team;
+use crate::{SCREEN_SIZE, TICK_TIME};
+
+/// Notices when the local player stops playing. After a warning countdown
+/// their blob is taken out of the match so it doesn't hold a place on its
+/// team, and it comes back as soon as they do something again.
+pub struct AfkWatch {
+    // Seconds since the last input.
+    idle: f32,
+    // The team of the blob that was taken out, while spectating.
+    spectating: Option<Option<Team>>,
+}
+
+impl AfkWatch {
+    pub fn new() -> AfkWatch {
+        AfkWatch {
+            idle: 0.0,
+            spectating: None,
+        }
+    }
+
+    pub fn is_spectating(&self) -> bool {
+        self.spectating.is_some()
+    }
+
+    /// Call on every input from the player. Returns the team to rejoin if
+    /// they were spectating for being away.
+    pub fn input(&mut self) -> Option<Option<Team>> {
+        self.idle = 0.0;
+        self.spectating.take()
+    }
+
+    /// Call once per tick of play with the team of the player's blob, or
+    /// None without one. Returns true when the player has been away too long
+    /// and should spectate from now on.
+    pub fn update(&mut self, team: Option<Option<Team>>) -> bool {
+        let team = match team {
+            Some(team) if self.spectating.is_none() => team,
+            _ => return false,
+        };
+        self.idle += TICK_TIME;
+        if self.idle < balance::current().afk.idle_time {
+            return false;
+        }
+        self.spectating = Some(team);
+        true
+    }
+
+    /// Warn the player when they are about to be moved to the spectators,
+    /// with the seconds left.
+    pub fn draw_warning(&self, ctx: &mut Context) -> GameResult<()> {
+        let stats = balance::current().afk;
+        let left = stats.idle_time - self.idle;
+        if self.spectating.is_some() || left > stats.warning_time {
+            return Ok(());
+        }
+        let text = format!(
+            "Still there? Moving you to the spectators in {:.0}",
+            left.ceil()
+        );
+        draw_centered(ctx, &text, 32.0, SCREEN_SIZE.1 / 3.0)
+    }
+}
@@ -0,0 +1,95 @@
+use ggez::filesystem;
+use ggez::graphics::{self, Color, Image, WrapMode};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+
+use crate::level::BackgroundLayer;
+use crate::{SCREEN_SIZE, TICK_TIME};
+
+struct Layer {
+    config: BackgroundLayer,
+    image: Image,
+}
+
+/// The level's background layers, drawn back to front so layers further
+/// away scroll slower than closer ones as the view moves. Layers whose
+/// image is missing are left out.
+pub struct Background {
+    layers: Vec<Layer>,
+    // Seconds since the level started, for the drift.
+    time: f32,
+}
+
+impl Background {
+    pub fn new(ctx: &mut Context, layers: &[BackgroundLayer]) -> Background {
+        let layers = layers
+            .iter()
+            .filter_map(|config| {
+                let mut image = load(ctx, &config.image)?;
+                image.set_wrap(WrapMode::Tile, WrapMode::Tile);
+                Some(Layer {
+                    config: config.clone(),
+                    image,
+                })
+            })
+            .collect();
+        Background { layers, time: 0.0 }
+    }
+
+    /// Call once per tick.
+    pub fn update(&mut self) {
+        self.time += TICK_TIME;
+    }
+
+    /// Draw the layers, in world coordinates, covering everything seen by a
+    /// view centered on camera at the given zoom, whichever way it's rolled.
+    pub fn draw(&self, ctx: &mut Context, camera: Point2<f32>, zoom: f32) -> GameResult<()> {
+        if self.layers.is_empty() {
+            return Ok(());
+        }
+        let screen = graphics::screen_coordinates(ctx);
+        let reach = (screen.w.powi(2) + screen.h.powi(2)).sqrt() / 2.0 / zoom;
+        let cover =
+            graphics::Rect::new(camera.x - reach, camera.y - reach, 2.0 * reach, 2.0 * reach);
+        let middle = Point2::new(SCREEN_SIZE.0 / 2.0, SCREEN_SIZE.1 / 2.0);
+        for layer in &self.layers {
+            let config = &layer.config;
+            // Far layers are carried along with the view, so they seem to
+            // move less than the level does.
+            let shift = (1.0 - config.parallax) * (camera - middle)
+                + self.time * Vector2::new(config.scroll.0, config.scroll.1);
+            let (width, height) = (
+                f32::from(layer.image.width()),
+                f32::from(layer.image.height()),
+            );
+            let src = graphics::Rect::new(
+                (cover.x - shift.x) / width,
+                (cover.y - shift.y) / height,
+                cover.w / width,
+                cover.h / height,
+            );
+            graphics::draw(
+                ctx,
+                &layer.image,
+                graphics::DrawParam::new()
+                    .src(src)
+                    .dest(Point2::new(cover.x, cover.y))
+                    .color(Color {
+                        a: config.alpha,
+                        ..graphics::WHITE
+                    }),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn load(ctx: &mut Context, path: &str) -> Option<Image> {
+    if !filesystem::exists(ctx, path) {
+        log::warn!("No background image {}, leaving the layer out", path);
+        return None;
+    }
+    Image::new(ctx, path)
+        .map_err(|e| log::warn!("Could not load background image {}: {}", path, e))
+        .ok()
+}
@@ -0,0 +1,29 @@
+use ggez::{filesystem, Context};
+use std::io::Read;
+
+pub use blobs_core::balance::*;
+
+// In the resource directory, next to the levels.
+const PATH: &str = "/balance.ron";
+
+/// Read the balance file and make it current. On errors the current balance
+/// is kept and the errors are returned, one per line. Without a balance file
+/// the defaults are used.
+pub fn load(ctx: &mut Context) -> Result<(), String> {
+    if !filesystem::exists(ctx, PATH) {
+        log::info!("No balance file, using the default balance");
+        return Ok(());
+    }
+    let mut source = String::new();
+    filesystem::open(ctx, PATH)
+        .and_then(|mut file| Ok(file.read_to_string(&mut source)?))
+        .map_err(|e| format!("Could not read {}: {}", PATH, e))?;
+    let balance: Balance =
+        ron::de::from_str(&source).map_err(|e| format!("Invalid {}: {}", PATH, e))?;
+    let errors = balance.validate();
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    set_current(balance);
+    Ok(())
+}
@@ -0,0 +1,219 @@
+//! Many things drawn in a few draw calls. Blobs, ropes and projectiles add
+//! their shapes to a batch instead of drawing a mesh each, and the batch
+//! draws them all at once.
+
+use ggez::graphics::spritebatch::SpriteBatch;
+use ggez::graphics::{self, Color, DrawMode, DrawParam, Mesh, MeshBuilder, Vertex};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+
+use crate::sprites;
+
+const TOLERANCE: f32 = 0.5;
+
+/// Shapes collected for drawing together. Shapes keep the order they were
+/// added in, but anything drawn with a sprite goes apart: blob sprites below
+/// all shapes and hook heads above them.
+///
+/// The batch keeps its meshes until it is cleared, so a batch that hasn't
+/// changed since the last frame is drawn again without rebuilding anything.
+#[derive(Default)]
+pub struct Batch {
+    shapes: MeshBuilder,
+    has_shapes: bool,
+    // Triangles textured with the blob sprite.
+    blob_vertices: Vec<Vertex>,
+    blob_indices: Vec<u32>,
+    hooks: Vec<DrawParam>,
+    built: Option<Built>,
+}
+
+struct Built {
+    shapes: Option<Mesh>,
+    blobs: Option<Mesh>,
+    hooks: Option<SpriteBatch>,
+}
+
+impl Batch {
+    pub fn new() -> Batch {
+        Batch::default()
+    }
+
+    /// Forget everything added, to add it again where it is now.
+    pub fn clear(&mut self) {
+        *self = Batch::new();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.has_shapes && self.blob_indices.is_empty() && self.hooks.is_empty()
+    }
+
+    pub fn polygon(
+        &mut self,
+        mode: DrawMode,
+        points: &[Point2<f32>],
+        color: Color,
+    ) -> GameResult<()> {
+        self.shapes.polygon(mode, points, color)?;
+        self.shape_added();
+        Ok(())
+    }
+
+    pub fn disc(&mut self, center: Point2<f32>, radius: f32, color: Color) {
+        self.shapes
+            .circle(DrawMode::fill(), center, radius, TOLERANCE, color);
+        self.shape_added();
+    }
+
+    pub fn ring(&mut self, center: Point2<f32>, radius: f32, width: f32, color: Color) {
+        self.shapes
+            .circle(DrawMode::stroke(width), center, radius, TOLERANCE, color);
+        self.shape_added();
+    }
+
+    pub fn line(&mut self, points: &[Point2<f32>], width: f32, color: Color) -> GameResult<()> {
+        self.shapes.line(points, width, color)?;
+        self.shape_added();
+        Ok(())
+    }
+
+    /// A strip through the points, each with its own width and color, that
+    /// blends from one to the next.
+    pub fn ribbon(&mut self, points: &[(Point2<f32>, f32, Color)]) {
+        if points.len() < 2 {
+            return;
+        }
+        let mut vertices = Vec::with_capacity(2 * points.len());
+        for (i, &(point, width, color)) in points.iter().enumerate() {
+            let ahead = points[(i + 1).min(points.len() - 1)].0;
+            let behind = points[i.saturating_sub(1)].0;
+            let along = ahead - behind;
+            let side = if along.norm() > 0.0 {
+                Vector2::new(-along.y, along.x).normalize() * width / 2.0
+            } else {
+                Vector2::zeros()
+            };
+            let color = linear(color);
+            for p in &[point + side, point - side] {
+                vertices.push(Vertex {
+                    pos: [p.x, p.y],
+                    uv: [0.0, 0.0],
+                    color,
+                });
+            }
+        }
+        let mut indices = Vec::with_capacity(6 * (points.len() - 1));
+        for i in 0..points.len() as u32 - 1 {
+            let (a, b) = (2 * i, 2 * i + 1);
+            indices.extend_from_slice(&[a, b, a + 2, b, b + 2, a + 2]);
+        }
+        self.shapes.raw(&vertices, &indices, None);
+        self.shape_added();
+    }
+
+    /// Add the blob sprite stretched over an outline around center, as
+    /// `sprites::blob_vertices` lays it out. Returns false without adding anything if there
+    /// is no blob sprite.
+    pub fn blob_sprite(
+        &mut self,
+        center: Point2<f32>,
+        outline: &[Point2<f32>],
+        color: Color,
+    ) -> bool {
+        let vertices = match sprites::blob_vertices(center, outline, color) {
+            Some(vertices) => vertices,
+            None => return false,
+        };
+        let first = self.blob_vertices.len() as u32;
+        let count = outline.len() as u32;
+        for i in 0..count {
+            self.blob_indices.extend_from_slice(&[
+                first,
+                first + i + 1,
+                first + (i + 1) % count + 1,
+            ]);
+        }
+        self.blob_vertices.extend(vertices);
+        self.changed();
+        true
+    }
+
+    /// Add a hook head, like `sprites::draw_hook`.
+    pub fn hook(&mut self, from: Point2<f32>, hook: Point2<f32>, color: Color) {
+        if let Some(param) = sprites::hook_param(from, hook, color) {
+            self.hooks.push(param);
+            self.changed();
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        if self.built.is_none() {
+            self.built = Some(self.build(ctx)?);
+        }
+        let built = self.built.as_ref().expect("batch was just built");
+        if let Some(blobs) = &built.blobs {
+            graphics::draw(ctx, blobs, DrawParam::new())?;
+        }
+        if let Some(shapes) = &built.shapes {
+            graphics::draw(ctx, shapes, DrawParam::new())?;
+        }
+        if let Some(hooks) = &built.hooks {
+            graphics::draw(ctx, hooks, DrawParam::new())?;
+        }
+        Ok(())
+    }
+
+    fn build(&self, ctx: &mut Context) -> GameResult<Built> {
+        let shapes = if self.has_shapes {
+            Some(self.shapes.build(ctx)?)
+        } else {
+            None
+        };
+        let blobs = match sprites::blob_image() {
+            Some(image) if !self.blob_indices.is_empty() => Some(Mesh::from_raw(
+                ctx,
+                &self.blob_vertices,
+                &self.blob_indices,
+                Some(image),
+            )?),
+            _ => None,
+        };
+        let hooks = match sprites::hook_image() {
+            Some(image) if !self.hooks.is_empty() => {
+                let mut batch = SpriteBatch::new(image);
+                for &param in &self.hooks {
+                    batch.add(param);
+                }
+                Some(batch)
+            }
+            _ => None,
+        };
+        Ok(Built {
+            shapes,
+            blobs,
+            hooks,
+        })
+    }
+
+    fn changed(&mut self) {
+        self.built = None;
+    }
+
+    fn shape_added(&mut self) {
+        self.has_shapes = true;
+        self.changed();
+    }
+}
+
+/// A color as the mesh builder stores it in vertices, which is in linear
+/// light rather than sRGB.
+fn linear(color: Color) -> [f32; 4] {
+    let f = |c: f32| {
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    [f(color.r), f(color.g), f(color.b), color.a]
+}
@@ -0,0 +1,84 @@
+use nalgebra::Point2;
+
+pub use blobs_core::bot::{Bot, Input};
+
+use crate::replay::{Replay, TICKS_PER_FRAME};
+use crate::tuning;
+use crate::{Blob, DT};
+
+/// Plays a blob the way a player played in a replay, for practice against
+/// someone who moves like a human. Replays only hold where things were, so
+/// the player's hook shots and releases are worked out from them. Shots go
+/// the same way as recorded, wherever the bot is, so the run adapts to the
+/// bot's own spawn. The run starts over when it ends or the bot dies.
+pub struct Mimic {
+    // Inputs by the tick they happen on.
+    inputs: Vec<(u32, Input)>,
+    length: u32,
+    tick: u32,
+    next: usize,
+}
+
+impl Mimic {
+    /// Follow the blob with the given id through the replay. None if that
+    /// blob never shoots its hook in it.
+    pub fn new(replay: &Replay, id: usize) -> Option<Mimic> {
+        // A recorded hook this close to its blob was shot since the last
+        // frame, even a double speed one.
+        let fresh_hook_distance =
+            2.0 * tuning::current().hook_traveling_speed * DT * TICKS_PER_FRAME as f32 + 1.0;
+        let mut inputs = Vec::new();
+        let mut last_hook: Option<Point2<f32>> = None;
+        for (i, frame) in replay.frames.iter().enumerate() {
+            let tick = i as u32 * TICKS_PER_FRAME;
+            let blob = frame.iter().find(|blob| blob.id == id);
+            let hook = blob.and_then(|blob| blob.hook);
+            match (blob, hook) {
+                (Some(blob), Some(hook)) => {
+                    let fresh = (hook - blob.center).norm() < fresh_hook_distance;
+                    let moved =
+                        last_hook.is_none_or(|last| (hook - last).norm() > fresh_hook_distance);
+                    let direction = hook - blob.center;
+                    if (last_hook.is_none() || fresh && moved) && direction.norm() > 0.0 {
+                        inputs.push((tick, Input::Hook(direction.normalize())));
+                    }
+                }
+                _ if last_hook.is_some() => inputs.push((tick, Input::Release)),
+                _ => {}
+            }
+            last_hook = hook;
+        }
+        if !inputs
+            .iter()
+            .any(|(_, input)| matches!(input, Input::Hook(_)))
+        {
+            return None;
+        }
+        Some(Mimic {
+            inputs,
+            length: replay.frames.len() as u32 * TICKS_PER_FRAME,
+            tick: 0,
+            next: 0,
+        })
+    }
+
+    pub fn restart(&mut self) {
+        self.tick = 0;
+        self.next = 0;
+    }
+
+    /// Give the blob this tick's inputs, once per tick.
+    pub fn update(&mut self, blob: &mut Blob) {
+        while let Some(&(tick, input)) = self.inputs.get(self.next) {
+            if tick > self.tick {
+                break;
+            }
+            input.apply(blob);
+            self.next += 1;
+        }
+        self.tick += 1;
+        if self.tick >= self.length {
+            self.restart();
+        }
+    }
+}
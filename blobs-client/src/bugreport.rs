@@ -0,0 +1,84 @@
+use ggez::filesystem;
+use ggez::graphics;
+use ggez::{Context, GameError, GameResult};
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use zip::write::{FileOptions, ZipWriter};
+
+use crate::replay::Replay;
+
+// Folder in the user data directory where bug reports are saved.
+const REPORT_DIR: &str = "bugreports";
+// Number of log lines kept for bug reports.
+const LOG_LINES: usize = 500;
+
+static RECENT_LOG: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Keep a log line in memory so the last few can be put in bug reports.
+pub fn remember(line: String) {
+    if let Ok(mut lines) = RECENT_LOG.lock() {
+        if lines.len() == LOG_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+}
+
+fn recent_log() -> String {
+    match RECENT_LOG.lock() {
+        Ok(lines) => lines.iter().map(|line| format!("{}\n", line)).collect(),
+        Err(_) => String::new(),
+    }
+}
+
+fn system_info(ctx: &Context) -> String {
+    format!(
+        "Version: {}\nOS: {}\nArch: {}\nRenderer: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        graphics::renderer_info(ctx).unwrap_or_else(|e| e.to_string())
+    )
+}
+
+/// Bundle a snapshot of the game state, recent logs, a replay of the last few
+/// seconds and system info into a zip in the user data directory.
+pub fn write(ctx: &Context, snapshot: &str, replay: &Replay) -> GameResult<PathBuf> {
+    let dir = filesystem::user_data_dir(ctx).join(REPORT_DIR);
+    fs::create_dir_all(&dir)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = dir.join(format!("bugreport-{}.zip", timestamp));
+
+    let files = [
+        ("snapshot.txt", snapshot.to_string()),
+        ("log.txt", recent_log()),
+        ("replay.txt", replay.to_share_code()?),
+        ("system.txt", system_info(ctx)),
+    ];
+    let zip_error = |e: zip::result::ZipError| GameError::FilesystemError(e.to_string());
+    let mut zip = ZipWriter::new(File::create(&path)?);
+    for (name, contents) in files.iter() {
+        zip.start_file(*name, FileOptions::default())
+            .map_err(zip_error)?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    zip.finish().map_err(zip_error)?;
+    Ok(path)
+}
+
+/// Write a bug report and describe how it went, for showing to the player.
+pub fn report(ctx: &Context, snapshot: &str, replay: &Replay) -> String {
+    let message = match write(ctx, snapshot, replay) {
+        Ok(path) => format!("Bug report saved to {}", path.display()),
+        Err(e) => format!("Couldn't save bug report: {}", e),
+    };
+    log::info!("{}", message);
+    message
+}
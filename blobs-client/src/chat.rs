@@ -0,0 +1,253 @@
+use ggez::filesystem;
+use ggez::graphics;
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Read;
+
+use crate::team::Team;
+use crate::text::{self, Effect, Style};
+use crate::{SCREEN_SIZE, TICK_TIME};
+
+// One word per line, lines starting with # are comments.
+const FILTER_PATH: &str = "/chat_filter.txt";
+const MAX_LINES: usize = 8;
+// Seconds each line is shown.
+const LINE_TIME: f32 = 10.0;
+// At most this many messages per window of seconds.
+const RATE_LIMIT: usize = 5;
+const RATE_WINDOW: f32 = 10.0;
+
+/// Who gets to read a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Channel {
+    All,
+    Team(Team),
+    Whisper(usize),
+}
+
+/// Masks filtered words in chat messages.
+pub struct ChatFilter {
+    words: HashSet<String>,
+}
+
+impl ChatFilter {
+    /// Load the word list from the resource directory. Without a word list
+    /// nothing is filtered.
+    pub fn load(ctx: &mut Context) -> ChatFilter {
+        let mut source = String::new();
+        if let Err(e) = filesystem::open(ctx, FILTER_PATH)
+            .and_then(|mut file| Ok(file.read_to_string(&mut source)?))
+        {
+            log::info!("No chat filter loaded: {}", e);
+        }
+        let words = source
+            .lines()
+            .map(|line| line.trim().to_lowercase())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        ChatFilter { words }
+    }
+
+    /// Replace every filtered word with asterisks, ignoring case.
+    pub fn apply(&self, message: &str) -> String {
+        let mut filtered = String::with_capacity(message.len());
+        let mut word = String::new();
+        for c in message.chars().chain(std::iter::once(' ')) {
+            if c.is_alphanumeric() {
+                word.push(c);
+                continue;
+            }
+            if self.words.contains(&word.to_lowercase()) {
+                filtered.extend(word.chars().map(|_| '*'));
+            } else {
+                filtered.push_str(&word);
+            }
+            word.clear();
+            filtered.push(c);
+        }
+        filtered.pop();
+        filtered
+    }
+}
+
+/// Limits how often a blob may send messages.
+struct RateLimiter {
+    clock: f32,
+    sent: HashMap<usize, VecDeque<f32>>,
+}
+
+impl RateLimiter {
+    fn new() -> RateLimiter {
+        RateLimiter {
+            clock: 0.0,
+            sent: HashMap::new(),
+        }
+    }
+
+    fn update(&mut self) {
+        self.clock += TICK_TIME;
+    }
+
+    /// Count a message from the sender. Returns false if the sender has sent
+    /// too many messages lately.
+    fn allow(&mut self, sender: usize) -> bool {
+        let clock = self.clock;
+        let sent = self.sent.entry(sender).or_default();
+        while sent.front().is_some_and(|&time| clock - time > RATE_WINDOW) {
+            sent.pop_front();
+        }
+        if sent.len() >= RATE_LIMIT {
+            return false;
+        }
+        sent.push_back(clock);
+        true
+    }
+}
+
+/// Chat messages and kick votes of a match. Messages are filtered and rate
+/// limited before they are shown.
+pub struct Chat {
+    filter: ChatFilter,
+    limiter: RateLimiter,
+    lines: VecDeque<(String, f32)>,
+    kick_votes: HashMap<usize, HashSet<usize>>,
+}
+
+impl Chat {
+    pub fn new(ctx: &mut Context) -> Chat {
+        Chat {
+            filter: ChatFilter::load(ctx),
+            limiter: RateLimiter::new(),
+            lines: VecDeque::with_capacity(MAX_LINES),
+            kick_votes: HashMap::new(),
+        }
+    }
+
+    /// Call once per tick.
+    pub fn update(&mut self) {
+        self.limiter.update();
+        for line in self.lines.iter_mut() {
+            line.1 -= TICK_TIME;
+        }
+        while self.lines.front().is_some_and(|line| line.1 <= 0.0) {
+            self.lines.pop_front();
+        }
+    }
+
+    /// Send a message from a blob. Emotes are shown as actions, like
+    /// "Blob 0 waves".
+    pub fn send(
+        &mut self,
+        sender: usize,
+        channel: Channel,
+        message: &str,
+        emote: bool,
+    ) -> Result<(), String> {
+        let message = message.trim();
+        if message.is_empty() {
+            return Err("Nothing to say".to_string());
+        }
+        if !self.limiter.allow(sender) {
+            return Err("You are sending messages too fast".to_string());
+        }
+        let message = self.filter.apply(message);
+        let prefix = match channel {
+            Channel::All => String::new(),
+            Channel::Team(team) => format!("[{:?} team] ", team),
+            Channel::Whisper(to) => format!("[to Blob {}] ", to),
+        };
+        let line = if emote {
+            format!("{}* Blob {} {}", prefix, sender, message)
+        } else {
+            format!("{}Blob {}: {}", prefix, sender, message)
+        };
+        self.show(line);
+        Ok(())
+    }
+
+    /// Count a vote to kick the target. Returns true once more than half of
+    /// the players have voted for it.
+    pub fn vote_kick(&mut self, voter: usize, target: usize, player_count: usize) -> bool {
+        let votes = self.kick_votes.entry(target).or_default();
+        votes.insert(voter);
+        let needed = player_count / 2 + 1;
+        let passed = votes.len() >= needed;
+        let line = if passed {
+            self.kick_votes.remove(&target);
+            format!("Blob {} was kicked", target)
+        } else {
+            format!(
+                "Blob {} voted to kick Blob {} ({}/{})",
+                voter,
+                target,
+                votes.len(),
+                needed
+            )
+        };
+        self.show(line);
+        passed
+    }
+
+    fn show(&mut self, line: String) {
+        if self.lines.len() == MAX_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back((line, LINE_TIME));
+    }
+
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let style = Style::default().effect(Effect::Outline(graphics::WHITE));
+        let mut y = SCREEN_SIZE.1 - 120.0;
+        for (line, _time_left) in self.lines.iter().rev() {
+            let (_, height) = text::size(ctx, line, style);
+            y -= height + 4.0;
+            text::draw(ctx, line, Point2::new(10.0, y), style)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_masks_whole_words() {
+        let filter = ChatFilter {
+            words: ["heck", "darn"]
+                .iter()
+                .map(|word| word.to_string())
+                .collect(),
+        };
+        assert_eq!(filter.apply("what the heck"), "what the ****");
+        assert_eq!(filter.apply("HECK, Darn!"), "****, ****!");
+        // Only whole words.
+        assert_eq!(filter.apply("darned hecks"), "darned hecks");
+        assert_eq!(filter.apply("  heck  "), "  ****  ");
+        assert_eq!(filter.apply(""), "");
+        let nothing = ChatFilter {
+            words: HashSet::new(),
+        };
+        assert_eq!(nothing.apply("heck"), "heck");
+    }
+
+    #[test]
+    fn rate_limit_frees_up_after_the_window() {
+        let mut limiter = RateLimiter::new();
+        for _ in 0..RATE_LIMIT {
+            assert!(limiter.allow(1));
+        }
+        assert!(!limiter.allow(1));
+        // Others have their own limit.
+        assert!(limiter.allow(2));
+        // Messages count for the whole window, and not a tick longer.
+        limiter.clock = RATE_WINDOW;
+        assert!(!limiter.allow(1));
+        limiter.clock = RATE_WINDOW + TICK_TIME;
+        for _ in 0..RATE_LIMIT {
+            assert!(limiter.allow(1));
+        }
+        assert!(!limiter.allow(1));
+    }
+}
@@ -0,0 +1,121 @@
+use clap::{ArgGroup, Parser};
+
+use crate::settings::{MAX_WINDOW_SIZE, MIN_WINDOW_SIZE};
+
+/// Launch options, so matches and test scenarios can be started the same
+/// way every time from scripts. Window options only apply to this run, they
+/// aren't saved to the settings.
+#[derive(Debug, Parser)]
+#[command(
+    name = "blobs",
+    version,
+    about = "Swing around on a rope and knock other blobs out"
+)]
+#[command(group(ArgGroup::new("level_given").args(["level", "level_path"])))]
+pub struct Args {
+    /// Start a match on this level right away, e.g. /levels/ctf.ron.
+    #[arg(long, conflicts_with = "screensaver")]
+    pub level: Option<String>,
+    /// Same as --level, for older scripts.
+    #[arg(value_name = "LEVEL", conflicts_with_all = ["level", "screensaver"])]
+    pub level_path: Option<String>,
+    /// Start fullscreen.
+    #[arg(long)]
+    pub fullscreen: bool,
+    /// Size of the window, e.g. 1280x720. One number makes a square window.
+    #[arg(long, value_name = "WIDTHxHEIGHT", value_parser = window_size)]
+    pub window_size: Option<(f32, f32)>,
+    /// Add this many bots to the match started with --level.
+    #[arg(long, value_name = "N", default_value_t = 0, requires = "level_given")]
+    pub bots: usize,
+    /// Seed for the randomness in the match started with --level, to play
+    /// it out the same way again. Matches log the seed they were given.
+    #[arg(long, value_name = "N", requires = "level_given")]
+    pub seed: Option<u64>,
+    /// Join a server at this address. Not supported yet.
+    #[arg(long, value_name = "ADDR")]
+    pub connect: Option<String>,
+    /// Host a match for others to join. Not supported yet.
+    #[arg(long)]
+    pub server: bool,
+    /// Name to play under. Not supported yet.
+    #[arg(long)]
+    pub name: Option<String>,
+    /// Show the screensaver.
+    #[arg(long)]
+    pub screensaver: bool,
+    /// Turn on developer tools: F4 in a match opens a menu to spawn bots,
+    /// pickups and projectiles at the cursor, and cheat cvars can be set.
+    #[arg(long)]
+    pub dev: bool,
+    /// Log levels by module, e.g. "info,blobs_core::physics=trace,blobs::net=debug".
+    /// Overrides the BLOBS_LOG environment variable.
+    #[arg(long, value_name = "LEVELS")]
+    pub log: Option<String>,
+}
+
+impl Args {
+    /// The level to start a match on, if any.
+    pub fn level(&self) -> Option<&str> {
+        self.level.as_deref().or(self.level_path.as_deref())
+    }
+
+    /// Describe the options that ask for something the game can't do yet.
+    pub fn unsupported(&self) -> Option<String> {
+        let mut options = Vec::new();
+        if self.connect.is_some() {
+            options.push("--connect");
+        }
+        if self.server {
+            options.push("--server");
+        }
+        if self.name.is_some() {
+            options.push("--name");
+        }
+        if options.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "{} can't be used yet, the game has no network play or player names",
+            options.join(", ")
+        ))
+    }
+}
+
+fn window_size(arg: &str) -> Result<(f32, f32), String> {
+    let side = |side: &str| {
+        let size: f32 = side
+            .parse()
+            .map_err(|_| format!("{} isn't a number", side))?;
+        if (MIN_WINDOW_SIZE..=MAX_WINDOW_SIZE).contains(&size) {
+            Ok(size)
+        } else {
+            Err(format!(
+                "each side must be from {} to {} pixels",
+                MIN_WINDOW_SIZE, MAX_WINDOW_SIZE
+            ))
+        }
+    };
+    match arg.split_once('x') {
+        Some((width, height)) => Ok((side(width)?, side(height)?)),
+        None => side(arg).map(|size| (size, size)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_sizes() {
+        assert_eq!(window_size("1280x720"), Ok((1280.0, 720.0)));
+        assert_eq!(window_size("800"), Ok((800.0, 800.0)));
+        assert!(window_size("1280x").is_err());
+        assert!(window_size("x720").is_err());
+        assert!(window_size("1280x720x2").is_err());
+        assert!(window_size("1280x10").is_err());
+        assert!(window_size("100000x720").is_err());
+        assert!(window_size("NaNx720").is_err());
+        assert!(window_size("abc").is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use crate::stress;
+
+const DEFAULT_STRESS_BLOBS: usize = 50;
+const DEFAULT_STRESS_SECONDS: f32 = 60.0;
+
+/// Commands that can be typed into the console. Text that doesn't start
+/// with a slash is said in chat.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// Save a bug report to attach to an issue.
+    BugReport,
+    /// Read the balance data file again.
+    ReloadBalance,
+    /// Upload the current map to the map repository.
+    UploadMap,
+    /// Download a map from the map repository by its hash and play it.
+    DownloadMap(String),
+    /// Chat to everyone.
+    Say(String),
+    /// Describe an action in chat, like "/me waves".
+    Me(String),
+    /// Chat to the own team only.
+    Team(String),
+    /// Chat to a single player.
+    Whisper { to: String, message: String },
+    /// Vote to kick a player from the match.
+    VoteKick(String),
+    /// Add a bot that plays like the local player did in a replay, given as
+    /// a share code or saved file name, or in the last few seconds.
+    PracticeBot(Option<String>),
+    /// Throw dummy blobs around the map for a while and report where they
+    /// got stuck in or went through walls.
+    StressMap { blobs: usize, seconds: f32 },
+    /// Kill the own blob, to get out of a corner, at a penalty set by the
+    /// game mode.
+    Kill,
+    /// Set a cvar.
+    Set { name: String, value: String },
+    /// Show a cvar's value and what it is for.
+    Get(String),
+    /// List the cvars with the text in their name, or all of them.
+    Cvars(Option<String>),
+}
+
+impl Command {
+    pub fn parse(input: &str) -> Result<Command, String> {
+        let input = input.trim();
+        if !input.starts_with('/') {
+            return Ok(Command::Say(input.to_string()));
+        }
+        let (name, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        let rest = rest.trim();
+        match name {
+            "/me" => return Ok(Command::Me(rest.to_string())),
+            "/team" => return Ok(Command::Team(rest.to_string())),
+            "/whisper" => {
+                return match rest.split_once(char::is_whitespace) {
+                    Some((to, message)) => Ok(Command::Whisper {
+                        to: to.to_string(),
+                        message: message.trim().to_string(),
+                    }),
+                    None => Err("Usage: /whisper <name> <message>".to_string()),
+                }
+            }
+            "/set" => {
+                return match rest.split_once(char::is_whitespace) {
+                    Some((name, value)) => Ok(Command::Set {
+                        name: name.to_string(),
+                        value: value.trim().to_string(),
+                    }),
+                    None => Err("Usage: /set <name> <value>".to_string()),
+                }
+            }
+            "/stress" => {
+                let mut words = rest.split_whitespace();
+                let blobs = words.next().map_or(Ok(DEFAULT_STRESS_BLOBS), str::parse);
+                let seconds = words.next().map_or(Ok(DEFAULT_STRESS_SECONDS), str::parse);
+                return match (blobs, seconds, words.next()) {
+                    (Ok(blobs), Ok(seconds), None) if blobs > 0 && seconds > 0.0 => {
+                        Ok(Command::StressMap {
+                            blobs: blobs.min(stress::MAX_BLOBS),
+                            seconds: seconds.min(stress::MAX_SECONDS),
+                        })
+                    }
+                    _ => Err("Usage: /stress [blobs] [seconds]".to_string()),
+                };
+            }
+            _ => {}
+        }
+        let mut words = input.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some("/bugreport"), None) => Ok(Command::BugReport),
+            (Some("/reloadbalance"), None) => Ok(Command::ReloadBalance),
+            (Some("/kill"), None) => Ok(Command::Kill),
+            (Some("/upload"), None) => Ok(Command::UploadMap),
+            (Some("/download"), Some(hash)) => Ok(Command::DownloadMap(hash.to_string())),
+            (Some("/download"), None) => Err("Usage: /download <map hash>".to_string()),
+            (Some("/votekick"), Some(name)) => Ok(Command::VoteKick(name.to_string())),
+            (Some("/votekick"), None) => Err("Usage: /votekick <name>".to_string()),
+            (Some("/practicebot"), replay) => Ok(Command::PracticeBot(replay.map(str::to_string))),
+            (Some("/get"), Some(name)) => Ok(Command::Get(name.to_string())),
+            (Some("/get"), None) => Err("Usage: /get <name>".to_string()),
+            (Some("/cvars"), text) => Ok(Command::Cvars(text.map(str::to_string))),
+            (None, _) => Err("No command given".to_string()),
+            _ => Err(format!("Unknown command: {}", input)),
+        }
+    }
+}
+
+/// Find the blob a player name refers to. Players are named after their blob,
+/// so "Blob10", "blob10" and "10" all name blob 10.
+pub fn find_player(name: &str, ids: impl IntoIterator<Item = usize>) -> Option<usize> {
+    let lower = name.to_lowercase();
+    let id: usize = lower.strip_prefix("blob").unwrap_or(&lower).parse().ok()?;
+    ids.into_iter().find(|&other| other == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            Command::parse("  hello "),
+            Ok(Command::Say("hello".to_string()))
+        );
+        assert_eq!(Command::parse("/kill"), Ok(Command::Kill));
+        assert_eq!(
+            Command::parse("/whisper Blob3  see you"),
+            Ok(Command::Whisper {
+                to: "Blob3".to_string(),
+                message: "see you".to_string(),
+            })
+        );
+        assert_eq!(
+            Command::parse("/set fps_cap 60"),
+            Ok(Command::Set {
+                name: "fps_cap".to_string(),
+                value: "60".to_string(),
+            })
+        );
+        assert_eq!(
+            Command::parse("/practicebot"),
+            Ok(Command::PracticeBot(None))
+        );
+        assert_eq!(Command::parse("/cvars"), Ok(Command::Cvars(None)));
+        assert_eq!(
+            Command::parse("/stress"),
+            Ok(Command::StressMap {
+                blobs: DEFAULT_STRESS_BLOBS,
+                seconds: DEFAULT_STRESS_SECONDS,
+            })
+        );
+        assert_eq!(
+            Command::parse("/stress 100000 1e9"),
+            Ok(Command::StressMap {
+                blobs: stress::MAX_BLOBS,
+                seconds: stress::MAX_SECONDS,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_commands_and_bad_arguments() {
+        assert_eq!(
+            Command::parse("/dance"),
+            Err("Unknown command: /dance".to_string())
+        );
+        // Commands without arguments don't take any.
+        assert!(Command::parse("/kill now").is_err());
+        assert!(Command::parse("/").is_err());
+        assert!(Command::parse("/download").is_err());
+        assert!(Command::parse("/votekick").is_err());
+        assert!(Command::parse("/get").is_err());
+        assert!(Command::parse("/whisper Blob3").is_err());
+        assert!(Command::parse("/set fps_cap").is_err());
+        assert!(Command::parse("/stress 0").is_err());
+        assert!(Command::parse("/stress ten").is_err());
+        assert!(Command::parse("/stress 10 -5").is_err());
+        assert!(Command::parse("/stress 10 NaN").is_err());
+        assert!(Command::parse("/stress 10 20 30").is_err());
+    }
+
+    #[test]
+    fn finds_players_by_name() {
+        assert_eq!(find_player("Blob10", [3, 10]), Some(10));
+        assert_eq!(find_player("blob10", [3, 10]), Some(10));
+        assert_eq!(find_player("10", [3, 10]), Some(10));
+        assert_eq!(find_player("Blob4", [3, 10]), None);
+        assert_eq!(find_player("Bob", [3, 10]), None);
+    }
+}
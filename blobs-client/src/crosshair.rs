@@ -0,0 +1,136 @@
+use ggez::graphics::{self, Color, DrawMode, MeshBuilder};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+use serde::{Deserialize, Serialize};
+
+use crate::HookState;
+
+/// Shapes to choose from on the crosshair screen.
+pub const SHAPES: [Shape; 4] = [Shape::Cross, Shape::Dot, Shape::Circle, Shape::CircleDot];
+/// Arm lengths, or radii for circles, to choose from.
+pub const SIZES: [f32; 6] = [4.0, 6.0, 8.0, 12.0, 16.0, 24.0];
+/// Gaps in the middle to choose from.
+pub const GAPS: [f32; 5] = [0.0, 2.0, 4.0, 6.0, 10.0];
+pub const THICKNESSES: [f32; 4] = [1.0, 2.0, 3.0, 4.0];
+/// Colors to choose from, with their names.
+pub const COLORS: [(&str, (u8, u8, u8)); 7] = [
+    ("White", (255, 255, 255)),
+    ("Black", (0, 0, 0)),
+    ("Red", (230, 50, 40)),
+    ("Green", (60, 220, 80)),
+    ("Yellow", (250, 220, 40)),
+    ("Cyan", (40, 220, 230)),
+    ("Magenta", (230, 60, 220)),
+];
+// How much wider the outline is than the lines it goes around.
+const OUTLINE_WIDTH: f32 = 2.0;
+const TOLERANCE: f32 = 0.2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Shape {
+    Cross,
+    Dot,
+    Circle,
+    CircleDot,
+}
+
+impl Shape {
+    pub fn name(self) -> &'static str {
+        match self {
+            Shape::Cross => "Cross",
+            Shape::Dot => "Dot",
+            Shape::Circle => "Circle",
+            Shape::CircleDot => "Circle with dot",
+        }
+    }
+}
+
+/// How the crosshair drawn at the mouse during play looks. Sizes are in
+/// logical pixels, before the UI scale.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Crosshair {
+    pub shape: Shape,
+    pub size: f32,
+    pub gap: f32,
+    pub thickness: f32,
+    pub color: (u8, u8, u8),
+    /// Draw a black border around the lines, to see them on any background.
+    pub outline: bool,
+    /// Color while the hook flies, or None to keep the usual color.
+    pub traveling_color: Option<(u8, u8, u8)>,
+    /// Color while hooked onto something.
+    pub hooked_color: Option<(u8, u8, u8)>,
+}
+
+impl Default for Crosshair {
+    fn default() -> Crosshair {
+        Crosshair {
+            shape: Shape::Cross,
+            size: 8.0,
+            gap: 4.0,
+            thickness: 2.0,
+            color: (255, 255, 255),
+            outline: true,
+            traveling_color: Some((250, 220, 40)),
+            hooked_color: Some((60, 220, 80)),
+        }
+    }
+}
+
+impl Crosshair {
+    /// The color for what the hook is doing.
+    fn color(&self, hook: &HookState) -> Color {
+        let (r, g, b) = match hook {
+            HookState::Traveling(..) => self.traveling_color.unwrap_or(self.color),
+            HookState::Attached(_) => self.hooked_color.unwrap_or(self.color),
+            HookState::None => self.color,
+        };
+        Color::from_rgb(r, g, b)
+    }
+
+    /// Draw the crosshair centered on the point, colored for the hook state.
+    pub fn draw(&self, ctx: &mut Context, center: Point2<f32>, hook: &HookState) -> GameResult<()> {
+        let mut builder = MeshBuilder::new();
+        if self.outline {
+            self.add(&mut builder, center, OUTLINE_WIDTH, graphics::BLACK)?;
+        }
+        self.add(&mut builder, center, 0.0, self.color(hook))?;
+        let mesh = builder.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::new())
+    }
+
+    // Add the shape with its lines widened by the given amount on each side.
+    fn add(
+        &self,
+        builder: &mut MeshBuilder,
+        center: Point2<f32>,
+        widen: f32,
+        color: Color,
+    ) -> GameResult<()> {
+        let width = self.thickness + 2.0 * widen;
+        let dot = |builder: &mut MeshBuilder| {
+            builder.circle(DrawMode::fill(), center, width, TOLERANCE, color);
+        };
+        match self.shape {
+            Shape::Cross => {
+                let directions = [(1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)];
+                for (x, y) in directions.iter() {
+                    let direction = Vector2::new(*x, *y);
+                    let from = center + (self.gap - widen) * direction;
+                    let to = center + (self.gap + self.size + widen) * direction;
+                    builder.line(&[from, to], width, color)?;
+                }
+            }
+            Shape::Dot => dot(builder),
+            Shape::Circle | Shape::CircleDot => {
+                let radius = self.gap + self.size;
+                builder.circle(DrawMode::stroke(width), center, radius, TOLERANCE, color);
+                if self.shape == Shape::CircleDot {
+                    dot(builder);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,484 @@
+use ggez::{filesystem, Context};
+use std::fs;
+
+use crate::balance;
+use crate::debug;
+use crate::settings;
+use crate::tuning;
+
+// File in the user data directory with cvars to set at startup, one
+// "name value" per line.
+const CONFIG_FILE: &str = "autoexec.cfg";
+
+/// Changes how the game plays, so it can only be set with developer tools
+/// turned on.
+const CHEAT: u8 = 1 << 0;
+/// Part of the rules of a match, which everyone in it has to agree on. A
+/// server would send these to its clients; without network play they are
+/// listed in bug reports, to tell which rules a report was played with.
+const REPLICATED: u8 = 1 << 1;
+/// Kept between runs, in the settings file.
+const ARCHIVED: u8 = 1 << 2;
+
+enum Kind {
+    Float {
+        get: fn() -> f32,
+        set: fn(f32),
+        min: f32,
+        max: f32,
+    },
+    Int {
+        get: fn() -> u32,
+        set: fn(u32),
+        min: u32,
+        max: u32,
+    },
+    Bool {
+        get: fn() -> bool,
+        set: fn(bool),
+    },
+}
+
+/// A named value that can be read and set from the console and the config
+/// file. Cvars don't hold values themselves, they read and write the
+/// tuning, balance and settings, named after where the value is in those.
+pub struct Cvar {
+    pub name: &'static str,
+    pub description: &'static str,
+    flags: u8,
+    kind: Kind,
+}
+
+impl Cvar {
+    pub fn value(&self) -> String {
+        match self.kind {
+            Kind::Float { get, .. } => get().to_string(),
+            Kind::Int { get, .. } => get().to_string(),
+            Kind::Bool { get, .. } => u8::from(get()).to_string(),
+        }
+    }
+
+    fn set(&self, value: &str) -> Result<(), String> {
+        match self.kind {
+            Kind::Float { set, min, max, .. } => match value.parse::<f32>() {
+                Ok(value) if (min..=max).contains(&value) => set(value),
+                _ => {
+                    return Err(format!(
+                        "{} must be a number from {} to {}",
+                        self.name, min, max
+                    ))
+                }
+            },
+            Kind::Int { set, min, max, .. } => match value.parse::<u32>() {
+                Ok(value) if (min..=max).contains(&value) => set(value),
+                _ => {
+                    return Err(format!(
+                        "{} must be a whole number from {} to {}",
+                        self.name, min, max
+                    ))
+                }
+            },
+            Kind::Bool { set, .. } => match value {
+                "1" | "true" | "on" => set(true),
+                "0" | "false" | "off" => set(false),
+                _ => return Err(format!("{} must be 1 or 0", self.name)),
+            },
+        }
+        Ok(())
+    }
+
+    /// Name, value, range and flags, and what the cvar is for.
+    pub fn describe(&self) -> String {
+        let range = match self.kind {
+            Kind::Float { min, max, .. } => format!("{} to {}", min, max),
+            Kind::Int { min, max, .. } => format!("{} to {}", min, max),
+            Kind::Bool { .. } => "0 or 1".to_string(),
+        };
+        let flags: Vec<&str> = [
+            (CHEAT, "cheat"),
+            (REPLICATED, "replicated"),
+            (ARCHIVED, "archived"),
+        ]
+        .iter()
+        .filter(|&&(flag, _)| self.flags & flag != 0)
+        .map(|&(_, name)| name)
+        .collect();
+        format!(
+            "{} = {} ({}) [{}] {}",
+            self.name,
+            self.value(),
+            range,
+            flags.join(", "),
+            self.description
+        )
+    }
+}
+
+macro_rules! tuning {
+    ($field:ident, $min:expr, $max:expr, $description:literal) => {
+        Cvar {
+            name: concat!("tuning.", stringify!($field)),
+            description: $description,
+            flags: CHEAT | REPLICATED,
+            kind: Kind::Float {
+                get: || tuning::current().$field,
+                set: |value| {
+                    let mut tuning = tuning::current();
+                    tuning.$field = value;
+                    tuning::set_current(tuning);
+                },
+                min: $min,
+                max: $max,
+            },
+        }
+    };
+}
+
+macro_rules! balance {
+    ($group:ident . $field:ident, $min:expr, $max:expr, $description:literal) => {
+        balance!(Float, $group.$field, $min, $max, $description)
+    };
+    ($kind:ident, $group:ident . $field:ident, $min:expr, $max:expr, $description:literal) => {
+        Cvar {
+            name: concat!("balance.", stringify!($group), ".", stringify!($field)),
+            description: $description,
+            flags: CHEAT | REPLICATED,
+            kind: Kind::$kind {
+                get: || balance::current().$group.$field,
+                set: |value| {
+                    let mut balance = balance::current();
+                    balance.$group.$field = value;
+                    balance::set_current(balance);
+                },
+                min: $min,
+                max: $max,
+            },
+        }
+    };
+}
+
+macro_rules! setting {
+    (Bool, $field:ident, $description:literal) => {
+        Cvar {
+            name: concat!("settings.", stringify!($field)),
+            description: $description,
+            flags: ARCHIVED,
+            kind: Kind::Bool {
+                get: || settings::current().$field,
+                set: |value| {
+                    let mut settings = settings::current();
+                    settings.$field = value;
+                    settings::save(settings);
+                },
+            },
+        }
+    };
+    (Float, $field:ident, $min:expr, $max:expr, $description:literal) => {
+        Cvar {
+            name: concat!("settings.", stringify!($field)),
+            description: $description,
+            flags: ARCHIVED,
+            kind: Kind::Float {
+                get: || settings::current().$field,
+                set: |value| {
+                    let mut settings = settings::current();
+                    settings.$field = value;
+                    settings::save(settings);
+                },
+                min: $min,
+                max: $max,
+            },
+        }
+    };
+}
+
+static CVARS: &[Cvar] = &[
+    tuning!(
+        spring_const,
+        0.1,
+        1000.0,
+        "Pull of the rope, divided by blob mass"
+    ),
+    tuning!(
+        spring_eq_len,
+        0.0,
+        1000.0,
+        "Length of the rope when it pulls with no force"
+    ),
+    tuning!(
+        damping_const,
+        0.0,
+        1.0,
+        "How much blobs are slowed down by the air"
+    ),
+    tuning!(gravity, 0.0, 100.0, "Downward acceleration of blobs"),
+    tuning!(hook_traveling_speed, 1.0, 1000.0, "Speed of a shot hook"),
+    tuning!(blob_radius, 5.0, 200.0, "Size of the blobs"),
+    balance!(weapon.speed, 1.0, 5000.0, "Speed of projectiles"),
+    balance!(weapon.radius, 0.5, 100.0, "Size of projectiles"),
+    balance!(
+        weapon.lifetime,
+        0.1,
+        60.0,
+        "Seconds until a projectile that hit nothing disappears"
+    ),
+    balance!(
+        weapon.knockback,
+        0.0,
+        1000.0,
+        "Velocity change of a blob hit by a projectile"
+    ),
+    balance!(weapon.damage, 0.0, 1000.0, "Damage of a projectile hit"),
+    balance!(
+        blob.impact_damage_speed,
+        1.0,
+        5000.0,
+        "Wall hits faster than this hurt the blob"
+    ),
+    balance!(
+        blob.impact_damage_per_speed,
+        0.0,
+        100.0,
+        "Damage of a wall hit per speed above the limit"
+    ),
+    balance!(
+        blob.hostile_base_damage_per_second,
+        0.0,
+        1000.0,
+        "Damage per second inside the other team's base"
+    ),
+    balance!(
+        blob.respawn_delay,
+        0.0,
+        60.0,
+        "Seconds from death to respawn"
+    ),
+    balance!(
+        blob.spawn_invulnerability,
+        0.0,
+        60.0,
+        "Seconds a respawned blob can't be hurt"
+    ),
+    balance!(
+        blob.perch_stamina,
+        0.1,
+        60.0,
+        "Seconds a blob can hang on to a wall"
+    ),
+    balance!(
+        blob.stamina_regen,
+        0.0,
+        60.0,
+        "Seconds of stamina regained per second off the walls"
+    ),
+    balance!(
+        blob.perch_kick_speed,
+        0.0,
+        1000.0,
+        "Speed a blob kicks off a wall with when it lets go"
+    ),
+    balance!(
+        pickups.effect_time,
+        0.1,
+        600.0,
+        "Seconds a picked up effect lasts"
+    ),
+    balance!(
+        pickups.health_amount,
+        0.0,
+        1000.0,
+        "Health given by a health pickup"
+    ),
+    balance!(
+        pickups.speed_boost_factor,
+        0.1,
+        10.0,
+        "Rope pull multiplier while the speed boost is active"
+    ),
+    balance!(
+        escort.push_radius,
+        1.0,
+        1000.0,
+        "Blobs within this distance of the payload push or contest it"
+    ),
+    balance!(
+        escort.push_speed,
+        0.1,
+        1000.0,
+        "Fastest the attackers push the payload"
+    ),
+    balance!(
+        escort.push_acc,
+        0.0,
+        100.0,
+        "How fast the attackers get the payload going"
+    ),
+    balance!(
+        escort.payload_friction,
+        0.0,
+        10.0,
+        "How fast the payload slows down on its own"
+    ),
+    balance!(
+        escort.tow_mass_ratio,
+        0.0,
+        10.0,
+        "Pull from blobs hooked on the payload"
+    ),
+    balance!(
+        escort.overtime_grace,
+        0.0,
+        60.0,
+        "Seconds of overtime after the attackers leave the payload"
+    ),
+    balance!(
+        duel.round_start_invulnerability,
+        0.0,
+        60.0,
+        "Seconds blobs can't be hurt at the start of a duel round"
+    ),
+    balance!(
+        hook_duel.slam_speed,
+        0.1,
+        1000.0,
+        "Slowest a pulled blob can enter a zone and count as slammed"
+    ),
+    balance!(
+        hook_duel.credit_time,
+        0.0,
+        60.0,
+        "Seconds a slam still counts after the hook lets go"
+    ),
+    balance!(
+        hook_duel.tow_mass_ratio,
+        0.0,
+        10.0,
+        "Pull on a hooked blob, relative to the pull on the hooker"
+    ),
+    balance!(
+        hide_and_seek.seeker_hook_range,
+        1.0,
+        5000.0,
+        "Furthest a seeker's hook flies"
+    ),
+    balance!(
+        hide_and_seek.latch_speed,
+        0.0,
+        1000.0,
+        "Hiders slower than this stick to walls they touch"
+    ),
+    balance!(
+        hide_and_seek.camouflage_time,
+        0.1,
+        60.0,
+        "Seconds for a latched hider to fade into the wall"
+    ),
+    balance!(
+        suicide.respawn_penalty,
+        0.0,
+        60.0,
+        "Seconds added to the respawn delay after /kill"
+    ),
+    balance!(
+        Int,
+        suicide.frag_penalty,
+        0,
+        100,
+        "Frags taken off for /kill in deathmatch"
+    ),
+    balance!(
+        afk.idle_time,
+        1.0,
+        3600.0,
+        "Seconds without input before a player is moved to the spectators"
+    ),
+    balance!(
+        afk.warning_time,
+        0.0,
+        3600.0,
+        "Seconds before that to warn them"
+    ),
+    setting!(Float, ui_scale, 0.5, 3.0, "Size of the HUD and overlays"),
+    setting!(Bool, speedometer, "Show how fast the local blob goes"),
+    setting!(
+        Bool,
+        speed_arrow,
+        "Point an arrow the way the blob moves, next to the speedometer"
+    ),
+    setting!(Bool, hints, "Show tips for new players"),
+    setting!(
+        Bool,
+        update_check,
+        "Look for a newer release when the game starts"
+    ),
+    setting!(
+        Bool,
+        show_own_nameplate,
+        "Draw a nameplate above the local blob too"
+    ),
+    setting!(
+        Bool,
+        screen_shake,
+        "Shake the view on heavy hits and deaths"
+    ),
+    setting!(Float, master_volume, 0.0, 1.0, "Volume of everything"),
+    setting!(Float, effects_volume, 0.0, 1.0, "Volume of sound effects"),
+    setting!(Float, music_volume, 0.0, 1.0, "Volume of the music"),
+];
+
+pub fn find(name: &str) -> Option<&'static Cvar> {
+    CVARS.iter().find(|cvar| cvar.name == name)
+}
+
+/// Cvars with the text anywhere in their name, in the order they are listed.
+pub fn matching(text: &str) -> impl Iterator<Item = &'static Cvar> + '_ {
+    CVARS.iter().filter(move |cvar| cvar.name.contains(text))
+}
+
+/// Set a cvar from text. Returns a line to show the player either way.
+pub fn set(name: &str, value: &str) -> Result<String, String> {
+    let cvar = find(name).ok_or_else(|| format!("No cvar named {}", name))?;
+    if cvar.flags & CHEAT != 0 && !debug::dev_tools() {
+        return Err(format!(
+            "{} is a cheat, start the game with --dev to set it",
+            name
+        ));
+    }
+    cvar.set(value)?;
+    log::info!("Set {} to {}", name, cvar.value());
+    Ok(format!("{} = {}", name, cvar.value()))
+}
+
+/// The replicated cvars and their values, one per line.
+pub fn replicated() -> String {
+    CVARS
+        .iter()
+        .filter(|cvar| cvar.flags & REPLICATED != 0)
+        .map(|cvar| format!("{} {}\n", cvar.name, cvar.value()))
+        .collect()
+}
+
+/// Set the cvars in the config file in the user data directory, if there is
+/// one. Lines are "name value", and lines starting with # are comments.
+/// Call once at startup, after the tuning and balance are loaded.
+pub fn exec_config(ctx: &Context) {
+    let path = filesystem::user_data_dir(ctx).join(CONFIG_FILE);
+    let source = match fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(_) => return,
+    };
+    log::info!("Running {}", path.display());
+    for (i, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let result = match line.split_once(char::is_whitespace) {
+            Some((name, value)) => set(name, value.trim()),
+            None => Err(format!("Expected a name and a value, not {:?}", line)),
+        };
+        if let Err(e) = result {
+            log::warn!("{} line {}: {}", CONFIG_FILE, i + 1, e);
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use ggez::graphics::{self, Color};
+use ggez::input::keyboard::KeyCode;
+use ggez::timer;
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::attachment::Anchor;
+use crate::collision::wall_blob_collision;
+use crate::level::Level;
+use crate::logging;
+use crate::replay::PoolStats;
+use crate::timestep::{self, TIME_SCALES};
+use crate::tuning;
+use crate::{Blob, HookState};
+
+// Screen length of the drawn vectors per unit of velocity or acceleration.
+const VELOCITY_SCALE: f32 = 1.0;
+const ACCELERATION_SCALE: f32 = 5.0;
+const NORMAL_LENGTH: f32 = 40.0;
+
+thread_local! {
+    static DEV_TOOLS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether developer tools were turned on with --dev.
+pub fn dev_tools() -> bool {
+    DEV_TOOLS.with(|enabled| enabled.get())
+}
+
+pub fn enable_dev_tools() {
+    DEV_TOOLS.with(|enabled| enabled.set(true));
+}
+
+/// Overlay with frame and tick rates, the state of every blob and the forces
+/// acting on it, for tuning how swinging feels. While it is open, the number
+/// keys slow the simulation down or speed it up, and P freezes it so it can
+/// be stepped a tick at a time with the period key.
+pub struct DebugOverlay {
+    ticks: u32,
+    window_start: Instant,
+    tick_rate: f32,
+    frozen: bool,
+    // Ticks to run while frozen.
+    steps: u32,
+}
+
+impl DebugOverlay {
+    pub fn new() -> DebugOverlay {
+        DebugOverlay {
+            ticks: 0,
+            window_start: Instant::now(),
+            tick_rate: 0.0,
+            frozen: false,
+            steps: 0,
+        }
+    }
+
+    /// Set the time scale for a number key, freeze or step the simulation,
+    /// returning whether the key was one of those.
+    pub fn key_down(&mut self, keycode: KeyCode) -> bool {
+        let keys = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4];
+        if let Some(i) = keys.iter().position(|&key| key == keycode) {
+            timestep::set_scale(TIME_SCALES[i]);
+            return true;
+        }
+        match keycode {
+            KeyCode::P => {
+                self.frozen = !self.frozen;
+                self.steps = 0;
+            }
+            KeyCode::Period => {
+                self.frozen = true;
+                self.steps += 1;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Whether the simulation should run this tick: always unless frozen,
+    /// and once per step asked for while frozen. Call once per tick.
+    pub fn advance(&mut self) -> bool {
+        if !self.frozen {
+            return true;
+        }
+        if self.steps == 0 {
+            return false;
+        }
+        self.steps -= 1;
+        true
+    }
+
+    /// Count a simulated tick. Call once per tick.
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            self.tick_rate = self.ticks as f32 / elapsed.as_secs_f32();
+            self.ticks = 0;
+            self.window_start = Instant::now();
+        }
+    }
+
+    pub fn draw(
+        &self,
+        ctx: &mut Context,
+        blobs: &BTreeMap<usize, Blob>,
+        level: &Level,
+        recorder_pool: PoolStats,
+    ) -> GameResult<()> {
+        let mut ids: Vec<_> = blobs.keys().copied().collect();
+        ids.sort_unstable();
+        let mut lines = vec![
+            format!(
+                "FPS {:.0}   ticks/s {:.0}   time scale {}x (1-4)",
+                timer::fps(ctx),
+                self.tick_rate,
+                timestep::scale()
+            ),
+            if self.frozen {
+                format!("FROZEN at tick {}   . to step, P to run", logging::frame())
+            } else {
+                format!("Tick {}   P to freeze", logging::frame())
+            },
+            format!(
+                "Replay frames: {} allocated, {} reused",
+                recorder_pool.allocated, recorder_pool.reused
+            ),
+        ];
+        let mut vectors = graphics::MeshBuilder::new();
+        for id in ids {
+            let blob = &blobs[&id];
+            lines.push(format!(
+                "Blob {}: pos ({:.0}, {:.0}) vel ({:.1}, {:.1}) hook {}",
+                id,
+                blob.center.x,
+                blob.center.y,
+                blob.vel.x,
+                blob.vel.y,
+                hook_description(&blob.hook)
+            ));
+
+            let spring = match blob.hook {
+                HookState::Attached(attachment) => attachment.acceleration(blob.center),
+                _ => Vector2::zeros(),
+            };
+            add_vector(
+                &mut vectors,
+                blob.center,
+                ACCELERATION_SCALE * spring,
+                Color::from_rgb(220, 40, 200),
+            )?;
+            add_vector(
+                &mut vectors,
+                blob.center,
+                VELOCITY_SCALE * blob.vel,
+                Color::from_rgb(40, 120, 240),
+            )?;
+            let normals = wall_blob_collision(blob.center).into_iter().chain(
+                level
+                    .bases
+                    .iter()
+                    .map(|base| base.region)
+                    .chain(level.hazards.iter().map(|hazard| hazard.region))
+                    .filter_map(|region| {
+                        region.blob_collision(blob.center, tuning::current().blob_radius)
+                    }),
+            );
+            for (point, normal) in normals {
+                add_vector(
+                    &mut vectors,
+                    point,
+                    NORMAL_LENGTH * normal,
+                    Color::from_rgb(240, 160, 0),
+                )?;
+            }
+        }
+        if let Ok(vectors) = vectors.build(ctx) {
+            graphics::draw(ctx, &vectors, graphics::DrawParam::new())?;
+        }
+
+        let text = graphics::Text::new(lines.join("\n"));
+        let dest = Point2::new(10.0, 200.0);
+        let (width, height) = text.dimensions(ctx);
+        let background = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(
+                dest.x - 4.0,
+                dest.y - 4.0,
+                width as f32 + 8.0,
+                height as f32 + 8.0,
+            ),
+            Color::new(1.0, 1.0, 1.0, 0.7),
+        )?;
+        graphics::draw(ctx, &background, graphics::DrawParam::new())?;
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new().dest(dest).color(graphics::BLACK),
+        )
+    }
+}
+
+// The time scale only lasts while the overlay is open, so closing it goes
+// back to normal speed.
+impl Drop for DebugOverlay {
+    fn drop(&mut self) {
+        timestep::set_scale(1.0);
+    }
+}
+
+fn hook_description(hook: &HookState) -> String {
+    match *hook {
+        HookState::Attached(attachment) => {
+            let p = attachment.point;
+            let what = match attachment.anchor {
+                Anchor::Wall => "hooked".to_string(),
+                Anchor::Blob(id) => format!("towing blob {}", id),
+                Anchor::Payload => "towing the payload".to_string(),
+            };
+            format!("{} at ({:.0}, {:.0})", what, p.x, p.y)
+        }
+        HookState::Traveling(p, _) => format!("traveling at ({:.0}, {:.0})", p.x, p.y),
+        HookState::None => "none".to_string(),
+    }
+}
+
+/// Add an arrow from start along v, unless v is too short to see.
+fn add_vector(
+    builder: &mut graphics::MeshBuilder,
+    start: Point2<f32>,
+    v: Vector2<f32>,
+    color: Color,
+) -> GameResult<()> {
+    if v.norm() < 2.0 {
+        return Ok(());
+    }
+    let end = start + v;
+    let dir = v.normalize();
+    let side = Vector2::new(-dir.y, dir.x);
+    builder.line(&[start, end], 2.0, color)?;
+    builder.polygon(
+        graphics::DrawMode::fill(),
+        &[end + 6.0 * dir, end + 4.0 * side, end - 4.0 * side],
+        color,
+    )?;
+    Ok(())
+}
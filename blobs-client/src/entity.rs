@@ -0,0 +1,14 @@
+pub use blobs_core::entity::*;
+
+use crate::balance;
+use crate::batch::Batch;
+
+/// Add the entity to a batch.
+pub fn add(batch: &mut Batch, entity: &Entity) {
+    match entity {
+        Entity::Projectile(projectile) => {
+            let radius = balance::current().weapon.radius;
+            batch.disc(projectile.pos(), radius, (60, 60, 60).into());
+        }
+    }
+}
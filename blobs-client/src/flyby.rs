@@ -0,0 +1,77 @@
+use ggez::audio::SoundData;
+use ggez::{Context, GameResult};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::particles::{self, Particles};
+use crate::sound;
+use crate::{Blob, LOCAL_ID};
+
+// Blobs passing closer than this, fast enough relative to the player, are
+// heard and seen rushing past.
+const PASS_DISTANCE: f32 = 250.0;
+const MIN_SPEED: f32 = 120.0;
+// Relative speed of a pass that plays the whoosh at full volume.
+const FULL_SPEED: f32 = 300.0;
+// Speed of sound for the doppler shift, much slower than in air so that
+// swinging speeds are clearly heard.
+const SOUND_SPEED: f32 = 600.0;
+
+/// Whooshes as other blobs rush past the local player. Each pass is played
+/// when the blobs are closest, pitched up by how fast they approached, the
+/// way a passing car's sound drops as it goes by, and leaves streaks behind
+/// the passing blob.
+pub struct Flybys {
+    whoosh: SoundData,
+    // Blobs getting closer to the player as of the last tick.
+    approaching: HashSet<usize>,
+}
+
+impl Flybys {
+    pub fn new() -> Flybys {
+        Flybys {
+            whoosh: sound::whoosh(0.4, 0.5),
+            approaching: HashSet::new(),
+        }
+    }
+
+    /// Call once per tick.
+    pub fn update(
+        &mut self,
+        ctx: &mut Context,
+        blobs: &BTreeMap<usize, Blob>,
+        particles: &mut Particles,
+    ) -> GameResult<()> {
+        let local = match blobs.get(&LOCAL_ID) {
+            Some(local) => local,
+            None => return Ok(()),
+        };
+        let mut approaching = HashSet::new();
+        for (&id, blob) in blobs.iter().filter(|&(&id, _)| id != LOCAL_ID) {
+            let offset = blob.center - local.center;
+            let relative_vel = blob.vel - local.vel;
+            if offset.dot(&relative_vel) < 0.0 {
+                approaching.insert(id);
+                continue;
+            }
+            let speed = relative_vel.norm();
+            let passed = self.approaching.contains(&id)
+                && offset.norm() < PASS_DISTANCE
+                && speed >= MIN_SPEED;
+            if passed {
+                let volume = (speed / FULL_SPEED).min(1.0);
+                let pitch = SOUND_SPEED / (SOUND_SPEED - speed.min(0.5 * SOUND_SPEED));
+                sound::play_pitched_at(
+                    ctx,
+                    &self.whoosh,
+                    volume,
+                    pitch,
+                    blob.center,
+                    local.center,
+                )?;
+                particles.emit(&particles::WHOOSH, blob.center, -relative_vel);
+            }
+        }
+        self.approaching = approaching;
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use ggez::timer;
+use std::time::{Duration, Instant};
+
+// Sleeping overshoots by up to a millisecond or so, so the end of every
+// frame is waited out by yielding instead.
+const YIELD_TIME: Duration = Duration::from_millis(2);
+
+/// Keeps frames from coming faster than a maximum rate, for when vsync
+/// doesn't.
+pub struct FrameLimiter {
+    next_frame: Instant,
+}
+
+impl FrameLimiter {
+    pub fn new() -> FrameLimiter {
+        FrameLimiter {
+            next_frame: Instant::now(),
+        }
+    }
+
+    /// Wait until the next frame is due. Call once per frame, after
+    /// presenting it. Without a maximum rate it returns right away.
+    pub fn wait(&mut self, max_fps: Option<u32>) {
+        let frame_time = match max_fps {
+            Some(fps) if fps > 0 => Duration::from_secs_f64(1.0 / f64::from(fps)),
+            _ => {
+                self.next_frame = Instant::now();
+                return;
+            }
+        };
+        let now = Instant::now();
+        if let Some(left) = self.next_frame.checked_duration_since(now) {
+            if left > YIELD_TIME {
+                timer::sleep(left - YIELD_TIME);
+            }
+            while Instant::now() < self.next_frame {
+                timer::yield_now();
+            }
+        }
+        // A late frame doesn't make the next ones come faster.
+        self.next_frame = self.next_frame.max(now) + frame_time;
+    }
+}
@@ -0,0 +1,225 @@
+use ggez::graphics::{self, Color};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+use std::collections::HashSet;
+
+use crate::collision::wall_blob_collision;
+use crate::settings;
+use crate::text::{self, Style};
+use crate::{Blob, HookState, SCREEN_SIZE, TICK_TIME};
+
+// Seconds a hint stays up.
+const SHOW_TIME: f32 = 8.0;
+// Blobs let go late when they are falling faster than this, in world units
+// per DT, and are told about it after this many late releases.
+const LATE_RELEASE_SPEED: f32 = 20.0;
+const LATE_RELEASES: u32 = 3;
+// Seconds spent slowly crawling along the floor before the floor hint.
+const FLOOR_TIME: f32 = 3.0;
+const FLOOR_SPEED: f32 = 10.0;
+// Walls bumped into without ever hanging on to one before the perch hint.
+const WALL_BUMPS: u32 = 6;
+const PADDING: f32 = 12.0;
+
+/// Tips for new players, shown during matches when what they do suggests
+/// they don't know a trick yet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Hint {
+    /// Lets go of the hook on the way down.
+    ReleaseAtTop,
+    /// Lies on the floor.
+    OffTheFloor,
+    /// Keeps bumping into walls.
+    Perch,
+    /// Held on to a wall until stamina ran out.
+    KickOff,
+}
+
+impl Hint {
+    fn text(self) -> String {
+        let keys = settings::current().keys;
+        match self {
+            Hint::ReleaseAtTop => {
+                "Release the hook near the top of your swing for the most height".to_string()
+            }
+            Hint::OffTheFloor => {
+                "Shoot your hook up at the ceiling to get off the floor".to_string()
+            }
+            Hint::Perch => format!("Hold {:?} against a wall to hang on to it", keys.perch),
+            Hint::KickOff => format!(
+                "Let go of {:?} before your stamina runs out to kick off the wall the way you aim",
+                keys.perch
+            ),
+        }
+    }
+
+    /// Bit of the hint in the settings' dismissed hints.
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+
+    fn is_dismissed(self) -> bool {
+        settings::current().dismissed_hints & self.bit() != 0
+    }
+}
+
+/// Watches what the local player does and shows a hint when it helps, at
+/// most once per hint in a match. Hints can be turned off in the settings,
+/// or one at a time with the dismiss key.
+pub struct Hints {
+    showing: Option<(Hint, f32)>,
+    shown: HashSet<Hint>,
+    was_hooked: bool,
+    late_releases: u32,
+    floor_time: f32,
+    was_touching_wall: bool,
+    wall_bumps: u32,
+    was_perched: bool,
+    has_perched: bool,
+}
+
+impl Hints {
+    pub fn new() -> Hints {
+        Hints {
+            showing: None,
+            shown: HashSet::new(),
+            was_hooked: false,
+            late_releases: 0,
+            floor_time: 0.0,
+            was_touching_wall: false,
+            wall_bumps: 0,
+            was_perched: false,
+            has_perched: false,
+        }
+    }
+
+    /// Call once per tick of play with the local blob.
+    pub fn update(&mut self, blob: Option<&Blob>) {
+        if let Some((_, time_left)) = &mut self.showing {
+            *time_left -= TICK_TIME;
+            if *time_left <= 0.0 {
+                self.showing = None;
+            }
+        }
+        let blob = match blob {
+            Some(blob) => blob,
+            None => return,
+        };
+
+        let hooked = matches!(blob.hook, HookState::Attached(_));
+        if self.was_hooked && matches!(blob.hook, HookState::None) {
+            if blob.vel.y > LATE_RELEASE_SPEED {
+                self.late_releases += 1;
+            } else {
+                self.late_releases = 0;
+            }
+        }
+        self.was_hooked = hooked;
+
+        let on_floor = matches!(
+            wall_blob_collision(blob.center),
+            Some((_, normal)) if normal.y < 0.0
+        );
+        if on_floor && blob.vel.norm() < FLOOR_SPEED && !hooked {
+            self.floor_time += TICK_TIME;
+        } else {
+            self.floor_time = 0.0;
+        }
+
+        let touching_wall = wall_blob_collision(blob.center).is_some();
+        if touching_wall && !self.was_touching_wall && !on_floor {
+            self.wall_bumps += 1;
+        }
+        self.was_touching_wall = touching_wall;
+
+        let perched = blob.perch.is_some();
+        let ran_out = self.was_perched && !perched && blob.stamina <= 0.0;
+        self.has_perched |= perched;
+        self.was_perched = perched;
+
+        if self.late_releases >= LATE_RELEASES {
+            self.late_releases = 0;
+            self.show(Hint::ReleaseAtTop);
+        }
+        if self.floor_time >= FLOOR_TIME {
+            self.floor_time = 0.0;
+            self.show(Hint::OffTheFloor);
+        }
+        if self.wall_bumps >= WALL_BUMPS && !self.has_perched {
+            self.wall_bumps = 0;
+            self.show(Hint::Perch);
+        }
+        if ran_out {
+            self.show(Hint::KickOff);
+        }
+    }
+
+    fn show(&mut self, hint: Hint) {
+        let wanted = settings::current().hints && !hint.is_dismissed();
+        if !wanted || self.showing.is_some() || self.shown.contains(&hint) {
+            return;
+        }
+        self.shown.insert(hint);
+        self.showing = Some((hint, SHOW_TIME));
+    }
+
+    /// Never show the hint that is up again. Returns whether one was up.
+    pub fn dismiss(&mut self) -> bool {
+        let (hint, _) = match self.showing.take() {
+            Some(showing) => showing,
+            None => return false,
+        };
+        let mut settings = settings::current();
+        settings.dismissed_hints |= hint.bit();
+        settings::save(settings);
+        true
+    }
+
+    /// Draw the hint that is up, if any, at the top middle of the screen.
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let hint = match self.showing {
+            Some((hint, _)) => hint,
+            None => return Ok(()),
+        };
+        let lines = [
+            hint.text(),
+            format!(
+                "{:?} to never show this again, hints can be turned off in the settings",
+                settings::current().keys.dismiss_hint
+            ),
+        ];
+        let styles = [
+            Style::new(22.0),
+            Style::new(16.0).color(Color::from_rgb(90, 90, 90)),
+        ];
+        let sizes: Vec<(f32, f32)> = lines
+            .iter()
+            .zip(&styles)
+            .map(|(line, &style)| text::size(ctx, line, style))
+            .collect();
+        let width = sizes.iter().map(|&(w, _)| w).fold(0.0, f32::max);
+        let height: f32 = sizes.iter().map(|&(_, h)| h + 4.0).sum();
+        let panel = graphics::Rect::new(
+            (SCREEN_SIZE.0 - width) / 2.0 - PADDING,
+            80.0,
+            width + 2.0 * PADDING,
+            height + 2.0 * PADDING,
+        );
+        let background = graphics::MeshBuilder::new()
+            .rectangle(
+                graphics::DrawMode::fill(),
+                panel,
+                Color::new(1.0, 1.0, 0.9, 0.9),
+            )
+            .rectangle(graphics::DrawMode::stroke(2.0), panel, graphics::BLACK)
+            .build(ctx)?;
+        graphics::draw(ctx, &background, graphics::DrawParam::new())?;
+        let mut y = panel.y + PADDING;
+        for ((line, &style), &(line_width, line_height)) in lines.iter().zip(&styles).zip(&sizes) {
+            let dest = Point2::new((SCREEN_SIZE.0 - line_width) / 2.0, y);
+            text::draw(ctx, line, dest, style)?;
+            y += line_height + 4.0;
+        }
+        Ok(())
+    }
+}
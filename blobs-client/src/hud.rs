@@ -0,0 +1,96 @@
+use ggez::graphics::{self, Color};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+
+use crate::balance;
+use crate::health::MAX_HEALTH;
+use crate::mode::GameMode;
+use crate::text::{self, Effect, Style};
+use crate::ui;
+use crate::{Blob, HookState, SCREEN_SIZE};
+
+const MARGIN: f32 = 10.0;
+const BAR_SIZE: (f32, f32) = (200.0, 16.0);
+
+/// Draw the local blob's health, rope length and score and the time left of
+/// the match in the bottom right corner, scaled by the UI scale.
+pub fn draw(
+    ctx: &mut Context,
+    id: usize,
+    blob: Option<&Blob>,
+    mode: &dyn GameMode,
+) -> GameResult<()> {
+    let corner = Point2::new(SCREEN_SIZE.0, SCREEN_SIZE.1);
+    ui::draw_scaled(ctx, corner, |ctx| draw_anchored(ctx, id, blob, mode))
+}
+
+fn draw_anchored(
+    ctx: &mut Context,
+    id: usize,
+    blob: Option<&Blob>,
+    mode: &dyn GameMode,
+) -> GameResult<()> {
+    let right = SCREEN_SIZE.0 - MARGIN;
+    let mut y = SCREEN_SIZE.1 - MARGIN - BAR_SIZE.1;
+    if let Some(blob) = blob {
+        draw_health_bar(
+            ctx,
+            graphics::Rect::new(right - BAR_SIZE.0, y, BAR_SIZE.0, BAR_SIZE.1),
+            blob.health,
+        )?;
+    }
+
+    let mut lines = Vec::new();
+    if let Some(blob) = blob {
+        lines.push(match blob.hook {
+            HookState::Attached(attachment) => {
+                format!("Rope {:.0}", (attachment.point - blob.center).norm())
+            }
+            HookState::Traveling(..) => "Rope out".to_string(),
+            HookState::None => "Rope ready".to_string(),
+        });
+        let max_stamina = balance::current().blob.perch_stamina;
+        if blob.perch.is_some() || blob.stamina < max_stamina {
+            lines.push(format!(
+                "Stamina {:.0}%",
+                100.0 * blob.stamina / max_stamina
+            ));
+        }
+        if let Some(score) = mode.score(id, blob.team) {
+            lines.push(score);
+        }
+    }
+    if let Some(time_left) = mode.time_left() {
+        let seconds = time_left.ceil() as u32;
+        lines.push(format!("{}:{:02}", seconds / 60, seconds % 60));
+    }
+
+    let style = Style::new(20.0).effect(Effect::Outline(graphics::WHITE));
+    for line in lines {
+        let (width, height) = text::size(ctx, &line, style);
+        y -= height + 4.0;
+        text::draw(ctx, &line, Point2::new(right - width, y), style)?;
+    }
+    Ok(())
+}
+
+/// Draw a health bar filling the given area.
+pub fn draw_health_bar(ctx: &mut Context, area: graphics::Rect, health: f32) -> GameResult<()> {
+    let fraction = (health / MAX_HEALTH).clamp(0.0, 1.0);
+    let color = if fraction > 0.3 {
+        Color::from_rgb(60, 200, 80)
+    } else {
+        Color::from_rgb(220, 50, 40)
+    };
+    let filled = graphics::Rect::new(area.x, area.y, area.w * fraction, area.h);
+    let bar = graphics::MeshBuilder::new()
+        .rectangle(
+            graphics::DrawMode::fill(),
+            area,
+            Color::new(0.0, 0.0, 0.0, 0.3),
+        )
+        .rectangle(graphics::DrawMode::fill(), filled, color)
+        .rectangle(graphics::DrawMode::stroke(2.0), area, graphics::BLACK)
+        .build(ctx)?;
+    graphics::draw(ctx, &bar, graphics::DrawParam::new())
+}
@@ -0,0 +1,70 @@
+use ggez::graphics;
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+use std::collections::VecDeque;
+
+use crate::health::GameEvent;
+use crate::text::{self, Effect, Style};
+use crate::{SCREEN_SIZE, TICK_TIME};
+
+const MAX_LINES: usize = 5;
+// Seconds each line is shown.
+const LINE_TIME: f32 = 5.0;
+
+/// List of recent deaths shown in the corner of the screen.
+pub struct KillFeed {
+    lines: VecDeque<(String, f32)>,
+}
+
+impl KillFeed {
+    pub fn new() -> KillFeed {
+        KillFeed {
+            lines: VecDeque::with_capacity(MAX_LINES),
+        }
+    }
+
+    /// Add lines for the deaths among events and age the old lines by a tick.
+    pub fn update(&mut self, events: &[GameEvent]) {
+        for line in self.lines.iter_mut() {
+            line.1 -= TICK_TIME;
+        }
+        while self.lines.front().is_some_and(|line| line.1 <= 0.0) {
+            self.lines.pop_front();
+        }
+        for event in events {
+            if let GameEvent::Died {
+                target,
+                killer,
+                suicide,
+                ..
+            } = *event
+            {
+                let line = match killer {
+                    Some(killer) => format!("Blob {} fragged Blob {}", killer, target),
+                    None if suicide => format!("Blob {} gave up", target),
+                    None => format!("Blob {} died", target),
+                };
+                if self.lines.len() == MAX_LINES {
+                    self.lines.pop_front();
+                }
+                self.lines.push_back((line, LINE_TIME));
+            }
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut y = 10.0;
+        let style = Style::default().effect(Effect::Outline(graphics::WHITE));
+        for (line, _time_left) in &self.lines {
+            let (width, height) = text::size(ctx, line, style);
+            text::draw(
+                ctx,
+                line,
+                Point2::new(SCREEN_SIZE.0 - width - 10.0, y),
+                style,
+            )?;
+            y += height + 4.0;
+        }
+        Ok(())
+    }
+}
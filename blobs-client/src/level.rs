@@ -0,0 +1,70 @@
+use ggez::filesystem;
+use ggez::graphics;
+use ggez::{Context, GameError, GameResult};
+use std::io::Read;
+
+pub use blobs_core::level::*;
+
+use crate::sprites;
+use crate::team::TeamColor;
+use crate::SCREEN_SIZE;
+
+/// Load a level from a RON file in the resource directory.
+pub fn load(ctx: &mut Context, path: &str) -> GameResult<Level> {
+    let mut file = filesystem::open(ctx, path)?;
+    let mut source = String::new();
+    file.read_to_string(&mut source)?;
+    Level::parse(&source)
+        .map_err(|e| GameError::ResourceLoadError(format!("Invalid level {}: {}", path, e)))
+}
+
+/// The region as a rectangle to draw.
+pub fn rect(region: Region) -> graphics::Rect {
+    graphics::Rect::new(region.x, region.y, region.w, region.h)
+}
+
+/// Color of a hazard, by what it does.
+pub fn hazard_color(hazard: &Hazard) -> graphics::Color {
+    match hazard.effect {
+        HazardEffect::Damage(_) => graphics::Color::from_rgb(240, 110, 30),
+        HazardEffect::Kill => graphics::Color::from_rgb(70, 70, 80),
+    }
+}
+
+/// Draw the floor, bases and hazards of the level.
+pub fn draw(ctx: &mut Context, level: &Level) -> GameResult<()> {
+    if level.backgrounds.is_empty() {
+        let whole = graphics::Rect::new(0.0, 0.0, SCREEN_SIZE.0, SCREEN_SIZE.1);
+        sprites::draw_tiled(ctx, whole, graphics::WHITE)?;
+    }
+    for base in &level.bases {
+        let area = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            rect(base.region),
+            base.team.area_color(),
+        )?;
+        graphics::draw(ctx, &area, graphics::DrawParam::new())?;
+        let outline = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::stroke(4.0),
+            rect(base.region),
+            base.team.color(),
+        )?;
+        graphics::draw(ctx, &outline, graphics::DrawParam::new())?;
+    }
+    for hazard in &level.hazards {
+        let color = hazard_color(hazard);
+        if sprites::draw_tiled(ctx, rect(hazard.region), color)? {
+            continue;
+        }
+        let area = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            rect(hazard.region),
+            color,
+        )?;
+        graphics::draw(ctx, &area, graphics::DrawParam::new())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use directories::ProjectDirs;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::bugreport;
+use crate::{AUTHOR, GAME_ID};
+
+/// Target for network events, which are also written to their own file.
+pub const NET: &str = "blobs::net";
+// Environment variable with the log levels, when not given on the command
+// line.
+const LEVELS_ENV_VAR: &str = "BLOBS_LOG";
+// Everything the game and its simulation say, and only problems from the
+// other libraries.
+const DEFAULT_LEVELS: &str = "warn,blobs=info,blobs_core=info";
+// Folder in the user data directory for the log files, which are written
+// anew each run.
+const LOG_DIR: &str = "logs";
+const LOG_FILE: &str = "blobs.log";
+const NET_LOG_FILE: &str = "network.log";
+
+// Simulation ticks since the start, to tell which tick a line was logged in.
+static FRAME: AtomicU64 = AtomicU64::new(0);
+
+/// The simulation tick that is being logged in.
+pub fn frame() -> u64 {
+    FRAME.load(Ordering::Relaxed)
+}
+
+/// Count a tick of the simulation, so what is logged from here on is tagged
+/// with it.
+pub fn next_frame() {
+    FRAME.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Whether the target is the module or inside it.
+fn in_module(target: &str, module: &str) -> bool {
+    target
+        .strip_prefix(module)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// Log levels by module, like "warn,blobs=info,blobs_core::physics=trace". The
+/// level without a module is for everything else, and the longest module
+/// path that matches a target wins.
+struct Levels {
+    default: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl Levels {
+    fn parse(spec: &str) -> Result<Levels, String> {
+        let mut levels = Levels {
+            default: LevelFilter::Off,
+            modules: Vec::new(),
+        };
+        let parse_level = |level: &str| {
+            level
+                .trim()
+                .parse::<LevelFilter>()
+                .map_err(|_| format!("Unknown log level {:?}", level.trim()))
+        };
+        for entry in spec.split(',').filter(|entry| !entry.trim().is_empty()) {
+            match entry.split_once('=') {
+                Some((module, level)) => levels
+                    .modules
+                    .push((module.trim().to_string(), parse_level(level)?)),
+                None => levels.default = parse_level(entry)?,
+            }
+        }
+        levels.modules.sort_by_key(|(module, _)| module.len());
+        Ok(levels)
+    }
+
+    fn level(&self, target: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .rev()
+            .find(|(module, _)| in_module(target, module))
+            .map_or(self.default, |&(_, level)| level)
+    }
+
+    fn max(&self) -> LevelFilter {
+        self.modules
+            .iter()
+            .map(|&(_, level)| level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+/// Writes log lines to stderr and the log file, network events also to the
+/// network log, and keeps the last few lines for bug reports.
+struct Logger {
+    levels: Levels,
+    start: Instant,
+    file: Option<Mutex<File>>,
+    net_file: Option<Mutex<File>>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.levels.level(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "{:9.3} #{:<7} {:5} {}: {}",
+            self.start.elapsed().as_secs_f32(),
+            FRAME.load(Ordering::Relaxed),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        eprintln!("{}", line);
+        let write = |file: &Mutex<File>| {
+            if let Ok(mut file) = file.lock() {
+                let _ = writeln!(file, "{}", line);
+            }
+        };
+        if let Some(file) = &self.file {
+            write(file);
+        }
+        if let Some(file) = &self.net_file {
+            if in_module(record.target(), NET) {
+                write(file);
+            }
+        }
+        bugreport::remember(line);
+    }
+
+    fn flush(&self) {
+        for file in self.file.iter().chain(&self.net_file) {
+            if let Ok(mut file) = file.lock() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+fn create(dir: &Path, name: &str) -> Option<Mutex<File>> {
+    File::create(dir.join(name)).ok().map(Mutex::new)
+}
+
+/// Start logging with the given levels, or those in the BLOBS_LOG
+/// environment variable, or the defaults. Call once at startup.
+pub fn init(levels: Option<&str>) {
+    let spec = levels
+        .map(str::to_string)
+        .or_else(|| env::var(LEVELS_ENV_VAR).ok());
+    let (levels, error) = match spec.as_deref().map(Levels::parse) {
+        Some(Ok(levels)) => (levels, None),
+        Some(Err(e)) => (Levels::parse(DEFAULT_LEVELS).unwrap(), Some(e)),
+        None => (Levels::parse(DEFAULT_LEVELS).unwrap(), None),
+    };
+    let dir = ProjectDirs::from("", AUTHOR, GAME_ID)
+        .map(|dirs| dirs.data_local_dir().join(LOG_DIR))
+        .filter(|dir| fs::create_dir_all(dir).is_ok());
+    let max = levels.max();
+    let logger = Logger {
+        levels,
+        start: Instant::now(),
+        file: dir.as_deref().and_then(|dir| create(dir, LOG_FILE)),
+        net_file: dir.as_deref().and_then(|dir| create(dir, NET_LOG_FILE)),
+    };
+    if log::set_logger(Box::leak(Box::new(logger))).is_err() {
+        return;
+    }
+    log::set_max_level(max);
+    if let Some(e) = error {
+        log::warn!("{}, using the default log levels {}", e, DEFAULT_LEVELS);
+    }
+    match dir {
+        Some(dir) => log::info!("Logging to {}", dir.display()),
+        None => log::warn!("No log files, the user data directory isn't writable"),
+    }
+}
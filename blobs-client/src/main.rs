@@ -0,0 +1,231 @@
+use clap::Parser;
+use ggez::event;
+use ggez::graphics;
+use ggez::GameResult;
+use nalgebra::{Point2, Vector2};
+use std::env;
+use std::path;
+use std::process;
+
+mod afk;
+mod background;
+mod balance;
+mod batch;
+mod bot;
+mod bugreport;
+mod chat;
+mod cli;
+mod console;
+mod crosshair;
+mod cvar;
+mod debug;
+mod entity;
+mod flyby;
+mod framerate;
+mod hints;
+mod hud;
+mod killfeed;
+mod level;
+mod logging;
+mod meshes;
+mod minimap;
+mod mode;
+mod music;
+mod nameplate;
+mod objective;
+mod particles;
+mod photo;
+mod pickup;
+mod profile;
+mod prompt;
+mod ratings;
+mod replay;
+mod rope;
+mod round;
+mod scene;
+mod schedule;
+mod scoreboard;
+mod screenshot;
+mod settings;
+mod shake;
+mod skin;
+mod sound;
+mod spawn_menu;
+mod speedometer;
+mod sprites;
+mod stress;
+mod team;
+mod telestrator;
+mod text;
+mod thumbnail;
+mod timestep;
+mod toast;
+mod trail;
+mod tuning;
+mod ui;
+mod update;
+mod workshop;
+
+use batch::Batch;
+use scene::game::Game;
+use scene::lobby::Lobby;
+use scene::menu::MainMenu;
+use scene::screensaver::Screensaver;
+use scene::SceneStack;
+use skin::{Pattern, Skin};
+
+// The simulation lives in its own crate, so it can be run without a window.
+// Its modules are used as if they were the game's own.
+use blobs_core::{attachment, collision, grid, health, physics, projectile, respawn, stuck, world};
+use blobs_core::{
+    enforce_hostile_entry, Blob, HookState, DT, SCREEN_SIZE, TICKS_PER_SECOND, TICK_TIME,
+};
+
+// Used by ggez to name the user data directories.
+const GAME_ID: &str = "Blobs";
+const AUTHOR: &str = "Freidrichen";
+
+const LOCAL_ID: usize = 0;
+// Bump when the game can no longer talk to older versions over the network.
+const PROTOCOL_VERSION: u32 = 1;
+
+/// Add the blob in the skin of the given id to a batch. The player's own
+/// blob gets a dark rim, so it stands out from the others. Camouflaged
+/// blobs fade out, though the player can still make out their own.
+fn draw_blob(
+    batch: &mut Batch,
+    blob: &Blob,
+    id: usize,
+    local: bool,
+    camouflage: f32,
+) -> GameResult<()> {
+    let skin = Skin::new(id, blob.team);
+    // Blink while invulnerable
+    let blink = if (blob.invulnerable * 8.0).fract() > 0.5 {
+        0.3
+    } else {
+        1.0
+    };
+    let hidden = if local { 0.6 } else { 1.0 };
+    let alpha = blink * (1.0 - hidden * camouflage);
+    let fade = |color: graphics::Color| graphics::Color {
+        a: color.a * alpha,
+        ..color
+    };
+    trail::add(batch, &blob.trail, blob.vel, fade(skin.tint));
+    let outline = blob.outline.outline(blob.center);
+    if !batch.blob_sprite(blob.center, &outline, fade(skin.body)) {
+        batch.polygon(graphics::DrawMode::fill(), &outline, fade(skin.body))?;
+    }
+    match skin.pattern {
+        Pattern::Plain => {}
+        Pattern::Core => {
+            let core: Vec<Point2<f32>> = outline
+                .iter()
+                .map(|&p| blob.center + 0.55 * (p - blob.center))
+                .collect();
+            batch.polygon(graphics::DrawMode::fill(), &core, fade(skin.accent))?;
+        }
+        Pattern::Spots => {
+            for &(x, y) in &[(-12.0, -10.0), (14.0, -4.0), (-2.0, 15.0)] {
+                batch.disc(blob.center + Vector2::new(x, y), 6.0, fade(skin.accent));
+            }
+        }
+    }
+    if local {
+        batch.polygon(
+            graphics::DrawMode::stroke(3.0),
+            &outline,
+            fade(graphics::BLACK),
+        )?;
+    }
+    let aim = blob.center + (tuning::current().blob_radius + 10.0) * blob.aim_vec;
+    batch.disc(aim, 4.0, skin.tint);
+    let (hook_point, attached) = match blob.hook {
+        HookState::Attached(attachment) => (attachment.point, true),
+        HookState::Traveling(hook_point, _) => (hook_point, false),
+        HookState::None => return Ok(()),
+    };
+    rope::add(batch, blob.center, hook_point, attached, skin.tint)?;
+    batch.hook(blob.center, hook_point, skin.tint);
+    Ok(())
+}
+
+fn main() {
+    let args = cli::Args::parse();
+    if let Some(unsupported) = args.unsupported() {
+        eprintln!("error: {}", unsupported);
+        process::exit(2);
+    }
+    logging::init(args.log.as_deref());
+    if args.dev {
+        debug::enable_dev_tools();
+    }
+    settings::load();
+    let settings = settings::set_overrides(settings::Overrides {
+        fullscreen: Some(true).filter(|_| args.fullscreen),
+        window_size: args.window_size,
+    });
+    let mut context_builder = ggez::ContextBuilder::new(GAME_ID, AUTHOR);
+    if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+        let mut resource_path = path::PathBuf::from(manifest_dir);
+        resource_path.push("resources");
+        context_builder = context_builder.add_resource_path(resource_path);
+    }
+    let built = context_builder
+        .window_setup(settings.window_setup())
+        .window_mode(settings.window_mode())
+        .build();
+    let (mut ctx, mut event_loop) = match built {
+        Ok(built) => built,
+        Err(e) => {
+            eprintln!("error: could not open the window: {}", e);
+            process::exit(1);
+        }
+    };
+    if let Err(e) = settings::apply_window(&mut ctx, &settings) {
+        eprintln!("error: could not set up the window: {}", e);
+        process::exit(1);
+    }
+    text::init(&mut ctx);
+    sprites::init(&mut ctx);
+    if let Err(e) = balance::load(&mut ctx) {
+        log::warn!("Using the default balance: {}", e);
+    }
+    if let Err(e) = tuning::load(&mut ctx) {
+        log::warn!("Using the default tuning: {}", e);
+    }
+    cvar::exec_config(&ctx);
+    let menu = MainMenu::new(&mut ctx);
+    let mut scenes = SceneStack::new(&mut ctx, Box::new(menu));
+    if args.screensaver {
+        match Screensaver::new(&mut ctx) {
+            Ok(screensaver) => scenes.push(Box::new(screensaver)),
+            Err(e) => {
+                eprintln!("error: could not start the screensaver: {}", e);
+                process::exit(1);
+            }
+        }
+    } else if let Some(level_path) = args.level() {
+        // Start a match right away on the level given on the command line.
+        log::info!("Loading level {}", level_path);
+        let mut game = match Game::new(&mut ctx, level_path) {
+            Ok(game) => game,
+            Err(e) => {
+                eprintln!("error: could not load level {}: {}", level_path, e);
+                process::exit(1);
+            }
+        };
+        if let Some(seed) = args.seed {
+            game.set_seed(seed);
+        }
+        game.add_bots(args.bots);
+        scenes.push(Box::new(Lobby::new(&mut ctx)));
+        scenes.push(Box::new(game));
+    }
+
+    match event::run(&mut ctx, &mut event_loop, &mut scenes) {
+        Ok(_) => log::info!("Exited cleanly"),
+        Err(e) => log::error!("Error occured: {}", e),
+    }
+}
@@ -0,0 +1,55 @@
+//! Circles that look the same every frame, built once and reused. They are
+//! built white and tinted when drawn.
+
+use ggez::graphics::{self, Color, Mesh};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+use std::cell::RefCell;
+
+// Discs are built at this radius and scaled to the size drawn, smooth up to
+// about this size.
+const DISC_RADIUS: f32 = 64.0;
+const TOLERANCE: f32 = 0.5;
+
+#[derive(Default)]
+struct Meshes {
+    disc: Option<Mesh>,
+}
+
+thread_local! {
+    static MESHES: RefCell<Meshes> = RefCell::new(Meshes::default());
+}
+
+/// Draw a filled circle.
+pub fn draw_disc(
+    ctx: &mut Context,
+    center: Point2<f32>,
+    radius: f32,
+    color: Color,
+) -> GameResult<()> {
+    let disc = MESHES.with(|meshes| meshes.borrow().disc.clone());
+    let disc = match disc {
+        Some(disc) => disc,
+        None => {
+            let disc = Mesh::new_circle(
+                ctx,
+                graphics::DrawMode::fill(),
+                Point2::origin(),
+                DISC_RADIUS,
+                TOLERANCE,
+                graphics::WHITE,
+            )?;
+            MESHES.with(|meshes| meshes.borrow_mut().disc = Some(disc.clone()));
+            disc
+        }
+    };
+    let scale = radius / DISC_RADIUS;
+    graphics::draw(
+        ctx,
+        &disc,
+        graphics::DrawParam::new()
+            .dest(center)
+            .scale(Vector2::new(scale, scale))
+            .color(color),
+    )
+}
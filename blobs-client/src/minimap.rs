@@ -0,0 +1,108 @@
+use ggez::graphics::{self, Color};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+
+use crate::level::{self, Level, Region};
+use crate::objective::{self, Objective};
+use crate::skin::Skin;
+use crate::team::TeamColor;
+use crate::{Blob, LOCAL_ID, SCREEN_SIZE};
+
+const SIZE: f32 = 150.0;
+const MARGIN: f32 = 10.0;
+// Room left below the minimap for the HUD.
+const HUD_HEIGHT: f32 = 130.0;
+const SCALE: f32 = SIZE / SCREEN_SIZE.0;
+
+/// Small overview of the whole level in the bottom right corner. The level
+/// geometry is drawn into a mesh once when the level is loaded, blobs and
+/// objectives are drawn on top of it every frame.
+pub struct Minimap {
+    background: graphics::Mesh,
+}
+
+impl Minimap {
+    pub fn new(ctx: &mut Context, level: &Level) -> GameResult<Minimap> {
+        let scaled = |region: Region| {
+            graphics::Rect::new(
+                region.x * SCALE,
+                region.y * SCALE,
+                region.w * SCALE,
+                region.h * SCALE,
+            )
+        };
+        let bounds = graphics::Rect::new(0.0, 0.0, SIZE, SIZE);
+        let mut builder = graphics::MeshBuilder::new();
+        builder.rectangle(
+            graphics::DrawMode::fill(),
+            bounds,
+            Color::new(1.0, 1.0, 1.0, 0.8),
+        );
+        for base in &level.bases {
+            builder.rectangle(
+                graphics::DrawMode::fill(),
+                scaled(base.region),
+                base.team.area_color(),
+            );
+        }
+        for hazard in &level.hazards {
+            builder.rectangle(
+                graphics::DrawMode::fill(),
+                scaled(hazard.region),
+                level::hazard_color(hazard),
+            );
+        }
+        // The walls are the edges of the level.
+        builder.rectangle(graphics::DrawMode::stroke(3.0), bounds, graphics::BLACK);
+        Ok(Minimap {
+            background: builder.build(ctx)?,
+        })
+    }
+
+    pub fn draw<'a>(
+        &self,
+        ctx: &mut Context,
+        blobs: impl IntoIterator<Item = (&'a usize, &'a Blob)>,
+        objectives: &[Objective],
+    ) -> GameResult<()> {
+        let corner = Point2::new(
+            SCREEN_SIZE.0 - MARGIN - SIZE,
+            SCREEN_SIZE.1 - HUD_HEIGHT - SIZE,
+        );
+        let to_map = |p: Point2<f32>| corner + Vector2::new(p.x * SCALE, p.y * SCALE);
+        graphics::draw(
+            ctx,
+            &self.background,
+            graphics::DrawParam::new().dest(corner),
+        )?;
+
+        let mut builder = graphics::MeshBuilder::new();
+        for objective in objectives {
+            let p = to_map(objective.pos);
+            builder.rectangle(
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(p.x - 3.0, p.y - 3.0, 6.0, 6.0),
+                objective::color(objective.kind),
+            );
+        }
+        for (&id, blob) in blobs {
+            let color = Skin::new(id, blob.team).body;
+            let center = to_map(blob.center);
+            builder.circle(graphics::DrawMode::fill(), center, 4.0, 0.5, color);
+            if id == LOCAL_ID {
+                builder.circle(
+                    graphics::DrawMode::stroke(2.0),
+                    center,
+                    6.0,
+                    0.5,
+                    graphics::BLACK,
+                );
+            }
+        }
+        // Building fails when there is nothing to mark.
+        if let Ok(markers) = builder.build(ctx) {
+            graphics::draw(ctx, &markers, graphics::DrawParam::new())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use ggez::graphics;
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::grid::Grid;
+use crate::health::GameEvent;
+use crate::level::{HostileEntry, Level};
+use crate::mode::{GameMode, Winner};
+use crate::objective::{Objective, ObjectiveKind};
+use crate::team::Team;
+use crate::{Blob, TICK_TIME};
+
+/// Teams steal each other's flags. Bringing the enemy flag to your own base
+/// while your own flag is at home scores a capture.
+pub struct CaptureTheFlag {
+    captures: HashMap<Team, u32>,
+    capture_limit: u32,
+    time_left: f32,
+    over: bool,
+}
+
+impl CaptureTheFlag {
+    pub fn new(capture_limit: u32, time_limit: f32) -> CaptureTheFlag {
+        CaptureTheFlag {
+            captures: HashMap::new(),
+            capture_limit,
+            time_left: time_limit,
+            over: false,
+        }
+    }
+
+    fn captures(&self, team: Team) -> u32 {
+        self.captures.get(&team).cloned().unwrap_or(0)
+    }
+}
+
+impl GameMode for CaptureTheFlag {
+    fn update(
+        &mut self,
+        blobs: &mut BTreeMap<usize, Blob>,
+        objectives: &mut [Objective],
+        level: &Level,
+        _grid: &Grid,
+        _events: &[GameEvent],
+    ) {
+        if self.over {
+            return;
+        }
+        let flag_at_home = |objectives: &[Objective], team: Team| {
+            objectives
+                .iter()
+                .filter(|o| o.kind == ObjectiveKind::Flag(team))
+                .all(|o| o.at_home())
+        };
+        for i in 0..objectives.len() {
+            let carrier = match objectives[i].carrier.and_then(|id| blobs.get(&id)) {
+                Some(carrier) => carrier,
+                None => continue,
+            };
+            let team = match carrier.team {
+                Some(team) => team,
+                None => continue,
+            };
+            let in_own_base = level
+                .team_base(team)
+                .is_some_and(|base| base.region.contains(carrier.center));
+            if in_own_base && flag_at_home(objectives, team) {
+                *self.captures.entry(team).or_insert(0) += 1;
+                objectives[i].return_home();
+            }
+        }
+        self.time_left = (self.time_left - TICK_TIME).max(0.0);
+        self.over =
+            self.time_left <= 0.0 || self.captures.values().any(|&c| c >= self.capture_limit);
+    }
+
+    fn hostile_base_entry(&self) -> HostileEntry {
+        HostileEntry::Allow
+    }
+
+    fn winner(&self) -> Option<Winner> {
+        if !self.over {
+            return None;
+        }
+        let (red, blue) = (self.captures(Team::Red), self.captures(Team::Blue));
+        Some(if red > blue {
+            Winner::Team(Team::Red)
+        } else if blue > red {
+            Winner::Team(Team::Blue)
+        } else {
+            Winner::Draw
+        })
+    }
+
+    fn time_left(&self) -> Option<f32> {
+        Some(self.time_left)
+    }
+
+    fn score(&self, _id: usize, team: Option<Team>) -> Option<String> {
+        team.map(|team| format!("{} / {} captures", self.captures(team), self.capture_limit))
+    }
+
+    fn draw_status(&self, ctx: &mut Context) -> GameResult<()> {
+        let text = graphics::Text::new(format!(
+            "Red {} - {} Blue   (capture limit {})\n{:.0}",
+            self.captures(Team::Red),
+            self.captures(Team::Blue),
+            self.capture_limit,
+            self.time_left.ceil()
+        ));
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new()
+                .dest(Point2::new(10.0, 10.0))
+                .color(graphics::BLACK),
+        )
+    }
+}
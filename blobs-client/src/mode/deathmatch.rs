@@ -0,0 +1,121 @@
+use ggez::graphics;
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::balance;
+use crate::grid::Grid;
+use crate::health::GameEvent;
+use crate::level::{HostileEntry, Level};
+use crate::mode::{GameMode, Winner};
+use crate::objective::Objective;
+use crate::team::Team;
+use crate::{Blob, TICK_TIME};
+
+/// Every blob for itself. The match ends when a blob reaches the frag limit,
+/// or when time runs out. Blobs that kill themselves lose frags.
+pub struct Deathmatch {
+    kills: HashMap<usize, u32>,
+    frag_limit: u32,
+    time_left: f32,
+    over: bool,
+}
+
+impl Deathmatch {
+    pub fn new(frag_limit: u32, time_limit: f32) -> Deathmatch {
+        Deathmatch {
+            kills: HashMap::new(),
+            frag_limit,
+            time_left: time_limit,
+            over: false,
+        }
+    }
+
+    /// Blob ids and kills, best first.
+    fn standings(&self) -> Vec<(usize, u32)> {
+        let mut standings: Vec<_> = self.kills.iter().map(|(&id, &kills)| (id, kills)).collect();
+        standings.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        standings
+    }
+}
+
+impl GameMode for Deathmatch {
+    fn update(
+        &mut self,
+        blobs: &mut BTreeMap<usize, Blob>,
+        _objectives: &mut [Objective],
+        _level: &Level,
+        _grid: &Grid,
+        events: &[GameEvent],
+    ) {
+        if self.over {
+            return;
+        }
+        for &id in blobs.keys() {
+            self.kills.entry(id).or_insert(0);
+        }
+        let frag_penalty = balance::current().suicide.frag_penalty;
+        for event in events {
+            match *event {
+                GameEvent::Died {
+                    killer: Some(killer),
+                    ..
+                } => *self.kills.entry(killer).or_insert(0) += 1,
+                GameEvent::Died {
+                    target,
+                    suicide: true,
+                    ..
+                } => {
+                    let kills = self.kills.entry(target).or_insert(0);
+                    *kills = kills.saturating_sub(frag_penalty);
+                }
+                _ => {}
+            }
+        }
+        self.time_left = (self.time_left - TICK_TIME).max(0.0);
+        self.over = self.time_left <= 0.0 || self.kills.values().any(|&k| k >= self.frag_limit);
+    }
+
+    fn hostile_base_entry(&self) -> HostileEntry {
+        HostileEntry::Allow
+    }
+
+    fn winner(&self) -> Option<Winner> {
+        if !self.over {
+            return None;
+        }
+        match self.standings().as_slice() {
+            [(_, first), (_, second), ..] if first == second => Some(Winner::Draw),
+            [(id, _), ..] => Some(Winner::Blob(*id)),
+            [] => Some(Winner::Draw),
+        }
+    }
+
+    fn time_left(&self) -> Option<f32> {
+        Some(self.time_left)
+    }
+
+    fn score(&self, id: usize, _team: Option<Team>) -> Option<String> {
+        let kills = self.kills.get(&id).cloned().unwrap_or(0);
+        Some(format!("{} / {} frags", kills, self.frag_limit))
+    }
+
+    fn draw_status(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut lines = vec![format!(
+            "{:.0}   (frag limit {})",
+            self.time_left.ceil(),
+            self.frag_limit
+        )];
+        for (id, kills) in self.standings() {
+            lines.push(format!("Blob {}: {}", id, kills));
+        }
+        let text = graphics::Text::new(lines.join("\n"));
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new()
+                .dest(Point2::new(10.0, 10.0))
+                .color(graphics::BLACK),
+        )
+    }
+}
@@ -0,0 +1,217 @@
+use ggez::graphics::{self, Color};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::grid::Grid;
+use crate::health::GameEvent;
+use crate::level::{HostileEntry, Level};
+use crate::mode::koth::{self, Contender, ZoneState};
+use crate::mode::{DominationConfig, GameMode, Winner, Zone};
+use crate::objective::Objective;
+use crate::team::Team;
+use crate::{Blob, SCREEN_SIZE, TICK_TIME};
+
+// Size of the capture progress bar across the middle of each point.
+const BAR_SIZE: (f32, f32) = (80.0, 8.0);
+
+struct CapturePoint {
+    zone: Zone,
+    state: ZoneState,
+    owner: Option<Contender>,
+    // Who is taking the point and how far along they are, from 0 to 1.
+    capture: Option<(Contender, f32)>,
+}
+
+impl CapturePoint {
+    fn new(zone: Zone) -> CapturePoint {
+        CapturePoint {
+            zone,
+            state: ZoneState::Empty,
+            owner: None,
+            capture: None,
+        }
+    }
+
+    /// A contender alone on the point takes it over time, after undoing
+    /// anyone else's progress. Progress slips back while nobody is there,
+    /// and stays put while the point is contested.
+    fn update(&mut self, blobs: &BTreeMap<usize, Blob>, capture_time: f32) {
+        self.state = koth::zone_state(&self.zone, blobs);
+        let step = TICK_TIME / capture_time;
+        self.capture = match (self.state, self.capture) {
+            (ZoneState::Contested, capture) => capture,
+            (ZoneState::Held(holder), Some((capturer, progress))) if capturer != holder => {
+                Some((capturer, progress - step)).filter(|&(_, progress)| progress > 0.0)
+            }
+            (ZoneState::Held(holder), _) if self.owner == Some(holder) => None,
+            (ZoneState::Held(holder), capture) => {
+                let progress = capture.map_or(0.0, |(_, progress)| progress) + step;
+                if progress >= 1.0 {
+                    self.owner = Some(holder);
+                    None
+                } else {
+                    Some((holder, progress))
+                }
+            }
+            (ZoneState::Empty, Some((capturer, progress))) => {
+                Some((capturer, progress - step)).filter(|&(_, progress)| progress > 0.0)
+            }
+            (ZoneState::Empty, None) => None,
+        };
+    }
+
+    fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut color = match (self.state, self.owner) {
+            (ZoneState::Contested, _) => Color::from_rgb(250, 150, 30),
+            (_, Some(owner)) => owner.color(),
+            (_, None) => Color::from_rgb(200, 200, 200),
+        };
+        color.a = 0.3;
+        let zone = koth::zone_mesh(ctx, &self.zone, color)?;
+        graphics::draw(ctx, &zone, graphics::DrawParam::new())?;
+        if let Some((capturer, progress)) = self.capture {
+            let center = self.zone.center();
+            let bar = graphics::Rect::new(
+                center.x - BAR_SIZE.0 / 2.0,
+                center.y - BAR_SIZE.1 / 2.0,
+                BAR_SIZE.0,
+                BAR_SIZE.1,
+            );
+            koth::draw_progress_bar(ctx, bar, progress, capturer.color())?;
+        }
+        Ok(())
+    }
+}
+
+/// Contenders take capture points by staying alone on them, and score for
+/// every second they own each point, like king of the hill with several
+/// hills that stay taken.
+pub struct Domination {
+    points: Vec<CapturePoint>,
+    capture_time: f32,
+    scores: HashMap<Contender, f32>,
+    score_limit: f32,
+    points_per_second: f32,
+    time_left: f32,
+    over: bool,
+}
+
+impl Domination {
+    pub fn new(config: &DominationConfig) -> Domination {
+        Domination {
+            points: config
+                .points
+                .iter()
+                .cloned()
+                .map(CapturePoint::new)
+                .collect(),
+            capture_time: config.capture_time,
+            scores: HashMap::new(),
+            score_limit: config.score_limit,
+            points_per_second: config.points_per_second,
+            time_left: config.time_limit,
+            over: false,
+        }
+    }
+
+    /// Contenders and scores, best first.
+    fn standings(&self) -> Vec<(Contender, f32)> {
+        let mut standings: Vec<_> = self.scores.iter().map(|(&c, &s)| (c, s)).collect();
+        standings.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        standings
+    }
+}
+
+impl GameMode for Domination {
+    fn update(
+        &mut self,
+        blobs: &mut BTreeMap<usize, Blob>,
+        _objectives: &mut [Objective],
+        _level: &Level,
+        _grid: &Grid,
+        _events: &[GameEvent],
+    ) {
+        if self.over {
+            return;
+        }
+        for point in self.points.iter_mut() {
+            point.update(blobs, self.capture_time);
+            if let Some(owner) = point.owner {
+                let score = self.scores.entry(owner).or_insert(0.0);
+                *score = (*score + self.points_per_second * TICK_TIME).min(self.score_limit);
+            }
+        }
+        self.time_left = (self.time_left - TICK_TIME).max(0.0);
+        self.over = self.time_left <= 0.0 || self.scores.values().any(|&s| s >= self.score_limit);
+    }
+
+    fn hostile_base_entry(&self) -> HostileEntry {
+        HostileEntry::Allow
+    }
+
+    fn winner(&self) -> Option<Winner> {
+        if !self.over {
+            return None;
+        }
+        match self.standings().as_slice() {
+            [(_, first), (_, second), ..] if first == second => Some(Winner::Draw),
+            [(Contender::Team(team), _), ..] => Some(Winner::Team(*team)),
+            [(Contender::Blob(id), _), ..] => Some(Winner::Blob(*id)),
+            [] => Some(Winner::Draw),
+        }
+    }
+
+    fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        for point in &self.points {
+            point.draw(ctx)?;
+        }
+        Ok(())
+    }
+
+    fn time_left(&self) -> Option<f32> {
+        Some(self.time_left)
+    }
+
+    fn score(&self, id: usize, team: Option<Team>) -> Option<String> {
+        let contender = match team {
+            Some(team) => Contender::Team(team),
+            None => Contender::Blob(id),
+        };
+        let score = self.scores.get(&contender).cloned().unwrap_or(0.0);
+        let owned = self
+            .points
+            .iter()
+            .filter(|point| point.owner == Some(contender))
+            .count();
+        Some(format!(
+            "{:.0} / {:.0} points, {} of {} held",
+            score.floor(),
+            self.score_limit,
+            owned,
+            self.points.len()
+        ))
+    }
+
+    fn draw_status(&self, ctx: &mut Context) -> GameResult<()> {
+        let y = koth::draw_score_bars(ctx, &self.standings(), self.score_limit)?;
+        let contested = self
+            .points
+            .iter()
+            .filter(|point| point.state == ZoneState::Contested)
+            .count();
+        let status = match contested {
+            0 => format!("{:.0}", self.time_left.ceil()),
+            n => format!("{:.0}   {} CONTESTED", self.time_left.ceil(), n),
+        };
+        let text = graphics::Text::new(status);
+        let x = SCREEN_SIZE.0 / 2.0 - text.width(ctx) as f32 / 2.0;
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new()
+                .dest(Point2::new(x, y))
+                .color(graphics::BLACK),
+        )
+    }
+}
@@ -0,0 +1,272 @@
+use ggez::graphics;
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::balance;
+use crate::grid::Grid;
+use crate::health::GameEvent;
+use crate::level::{HostileEntry, Level};
+use crate::mode::{GameMode, Winner};
+use crate::objective::Objective;
+use crate::team::Team;
+use crate::{Blob, TICK_TIME};
+
+// Seconds that announcements stay on screen.
+const ANNOUNCEMENT_TIME: f32 = 2.0;
+
+/// One against one in rounds. The first to win the majority of the rounds
+/// wins the match, and the loser goes to the back of the queue of waiting
+/// blobs while the next in line takes them on.
+pub struct Duel {
+    best_of: u32,
+    /// Spawn points of the two sides, swapped every round.
+    sides: [Point2<f32>; 2],
+    duelists: Vec<usize>,
+    round_wins: HashMap<usize, u32>,
+    round: u32,
+    /// Blobs waiting for their turn, kept out of play.
+    queue: VecDeque<(usize, Blob)>,
+    champion: Option<usize>,
+    announcement: Option<(String, f32)>,
+}
+
+impl Duel {
+    pub fn new(best_of: u32, sides: [Point2<f32>; 2]) -> Duel {
+        Duel {
+            best_of,
+            sides,
+            duelists: Vec::with_capacity(2),
+            round_wins: HashMap::new(),
+            round: 0,
+            queue: VecDeque::new(),
+            champion: None,
+            announcement: None,
+        }
+    }
+
+    fn rounds_to_win(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+
+    fn announce(&mut self, text: String) {
+        self.announcement = Some((text, ANNOUNCEMENT_TIME));
+    }
+
+    /// Put the duelists back on their sides, swapping sides every round.
+    fn start_round(&mut self, blobs: &mut BTreeMap<usize, Blob>) {
+        self.round += 1;
+        for (i, id) in self.duelists.iter().enumerate() {
+            if let Some(blob) = blobs.get_mut(id) {
+                blob.respawn(self.sides[(i + self.round as usize) % 2]);
+                blob.invulnerable = balance::current().duel.round_start_invulnerability;
+            }
+        }
+        self.announce(format!("Round {}", self.round));
+    }
+
+    /// Fill the free duelist slots from the queue, and bench everyone else.
+    /// Duelists that were kicked or left give up their slot.
+    fn seat_blobs(&mut self, blobs: &mut BTreeMap<usize, Blob>) {
+        let gone: Vec<usize> = self
+            .duelists
+            .iter()
+            .filter(|id| !blobs.contains_key(id))
+            .cloned()
+            .collect();
+        for id in gone {
+            self.duelists.retain(|&duelist| duelist != id);
+            self.round_wins.remove(&id);
+            self.announce(format!("Blob {} left the duel", id));
+        }
+        let newcomers: Vec<usize> = blobs
+            .keys()
+            .filter(|id| !self.duelists.contains(id))
+            .cloned()
+            .collect();
+        for id in newcomers {
+            let blob = blobs.remove(&id).unwrap();
+            self.queue.push_back((id, blob));
+        }
+        let was_full = self.duelists.len() == 2;
+        while self.duelists.len() < 2 {
+            match self.queue.pop_front() {
+                Some((id, blob)) => {
+                    blobs.insert(id, blob);
+                    self.round_wins.insert(id, 0);
+                    self.duelists.push(id);
+                }
+                None => break,
+            }
+        }
+        if !was_full && self.duelists.len() == 2 {
+            self.round = 0;
+            self.champion = None;
+            self.start_round(blobs);
+        }
+    }
+
+    fn end_round(&mut self, blobs: &mut BTreeMap<usize, Blob>, dead: &[usize]) {
+        let survivors: Vec<usize> = self
+            .duelists
+            .iter()
+            .filter(|id| !dead.contains(id))
+            .cloned()
+            .collect();
+        let round_winner = match survivors.as_slice() {
+            [winner] => *winner,
+            _ => {
+                self.round -= 1;
+                self.start_round(blobs);
+                self.announce("Draw! Replaying round".to_string());
+                return;
+            }
+        };
+        let wins = self.round_wins.entry(round_winner).or_insert(0);
+        *wins += 1;
+        if *wins < self.rounds_to_win() {
+            self.start_round(blobs);
+            return;
+        }
+
+        // The loser goes to the back of the queue and the next in line steps up.
+        let loser = self
+            .duelists
+            .iter()
+            .cloned()
+            .find(|&id| id != round_winner)
+            .unwrap();
+        self.duelists.retain(|&id| id == round_winner);
+        if let Some(blob) = blobs.remove(&loser) {
+            self.queue.push_back((loser, blob));
+        }
+        if self.queue.len() > 1 || self.queue.front().is_some_and(|&(id, _)| id != loser) {
+            self.announce(format!("Blob {} wins the duel!", round_winner));
+            self.round_wins.insert(round_winner, 0);
+            self.seat_blobs(blobs);
+        } else {
+            // Nobody else is waiting, so the match is over.
+            self.champion = Some(round_winner);
+        }
+    }
+}
+
+impl GameMode for Duel {
+    fn update(
+        &mut self,
+        blobs: &mut BTreeMap<usize, Blob>,
+        _objectives: &mut [Objective],
+        _level: &Level,
+        _grid: &Grid,
+        events: &[GameEvent],
+    ) {
+        if let Some((_text, time_left)) = &mut self.announcement {
+            *time_left -= TICK_TIME;
+            if *time_left <= 0.0 {
+                self.announcement = None;
+            }
+        }
+        if self.champion.is_some() {
+            return;
+        }
+        self.seat_blobs(blobs);
+
+        let dead: Vec<usize> = events
+            .iter()
+            .filter_map(|event| match *event {
+                GameEvent::Died { target, .. } if self.duelists.contains(&target) => Some(target),
+                _ => None,
+            })
+            .collect();
+        if !dead.is_empty() && self.duelists.len() == 2 {
+            self.end_round(blobs, &dead);
+        }
+    }
+
+    fn hostile_base_entry(&self) -> HostileEntry {
+        HostileEntry::Allow
+    }
+
+    fn winner(&self) -> Option<Winner> {
+        self.champion.map(Winner::Blob)
+    }
+
+    fn score(&self, id: usize, _team: Option<Team>) -> Option<String> {
+        let wins = self.round_wins.get(&id)?;
+        Some(format!("{} / {} rounds", wins, self.rounds_to_win()))
+    }
+
+    fn draw_status(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut lines = Vec::new();
+        match self.duelists.as_slice() {
+            [a, b] => lines.push(format!(
+                "Blob {} ({})  vs  Blob {} ({})   best of {}",
+                a, self.round_wins[a], b, self.round_wins[b], self.best_of
+            )),
+            _ => lines.push("Waiting for an opponent".to_string()),
+        }
+        if !self.queue.is_empty() {
+            let waiting: Vec<String> = self
+                .queue
+                .iter()
+                .map(|(id, _blob)| format!("Blob {}", id))
+                .collect();
+            lines.push(format!("Waiting: {}", waiting.join(", ")));
+        }
+        if let Some((text, _time_left)) = &self.announcement {
+            lines.push(text.clone());
+        }
+        let text = graphics::Text::new(lines.join("\n"));
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new()
+                .dest(Point2::new(10.0, 10.0))
+                .color(graphics::BLACK),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn died(target: usize) -> GameEvent {
+        GameEvent::Died {
+            target,
+            killer: None,
+            pos: Point2::origin(),
+            suicide: false,
+        }
+    }
+
+    #[test]
+    fn next_in_line_replaces_a_kicked_duelist() {
+        let level = Level::parse("()").unwrap();
+        let grid = Grid::new();
+        let sides = [Point2::new(200.0, 500.0), Point2::new(800.0, 500.0)];
+        let mut duel = Duel::new(1, sides);
+        let mut blobs: BTreeMap<usize, Blob> = (0..3)
+            .map(|id| (id, Blob::new(Point2::new(500.0, 500.0), None)))
+            .collect();
+        let update = |duel: &mut Duel, blobs: &mut BTreeMap<usize, Blob>, events| {
+            duel.update(blobs, &mut [], &level, &grid, events);
+        };
+
+        update(&mut duel, &mut blobs, &[]);
+        assert_eq!(duel.duelists, [0, 1]);
+        assert_eq!(blobs.keys().collect::<Vec<_>>(), [&0, &1]);
+
+        // Kicked mid-round.
+        blobs.remove(&1);
+        update(&mut duel, &mut blobs, &[]);
+        assert_eq!(duel.duelists, [0, 2]);
+        assert!(duel.queue.is_empty());
+        assert!(blobs.contains_key(&2));
+
+        // The new duel plays out, and with nobody left waiting it decides
+        // the match.
+        update(&mut duel, &mut blobs, &[died(2)]);
+        assert!(matches!(duel.winner(), Some(Winner::Blob(0))));
+    }
+}
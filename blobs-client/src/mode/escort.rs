@@ -0,0 +1,308 @@
+use ggez::graphics::{self, Color};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+use std::collections::BTreeMap;
+
+use crate::attachment::{Anchor, Attachment};
+use crate::balance;
+use crate::grid::Grid;
+use crate::health::GameEvent;
+use crate::level::{HostileEntry, Level};
+use crate::mode::{EscortConfig, GameMode, Winner};
+use crate::objective::Objective;
+use crate::team::{Team, TeamColor};
+use crate::tuning;
+use crate::{Blob, HookState, DT, SCREEN_SIZE, TICK_TIME};
+
+const PAYLOAD_RADIUS: f32 = 30.0;
+
+/// Polyline that the payload moves along.
+struct Track {
+    points: Vec<Point2<f32>>,
+    length: f32,
+}
+
+impl Track {
+    fn new(points: &[(f32, f32)]) -> Track {
+        let points: Vec<_> = points.iter().map(|&(x, y)| Point2::new(x, y)).collect();
+        let length = points.windows(2).map(|w| (w[1] - w[0]).norm()).sum();
+        Track { points, length }
+    }
+
+    /// Find the track segment at distance s along the track. Returns the
+    /// segment start point, its direction and the distance into the segment.
+    fn segment_at(&self, s: f32) -> (Point2<f32>, Vector2<f32>, f32) {
+        let mut s = s;
+        for w in self.points.windows(2) {
+            let segment = w[1] - w[0];
+            if s <= segment.norm() {
+                return (w[0], segment.normalize(), s);
+            }
+            s -= segment.norm();
+        }
+        match self.points.as_slice() {
+            [.., a, b] => (*b, (b - a).normalize(), 0.0),
+            [a] => (*a, Vector2::x(), 0.0),
+            [] => (Point2::origin(), Vector2::x(), 0.0),
+        }
+    }
+
+    fn point_at(&self, s: f32) -> Point2<f32> {
+        let (start, direction, into) = self.segment_at(s);
+        start + into * direction
+    }
+
+    fn tangent_at(&self, s: f32) -> Vector2<f32> {
+        self.segment_at(s).1
+    }
+}
+
+/// Mode where the attacking team gets a payload to the end of a track before
+/// time runs out, while the other team defends.
+pub struct Escort {
+    attackers: Team,
+    track: Track,
+    checkpoints: Vec<f32>,
+    reached_checkpoints: usize,
+    checkpoint_bonus: f32,
+    payload_s: f32,
+    payload_speed: f32,
+    time_left: f32,
+    overtime_left: Option<f32>,
+    winner: Option<Team>,
+}
+
+impl Escort {
+    pub fn new(config: &EscortConfig) -> Escort {
+        let track = Track::new(&config.track);
+        let mut checkpoints: Vec<f32> = config
+            .checkpoints
+            .iter()
+            .map(|fraction| fraction * track.length)
+            .collect();
+        checkpoints.sort_by(f32::total_cmp);
+        Escort {
+            attackers: config.attackers,
+            track,
+            checkpoints,
+            reached_checkpoints: 0,
+            checkpoint_bonus: config.checkpoint_bonus,
+            payload_s: 0.0,
+            payload_speed: 0.0,
+            time_left: config.time_limit,
+            overtime_left: None,
+            winner: None,
+        }
+    }
+
+    pub fn payload_pos(&self) -> Point2<f32> {
+        self.track.point_at(self.payload_s)
+    }
+}
+
+impl GameMode for Escort {
+    fn update(
+        &mut self,
+        blobs: &mut BTreeMap<usize, Blob>,
+        _objectives: &mut [Objective],
+        _level: &Level,
+        _grid: &Grid,
+        _events: &[GameEvent],
+    ) {
+        let payload_pos = self.payload_pos();
+        for blob in blobs.values_mut() {
+            collide_with_payload(blob, payload_pos);
+        }
+        if self.winner.is_some() {
+            return;
+        }
+
+        let stats = balance::current().escort;
+        let near = |team| {
+            blobs.values().any(|blob| {
+                blob.team == Some(team) && (blob.center - payload_pos).norm() < stats.push_radius
+            })
+        };
+        let attackers_near = near(self.attackers);
+        let defenders_near = near(self.attackers.opponent());
+
+        // Move the payload along the track
+        let tangent = self.track.tangent_at(self.payload_s);
+        let mut acc = -stats.payload_friction * self.payload_speed;
+        if attackers_near && !defenders_near && self.payload_speed < stats.push_speed {
+            acc += stats.push_acc;
+        }
+        for blob in blobs.values() {
+            if let HookState::Attached(attachment) = blob.hook {
+                if attachment.anchor == Anchor::Payload {
+                    let tow_acc = stats.tow_mass_ratio * attachment.reaction(blob.center);
+                    acc += tow_acc.dot(&tangent);
+                }
+            }
+        }
+        self.payload_speed += acc * DT;
+        let min_s = match self.reached_checkpoints {
+            0 => 0.0,
+            n => self.checkpoints[n - 1],
+        };
+        self.payload_s += self.payload_speed * DT;
+        if self.payload_s < min_s {
+            // The payload can't be pushed back past a reached checkpoint.
+            self.payload_s = min_s;
+            self.payload_speed = 0.0;
+        }
+        self.payload_s = self.payload_s.min(self.track.length);
+
+        // Attach hooks to the payload and keep towing hooks on it
+        let payload_pos = self.payload_pos();
+        for blob in blobs.values_mut() {
+            match &mut blob.hook {
+                HookState::Traveling(hook_point, _)
+                    if (*hook_point - payload_pos).norm() < PAYLOAD_RADIUS =>
+                {
+                    blob.hook = HookState::Attached(Attachment::rope(Anchor::Payload, payload_pos));
+                }
+                HookState::Attached(attachment) if attachment.anchor == Anchor::Payload => {
+                    attachment.point = payload_pos;
+                }
+                _ => {}
+            }
+        }
+
+        // Progress and time
+        while self.reached_checkpoints < self.checkpoints.len()
+            && self.payload_s >= self.checkpoints[self.reached_checkpoints]
+        {
+            self.reached_checkpoints += 1;
+            self.time_left += self.checkpoint_bonus;
+        }
+        if self.payload_s >= self.track.length {
+            self.winner = Some(self.attackers);
+            return;
+        }
+        self.time_left = (self.time_left - TICK_TIME).max(0.0);
+        if self.time_left > 0.0 {
+            return;
+        }
+        // Overtime lasts as long as the attackers keep coming back to the
+        // payload within the grace period.
+        let overtime_left = if attackers_near {
+            stats.overtime_grace
+        } else {
+            self.overtime_left.unwrap_or(0.0) - TICK_TIME
+        };
+        if overtime_left <= 0.0 {
+            self.winner = Some(self.attackers.opponent());
+        }
+        self.overtime_left = Some(overtime_left);
+    }
+
+    fn hostile_base_entry(&self) -> HostileEntry {
+        HostileEntry::Block
+    }
+
+    fn winner(&self) -> Option<Winner> {
+        self.winner.map(Winner::Team)
+    }
+
+    fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let track =
+            graphics::Mesh::new_line(ctx, &self.track.points, 6.0, Color::from_rgb(180, 180, 180))?;
+        graphics::draw(ctx, &track, graphics::DrawParam::new())?;
+        for &checkpoint in &self.checkpoints {
+            let marker = graphics::Mesh::new_circle(
+                ctx,
+                graphics::DrawMode::stroke(3.0),
+                self.track.point_at(checkpoint),
+                10.0,
+                0.5,
+                self.attackers.color(),
+            )?;
+            graphics::draw(ctx, &marker, graphics::DrawParam::new())?;
+        }
+        let payload = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::fill(),
+            self.payload_pos(),
+            PAYLOAD_RADIUS,
+            0.5,
+            Color::from_rgb(90, 70, 50),
+        )?;
+        graphics::draw(ctx, &payload, graphics::DrawParam::new())
+    }
+
+    /// Draw the progress bar and clock at the top of the screen.
+    fn time_left(&self) -> Option<f32> {
+        Some(self.time_left)
+    }
+
+    fn score(&self, _id: usize, _team: Option<Team>) -> Option<String> {
+        Some(format!(
+            "{} / {} checkpoints",
+            self.reached_checkpoints,
+            self.checkpoints.len()
+        ))
+    }
+
+    fn draw_status(&self, ctx: &mut Context) -> GameResult<()> {
+        let bar = graphics::Rect::new(SCREEN_SIZE.0 / 2.0 - 200.0, 20.0, 400.0, 16.0);
+        let mut progress = bar;
+        progress.w *= self.payload_s / self.track.length;
+        let background = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            bar,
+            Color::from_rgb(220, 220, 220),
+        )?;
+        graphics::draw(ctx, &background, graphics::DrawParam::new())?;
+        if progress.w > 0.0 {
+            let filled = graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                progress,
+                self.attackers.color(),
+            )?;
+            graphics::draw(ctx, &filled, graphics::DrawParam::new())?;
+        }
+        for &checkpoint in &self.checkpoints {
+            let x = bar.x + bar.w * checkpoint / self.track.length;
+            let tick = graphics::Mesh::new_line(
+                ctx,
+                &[Point2::new(x, bar.y), Point2::new(x, bar.y + bar.h)],
+                2.0,
+                graphics::BLACK,
+            )?;
+            graphics::draw(ctx, &tick, graphics::DrawParam::new())?;
+        }
+
+        let status = match self.overtime_left {
+            Some(_) => "OVERTIME".to_string(),
+            None => format!("{:.0}", self.time_left.ceil()),
+        };
+        let text = graphics::Text::new(status);
+        let x = SCREEN_SIZE.0 / 2.0 - text.width(ctx) as f32 / 2.0;
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new()
+                .dest(Point2::new(x, bar.y + bar.h + 6.0))
+                .color(graphics::BLACK),
+        )
+    }
+}
+
+/// Push a blob out of the payload, which is too heavy to be moved by bumping.
+fn collide_with_payload(blob: &mut Blob, payload_pos: Point2<f32>) {
+    let offset = blob.center - payload_pos;
+    let distance = offset.norm();
+    let reach = tuning::current().blob_radius + PAYLOAD_RADIUS;
+    if distance >= reach || distance == 0.0 {
+        return;
+    }
+    let normal = offset / distance;
+    blob.center = payload_pos + reach * normal;
+    let normal_vel = blob.vel.dot(&normal);
+    if normal_vel < 0.0 {
+        blob.vel -= 2.0 * normal_vel * normal;
+    }
+}
@@ -0,0 +1,195 @@
+use ggez::graphics;
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::balance;
+use crate::grid::Grid;
+use crate::health::GameEvent;
+use crate::level::{HostileEntry, Level};
+use crate::mode::{GameMode, HideAndSeekConfig, Winner};
+use crate::objective::Objective;
+use crate::team::Team;
+use crate::tuning;
+use crate::{Blob, HookState, SCREEN_SIZE, TICK_TIME};
+
+// Blobs this close to a wall, beyond touching it, can latch on.
+const LATCH_MARGIN: f32 = 2.0;
+
+/// A hider stuck to a wall.
+struct Latch {
+    pos: Point2<f32>,
+    // Seconds since latching, the hider fades in the meantime.
+    time: f32,
+}
+
+/// The seekers hunt the other team's blobs before time runs out. Hiders that
+/// come to rest against a wall stick there and slowly fade into it, until
+/// they shoot their hook again. Seekers have a short hook, and a hider hit
+/// by it or bumped into is found and joins the seekers.
+pub struct HideAndSeek {
+    seekers: Team,
+    latched: HashMap<usize, Latch>,
+    hiders_left: usize,
+    time_left: f32,
+    winner: Option<Team>,
+}
+
+impl HideAndSeek {
+    pub fn new(config: &HideAndSeekConfig) -> HideAndSeek {
+        HideAndSeek {
+            seekers: config.seekers,
+            latched: HashMap::new(),
+            hiders_left: 0,
+            time_left: config.time_limit,
+            winner: None,
+        }
+    }
+
+    fn is_seeker(&self, blob: &Blob) -> bool {
+        blob.team == Some(self.seekers)
+    }
+
+    /// Stick resting hiders to walls and keep latched ones still.
+    fn latch(&mut self, blobs: &mut BTreeMap<usize, Blob>) {
+        let stats = balance::current().hide_and_seek;
+        for (&id, blob) in blobs.iter_mut() {
+            if self.is_seeker(blob) || !matches!(blob.hook, HookState::None) {
+                self.latched.remove(&id);
+                continue;
+            }
+            match self.latched.get_mut(&id) {
+                Some(latch) => {
+                    latch.time += TICK_TIME;
+                    blob.center = latch.pos;
+                    blob.vel = Vector2::zeros();
+                }
+                None if touches_wall(blob.center) && blob.vel.norm() < stats.latch_speed => {
+                    blob.vel = Vector2::zeros();
+                    self.latched.insert(
+                        id,
+                        Latch {
+                            pos: blob.center,
+                            time: 0.0,
+                        },
+                    );
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Cut seeker hooks off at their range and turn hiders they hit into
+    /// seekers.
+    fn seek(&mut self, blobs: &mut BTreeMap<usize, Blob>) {
+        let stats = balance::current().hide_and_seek;
+        let seekers: Vec<(Point2<f32>, Option<Point2<f32>>)> = blobs
+            .values_mut()
+            .filter(|blob| blob.team == Some(self.seekers))
+            .map(|blob| {
+                let hook = match blob.hook {
+                    HookState::Traveling(hook_point, _) => Some(hook_point),
+                    _ => None,
+                };
+                if let Some(hook_point) = hook {
+                    if (hook_point - blob.center).norm() > stats.seeker_hook_range {
+                        blob.hook = HookState::None;
+                    }
+                }
+                (blob.center, hook)
+            })
+            .collect();
+        let radius = tuning::current().blob_radius;
+        for (&id, blob) in blobs.iter_mut() {
+            if blob.team == Some(self.seekers) {
+                continue;
+            }
+            let found = seekers.iter().any(|&(center, hook)| {
+                (center - blob.center).norm() < 2.0 * radius
+                    || hook.is_some_and(|hook| (hook - blob.center).norm() < radius)
+            });
+            if found {
+                blob.team = Some(self.seekers);
+                self.latched.remove(&id);
+            }
+        }
+    }
+}
+
+impl GameMode for HideAndSeek {
+    fn update(
+        &mut self,
+        blobs: &mut BTreeMap<usize, Blob>,
+        _objectives: &mut [Objective],
+        _level: &Level,
+        _grid: &Grid,
+        _events: &[GameEvent],
+    ) {
+        if self.winner.is_some() {
+            return;
+        }
+        self.seek(blobs);
+        self.latch(blobs);
+        self.hiders_left = blobs.values().filter(|blob| !self.is_seeker(blob)).count();
+        self.time_left = (self.time_left - TICK_TIME).max(0.0);
+        if self.hiders_left == 0 {
+            self.winner = Some(self.seekers);
+        } else if self.time_left <= 0.0 {
+            self.winner = Some(self.seekers.opponent());
+        }
+    }
+
+    fn hostile_base_entry(&self) -> HostileEntry {
+        HostileEntry::Allow
+    }
+
+    fn winner(&self) -> Option<Winner> {
+        self.winner.map(Winner::Team)
+    }
+
+    fn camouflage(&self, id: usize) -> f32 {
+        let camouflage_time = balance::current().hide_and_seek.camouflage_time;
+        self.latched
+            .get(&id)
+            .map_or(0.0, |latch| (latch.time / camouflage_time).min(1.0))
+    }
+
+    fn time_left(&self) -> Option<f32> {
+        Some(self.time_left)
+    }
+
+    fn score(&self, _id: usize, team: Option<Team>) -> Option<String> {
+        Some(if team == Some(self.seekers) {
+            format!("Seeking, {} left", self.hiders_left)
+        } else {
+            "Hiding".to_string()
+        })
+    }
+
+    fn draw_status(&self, ctx: &mut Context) -> GameResult<()> {
+        let status = format!(
+            "{:.0}   {} hiding from {:?}",
+            self.time_left.ceil(),
+            self.hiders_left,
+            self.seekers
+        );
+        let text = graphics::Text::new(status);
+        let x = SCREEN_SIZE.0 / 2.0 - text.width(ctx) as f32 / 2.0;
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new()
+                .dest(Point2::new(x, 20.0))
+                .color(graphics::BLACK),
+        )
+    }
+}
+
+/// Whether a blob at center is up against a wall, floor or ceiling.
+fn touches_wall(center: Point2<f32>) -> bool {
+    let reach = tuning::current().blob_radius + LATCH_MARGIN;
+    center.x <= reach
+        || center.y <= reach
+        || center.x >= SCREEN_SIZE.0 - reach
+        || center.y >= SCREEN_SIZE.1 - reach
+}
@@ -0,0 +1,237 @@
+use ggez::graphics::{self, Color};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::attachment::{Anchor, Attachment};
+use crate::balance;
+use crate::grid::Grid;
+use crate::health::GameEvent;
+use crate::level::{HostileEntry, Level};
+use crate::mode::koth::{self, Contender};
+use crate::mode::{GameMode, HookDuelConfig, Winner, Zone};
+use crate::objective::Objective;
+use crate::team::Team;
+use crate::tuning;
+use crate::{Blob, HookState, DT, TICK_TIME};
+
+/// Nobody gets hurt. Hooks catch on other blobs and pull them along, and the
+/// only way to score is to slam a hooked blob into one of the zones. A slam
+/// still counts for a short while after letting go, so blobs can be flung.
+pub struct HookDuel {
+    zones: Vec<Zone>,
+    // Who gets the point if each pulled blob hits a zone now, and for how
+    // many more seconds.
+    credit: BTreeMap<usize, (usize, f32)>,
+    // Blobs that were in a zone last tick, so slams count once on the way in.
+    in_zone: HashSet<usize>,
+    scores: BTreeMap<Contender, u32>,
+    score_limit: u32,
+    time_left: f32,
+    over: bool,
+}
+
+impl HookDuel {
+    pub fn new(config: &HookDuelConfig) -> HookDuel {
+        HookDuel {
+            zones: config.zones.clone(),
+            credit: BTreeMap::new(),
+            in_zone: HashSet::new(),
+            scores: BTreeMap::new(),
+            score_limit: config.score_limit,
+            time_left: config.time_limit,
+            over: false,
+        }
+    }
+
+    /// Catch blobs with traveling hooks and keep hooks on the blobs they
+    /// caught. Hooks on blobs that are gone come loose.
+    fn update_hooks(&mut self, blobs: &mut BTreeMap<usize, Blob>, grid: &Grid) {
+        let targets: BTreeMap<usize, (Contender, Point2<f32>)> = blobs
+            .iter()
+            .map(|(&id, blob)| (id, (Contender::of(id, blob), blob.center)))
+            .collect();
+        let radius = tuning::current().blob_radius;
+        for (&id, blob) in blobs.iter_mut() {
+            let contender = Contender::of(id, blob);
+            match blob.hook {
+                HookState::Traveling(hook_point, _) => {
+                    // Of the blobs the hook touches, the lowest id is caught.
+                    let caught = grid.lowest_near(hook_point, radius, |target, center| {
+                        targets
+                            .get(&target)
+                            .is_some_and(|&(other, _)| other != contender)
+                            && (hook_point - center).norm() < radius
+                    });
+                    if let Some(target) = caught {
+                        let center = targets[&target].1;
+                        blob.hook =
+                            HookState::Attached(Attachment::rope(Anchor::Blob(target), center));
+                    }
+                }
+                HookState::Attached(ref mut attachment) => {
+                    if let Anchor::Blob(target) = attachment.anchor {
+                        match targets.get(&target) {
+                            Some(&(_, center)) => attachment.point = center,
+                            None => blob.hook = HookState::None,
+                        }
+                    }
+                }
+                HookState::None => {}
+            }
+        }
+    }
+
+    /// Pull hooked blobs towards the blobs hooking them, as hard as the
+    /// rope pulls the other way.
+    fn pull(&mut self, blobs: &mut BTreeMap<usize, Blob>) {
+        let stats = balance::current().hook_duel;
+        let towing: Vec<(usize, usize, Attachment, Point2<f32>)> = blobs
+            .iter()
+            .filter_map(|(&hooker, blob)| match blob.hook {
+                HookState::Attached(attachment) => match attachment.anchor {
+                    Anchor::Blob(target) => Some((hooker, target, attachment, blob.center)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        for (hooker, target, attachment, hooker_center) in towing {
+            if let Some(blob) = blobs.get_mut(&target) {
+                blob.vel += stats.tow_mass_ratio * attachment.reaction(hooker_center) * DT;
+                self.credit.insert(target, (hooker, stats.credit_time));
+            }
+        }
+    }
+
+    /// Score for blobs entering a zone fast enough while pulled or flung.
+    fn score_slams(&mut self, blobs: &mut BTreeMap<usize, Blob>) {
+        let stats = balance::current().hook_duel;
+        let zones = &self.zones;
+        let in_zone: HashSet<usize> = blobs
+            .iter()
+            .filter(|(_, blob)| zones.iter().any(|zone| zone.contains(blob.center)))
+            .map(|(&id, _)| id)
+            .collect();
+        let mut slams = Vec::new();
+        for (&target, &(hooker, _)) in &self.credit {
+            let slammed = blobs.get(&target).is_some_and(|blob| {
+                blob.vel.norm() >= stats.slam_speed
+                    && in_zone.contains(&target)
+                    && !self.in_zone.contains(&target)
+            });
+            if slammed {
+                slams.push((target, hooker));
+            }
+        }
+        for (target, hooker) in slams {
+            self.credit.remove(&target);
+            if let Some(blob) = blobs.get_mut(&hooker) {
+                *self.scores.entry(Contender::of(hooker, blob)).or_insert(0) += 1;
+                if let HookState::Attached(Attachment {
+                    anchor: Anchor::Blob(_),
+                    ..
+                }) = blob.hook
+                {
+                    blob.hook = HookState::None;
+                }
+            }
+        }
+        self.in_zone = in_zone;
+        for (_, time) in self.credit.values_mut() {
+            *time -= TICK_TIME;
+        }
+        self.credit.retain(|_, &mut (_, time)| time > 0.0);
+    }
+
+    /// Contenders and slams, best first.
+    fn standings(&self) -> Vec<(Contender, u32)> {
+        let mut standings: Vec<_> = self.scores.iter().map(|(&c, &s)| (c, s)).collect();
+        standings.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        standings
+    }
+}
+
+impl GameMode for HookDuel {
+    fn update(
+        &mut self,
+        blobs: &mut BTreeMap<usize, Blob>,
+        _objectives: &mut [Objective],
+        _level: &Level,
+        grid: &Grid,
+        _events: &[GameEvent],
+    ) {
+        self.update_hooks(blobs, grid);
+        self.pull(blobs);
+        if self.over {
+            return;
+        }
+        for (&id, blob) in blobs.iter() {
+            self.scores.entry(Contender::of(id, blob)).or_insert(0);
+        }
+        self.score_slams(blobs);
+        self.time_left = (self.time_left - TICK_TIME).max(0.0);
+        self.over = self.time_left <= 0.0 || self.scores.values().any(|&s| s >= self.score_limit);
+    }
+
+    fn hostile_base_entry(&self) -> HostileEntry {
+        HostileEntry::Allow
+    }
+
+    fn allows_damage(&self) -> bool {
+        false
+    }
+
+    fn winner(&self) -> Option<Winner> {
+        if !self.over {
+            return None;
+        }
+        match self.standings().as_slice() {
+            [(_, first), (_, second), ..] if first == second => Some(Winner::Draw),
+            [(Contender::Team(team), _), ..] => Some(Winner::Team(*team)),
+            [(Contender::Blob(id), _), ..] => Some(Winner::Blob(*id)),
+            [] => Some(Winner::Draw),
+        }
+    }
+
+    fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let color = Color::new(0.9, 0.45, 0.1, 0.3);
+        for zone in &self.zones {
+            let mesh = koth::zone_mesh(ctx, zone, color)?;
+            graphics::draw(ctx, &mesh, graphics::DrawParam::new())?;
+        }
+        Ok(())
+    }
+
+    fn time_left(&self) -> Option<f32> {
+        Some(self.time_left)
+    }
+
+    fn score(&self, id: usize, team: Option<Team>) -> Option<String> {
+        let contender = match team {
+            Some(team) => Contender::Team(team),
+            None => Contender::Blob(id),
+        };
+        let slams = self.scores.get(&contender).cloned().unwrap_or(0);
+        Some(format!("{} / {} slams", slams, self.score_limit))
+    }
+
+    fn draw_status(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut lines = vec![format!(
+            "{:.0}   (slam limit {})",
+            self.time_left.ceil(),
+            self.score_limit
+        )];
+        for (contender, slams) in self.standings() {
+            lines.push(format!("{}: {}", contender.name(), slams));
+        }
+        let text = graphics::Text::new(lines.join("\n"));
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new()
+                .dest(Point2::new(10.0, 10.0))
+                .color(graphics::BLACK),
+        )
+    }
+}
@@ -0,0 +1,247 @@
+use ggez::graphics::{self, Color};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::grid::Grid;
+use crate::health::GameEvent;
+use crate::level::{HostileEntry, Level};
+use crate::mode::{GameMode, KingOfTheHillConfig, Winner, Zone};
+use crate::objective::Objective;
+use crate::team::{Team, TeamColor};
+use crate::{Blob, SCREEN_SIZE, TICK_TIME};
+
+/// Who is in the zone.
+pub fn zone_state(zone: &Zone, blobs: &BTreeMap<usize, Blob>) -> ZoneState {
+    let mut inside = blobs
+        .iter()
+        .filter(|(_, blob)| zone.contains(blob.center))
+        .map(|(&id, blob)| Contender::of(id, blob));
+    match inside.next() {
+        None => ZoneState::Empty,
+        Some(first) if inside.all(|other| other == first) => ZoneState::Held(first),
+        Some(_) => ZoneState::Contested,
+    }
+}
+
+/// The zone filled with the given color.
+pub fn zone_mesh(ctx: &mut Context, zone: &Zone, color: Color) -> GameResult<graphics::Mesh> {
+    match zone {
+        Zone::Circle { center, radius } => graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::fill(),
+            Point2::new(center.0, center.1),
+            *radius,
+            0.5,
+            color,
+        ),
+        Zone::Polygon(points) => {
+            let points: Vec<_> = points.iter().map(|&(x, y)| Point2::new(x, y)).collect();
+            graphics::Mesh::new_polygon(ctx, graphics::DrawMode::fill(), &points, color)
+        }
+    }
+}
+
+/// Who scores from holding the zone, or from slams in a hook duel. Blobs without a team score for
+/// themselves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Contender {
+    Team(Team),
+    Blob(usize),
+}
+
+impl Contender {
+    pub fn of(id: usize, blob: &Blob) -> Contender {
+        match blob.team {
+            Some(team) => Contender::Team(team),
+            None => Contender::Blob(id),
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Contender::Team(team) => team.color(),
+            Contender::Blob(_) => Color::from_rgb(128, 128, 128),
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Contender::Team(team) => format!("{:?}", team),
+            Contender::Blob(id) => format!("Blob {}", id),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZoneState {
+    Empty,
+    Held(Contender),
+    /// More than one contender is inside, so nobody scores.
+    Contested,
+}
+
+/// Contenders score points for every second they are alone in the zone.
+pub struct KingOfTheHill {
+    zone: Zone,
+    state: ZoneState,
+    scores: HashMap<Contender, f32>,
+    score_limit: f32,
+    points_per_second: f32,
+    time_left: f32,
+    over: bool,
+}
+
+impl KingOfTheHill {
+    pub fn new(config: &KingOfTheHillConfig) -> KingOfTheHill {
+        KingOfTheHill {
+            zone: config.zone.clone(),
+            state: ZoneState::Empty,
+            scores: HashMap::new(),
+            score_limit: config.score_limit,
+            points_per_second: config.points_per_second,
+            time_left: config.time_limit,
+            over: false,
+        }
+    }
+
+    /// Contenders and scores, best first.
+    fn standings(&self) -> Vec<(Contender, f32)> {
+        let mut standings: Vec<_> = self.scores.iter().map(|(&c, &s)| (c, s)).collect();
+        standings.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        standings
+    }
+}
+
+impl GameMode for KingOfTheHill {
+    fn update(
+        &mut self,
+        blobs: &mut BTreeMap<usize, Blob>,
+        _objectives: &mut [Objective],
+        _level: &Level,
+        _grid: &Grid,
+        _events: &[GameEvent],
+    ) {
+        if self.over {
+            return;
+        }
+        self.state = zone_state(&self.zone, blobs);
+        if let ZoneState::Held(holder) = self.state {
+            let score = self.scores.entry(holder).or_insert(0.0);
+            *score = (*score + self.points_per_second * TICK_TIME).min(self.score_limit);
+        }
+        self.time_left = (self.time_left - TICK_TIME).max(0.0);
+        self.over = self.time_left <= 0.0 || self.scores.values().any(|&s| s >= self.score_limit);
+    }
+
+    fn hostile_base_entry(&self) -> HostileEntry {
+        HostileEntry::Allow
+    }
+
+    fn winner(&self) -> Option<Winner> {
+        if !self.over {
+            return None;
+        }
+        match self.standings().as_slice() {
+            [(_, first), (_, second), ..] if first == second => Some(Winner::Draw),
+            [(Contender::Team(team), _), ..] => Some(Winner::Team(*team)),
+            [(Contender::Blob(id), _), ..] => Some(Winner::Blob(*id)),
+            [] => Some(Winner::Draw),
+        }
+    }
+
+    fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut color = match self.state {
+            ZoneState::Empty => Color::from_rgb(200, 200, 200),
+            ZoneState::Held(holder) => holder.color(),
+            ZoneState::Contested => Color::from_rgb(250, 150, 30),
+        };
+        color.a = 0.3;
+        let zone = zone_mesh(ctx, &self.zone, color)?;
+        graphics::draw(ctx, &zone, graphics::DrawParam::new())
+    }
+
+    fn time_left(&self) -> Option<f32> {
+        Some(self.time_left)
+    }
+
+    fn score(&self, id: usize, team: Option<Team>) -> Option<String> {
+        let contender = match team {
+            Some(team) => Contender::Team(team),
+            None => Contender::Blob(id),
+        };
+        let score = self.scores.get(&contender).cloned().unwrap_or(0.0);
+        Some(format!(
+            "{:.0} / {:.0} points",
+            score.floor(),
+            self.score_limit
+        ))
+    }
+
+    fn draw_status(&self, ctx: &mut Context) -> GameResult<()> {
+        let y = draw_score_bars(ctx, &self.standings(), self.score_limit)?;
+        let status = match self.state {
+            ZoneState::Contested => format!("{:.0}   CONTESTED", self.time_left.ceil()),
+            _ => format!("{:.0}", self.time_left.ceil()),
+        };
+        let text = graphics::Text::new(status);
+        let x = SCREEN_SIZE.0 / 2.0 - text.width(ctx) as f32 / 2.0;
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new()
+                .dest(Point2::new(x, y))
+                .color(graphics::BLACK),
+        )
+    }
+}
+
+/// Draw a bar for each contender's score at the top of the screen, named on
+/// the right. Returns the height below the bars.
+pub fn draw_score_bars(
+    ctx: &mut Context,
+    standings: &[(Contender, f32)],
+    score_limit: f32,
+) -> GameResult<f32> {
+    let bar_width = 400.0;
+    let x = SCREEN_SIZE.0 / 2.0 - bar_width / 2.0;
+    let mut y = 20.0;
+    for &(contender, score) in standings {
+        let bar = graphics::Rect::new(x, y, bar_width, 16.0);
+        draw_progress_bar(ctx, bar, score / score_limit, contender.color())?;
+        let name = graphics::Text::new(contender.name());
+        graphics::draw(
+            ctx,
+            &name,
+            graphics::DrawParam::new()
+                .dest(Point2::new(x + bar_width + 8.0, y))
+                .color(graphics::BLACK),
+        )?;
+        y += 22.0;
+    }
+    Ok(y)
+}
+
+/// Draw a bar filled the given fraction from the left.
+pub fn draw_progress_bar(
+    ctx: &mut Context,
+    bar: graphics::Rect,
+    fraction: f32,
+    color: Color,
+) -> GameResult<()> {
+    let background = graphics::Mesh::new_rectangle(
+        ctx,
+        graphics::DrawMode::fill(),
+        bar,
+        Color::from_rgb(220, 220, 220),
+    )?;
+    graphics::draw(ctx, &background, graphics::DrawParam::new())?;
+    let mut progress = bar;
+    progress.w *= fraction.clamp(0.0, 1.0);
+    if progress.w > 0.0 {
+        let filled =
+            graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), progress, color)?;
+        graphics::draw(ctx, &filled, graphics::DrawParam::new())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use ggez::{Context, GameResult};
+use std::collections::BTreeMap;
+
+pub use blobs_core::mode::*;
+
+use crate::balance;
+use crate::grid::Grid;
+use crate::health::GameEvent;
+use crate::level::{HostileEntry, Level};
+use crate::objective::Objective;
+use crate::team::Team;
+use crate::Blob;
+
+pub mod ctf;
+pub mod deathmatch;
+pub mod domination;
+pub mod duel;
+pub mod escort;
+pub mod hide_and_seek;
+pub mod hook_duel;
+pub mod koth;
+
+use ctf::CaptureTheFlag;
+use deathmatch::Deathmatch;
+use domination::Domination;
+use duel::Duel;
+use escort::Escort;
+use hide_and_seek::HideAndSeek;
+use hook_duel::HookDuel;
+use koth::KingOfTheHill;
+
+/// Set up the mode the level is played in.
+pub fn create(config: &ModeConfig, level: &Level) -> Box<dyn GameMode> {
+    match config {
+        ModeConfig::Deathmatch {
+            frag_limit,
+            time_limit,
+        } => Box::new(Deathmatch::new(*frag_limit, *time_limit)),
+        ModeConfig::Duel { best_of } => Box::new(Duel::new(
+            *best_of,
+            [
+                level.spawn_point(Some(Team::Red)),
+                level.spawn_point(Some(Team::Blue)),
+            ],
+        )),
+        ModeConfig::CaptureTheFlag {
+            capture_limit,
+            time_limit,
+        } => Box::new(CaptureTheFlag::new(*capture_limit, *time_limit)),
+        ModeConfig::Escort(config) => Box::new(Escort::new(config)),
+        ModeConfig::KingOfTheHill(config) => Box::new(KingOfTheHill::new(config)),
+        ModeConfig::HookDuel(config) => Box::new(HookDuel::new(config)),
+        ModeConfig::Domination(config) => Box::new(Domination::new(config)),
+        ModeConfig::HideAndSeek(config) => Box::new(HideAndSeek::new(config)),
+    }
+}
+
+#[derive(Debug)]
+pub enum Winner {
+    Blob(usize),
+    Team(Team),
+    Draw,
+}
+
+impl Winner {
+    pub fn announcement(&self) -> String {
+        match self {
+            Winner::Blob(id) => format!("Blob {} wins!", id),
+            Winner::Team(team) => format!("{:?} team wins!", team),
+            Winner::Draw => "Draw!".to_string(),
+        }
+    }
+}
+
+/// Rules and scoring of a match. The game state steps the simulation and
+/// lets the mode react to what happened each tick.
+pub trait GameMode {
+    /// Advance the mode one tick. The grid holds where the blobs ended up,
+    /// and events are the things that happened during the tick.
+    fn update(
+        &mut self,
+        blobs: &mut BTreeMap<usize, Blob>,
+        objectives: &mut [Objective],
+        level: &Level,
+        grid: &Grid,
+        events: &[GameEvent],
+    );
+
+    /// What happens to blobs entering another team's base.
+    fn hostile_base_entry(&self) -> HostileEntry;
+
+    /// Whether blobs get hurt at all. Without damage, nothing hurts the
+    /// blobs, not even hazards.
+    fn allows_damage(&self) -> bool {
+        true
+    }
+
+    /// Seconds added to the respawn delay of blobs that kill themselves.
+    fn suicide_respawn_penalty(&self) -> f32 {
+        balance::current().suicide.respawn_penalty
+    }
+
+    /// How well the given blob blends in with the level, from 0 for not at
+    /// all to 1 for invisible to others.
+    fn camouflage(&self, _id: usize) -> f32 {
+        0.0
+    }
+
+    /// The winner of the match, once it is over.
+    fn winner(&self) -> Option<Winner>;
+
+    /// Draw mode specific things in the world, below the blobs.
+    fn draw(&self, _ctx: &mut Context) -> GameResult<()> {
+        Ok(())
+    }
+
+    /// Draw scores, clocks and other match status on top of the world.
+    fn draw_status(&self, ctx: &mut Context) -> GameResult<()>;
+
+    /// Seconds left of the match, for modes with a clock.
+    fn time_left(&self) -> Option<f32> {
+        None
+    }
+
+    /// Score of the given blob, or of its team in team modes, for the HUD.
+    fn score(&self, _id: usize, _team: Option<Team>) -> Option<String> {
+        None
+    }
+}
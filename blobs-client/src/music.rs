@@ -0,0 +1,98 @@
+use ggez::audio::{self, SoundSource};
+use ggez::Context;
+
+use crate::settings;
+use crate::TICK_TIME;
+
+// Seconds to fade a track out, and the next one in.
+const FADE_TIME: f32 = 1.0;
+
+/// Background music, one looping track per kind of scene.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Track {
+    Menu,
+    Game,
+}
+
+impl Track {
+    fn path(self) -> &'static str {
+        match self {
+            Track::Menu => "/music/menu.ogg",
+            Track::Game => "/music/game.ogg",
+        }
+    }
+}
+
+/// Plays the track wanted by the current scene. Switching tracks fades the
+/// old one out before the new one fades in.
+pub struct Music {
+    menu: Option<audio::Source>,
+    game: Option<audio::Source>,
+    playing: Option<Track>,
+    // How far the playing track has faded in, from 0 to 1.
+    fade: f32,
+}
+
+impl Music {
+    /// Load the tracks from the resource directory. Missing tracks are
+    /// skipped, leaving silence.
+    pub fn new(ctx: &mut Context) -> Music {
+        Music {
+            menu: load(ctx, Track::Menu),
+            game: load(ctx, Track::Game),
+            playing: None,
+            fade: 0.0,
+        }
+    }
+
+    fn source(&mut self, track: Track) -> Option<&mut audio::Source> {
+        match track {
+            Track::Menu => self.menu.as_mut(),
+            Track::Game => self.game.as_mut(),
+        }
+    }
+
+    /// Call once per tick with the track that should be playing.
+    pub fn update(&mut self, wanted: Track) {
+        let step = TICK_TIME / FADE_TIME;
+        if self.playing == Some(wanted) {
+            self.fade = (self.fade + step).min(1.0);
+        } else {
+            self.fade -= step;
+            if self.fade <= 0.0 {
+                self.switch(wanted);
+            }
+        }
+        let volume = self.fade.max(0.0) * settings::current().music_volume();
+        if let Some(source) = self.playing.and_then(|track| self.source(track)) {
+            source.set_volume(volume);
+        }
+    }
+
+    fn switch(&mut self, track: Track) {
+        if let Some(source) = self.playing.and_then(|old| self.source(old)) {
+            source.stop();
+        }
+        self.playing = Some(track);
+        self.fade = 0.0;
+        if let Some(source) = self.source(track) {
+            source.set_volume(0.0);
+            if let Err(e) = source.play() {
+                log::warn!("Could not play music: {}", e);
+            }
+        }
+    }
+}
+
+fn load(ctx: &mut Context, track: Track) -> Option<audio::Source> {
+    match audio::Source::new(ctx, track.path()) {
+        Ok(mut source) => {
+            source.set_repeat(true);
+            Some(source)
+        }
+        Err(e) => {
+            log::info!("No music loaded from {}: {}", track.path(), e);
+            None
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use ggez::graphics;
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+
+use crate::hud;
+use crate::skin::Skin;
+use crate::text::{self, Effect, Style};
+use crate::tuning;
+use crate::Blob;
+
+const BAR_SIZE: (f32, f32) = (60.0, 6.0);
+
+/// Draw a blob's name and health above it, in world coordinates so the
+/// nameplate follows the blob.
+pub fn draw(ctx: &mut Context, id: usize, blob: &Blob) -> GameResult<()> {
+    let bar_top = blob.center.y - tuning::current().blob_radius - 10.0 - BAR_SIZE.1;
+    hud::draw_health_bar(
+        ctx,
+        graphics::Rect::new(
+            blob.center.x - BAR_SIZE.0 / 2.0,
+            bar_top,
+            BAR_SIZE.0,
+            BAR_SIZE.1,
+        ),
+        blob.health,
+    )?;
+    let name = format!("Blob {}", id);
+    let color = Skin::new(id, blob.team).body;
+    let style = Style::new(16.0)
+        .color(color)
+        .effect(Effect::Outline(graphics::WHITE));
+    let (width, height) = text::size(ctx, &name, style);
+    text::draw(
+        ctx,
+        &name,
+        Point2::new(blob.center.x - width / 2.0, bar_top - height - 2.0),
+        style,
+    )
+}
@@ -0,0 +1,225 @@
+use ggez::audio::SoundData;
+use ggez::graphics::{self, Color};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+use std::collections::{BTreeMap, VecDeque};
+
+pub use blobs_core::objective::{ObjectiveKind, ObjectiveSpawn};
+
+use crate::meshes;
+use crate::sound;
+use crate::team::TeamColor;
+use crate::tuning;
+use crate::{Blob, HookState, SCREEN_SIZE, TICK_TIME};
+
+const OBJECTIVE_RADIUS: f32 = 15.0;
+const TRAIL_LENGTH: usize = 20;
+const BEEP_INTERVAL: f32 = 1.0;
+
+/// Flags are in their team's color.
+pub fn color(kind: ObjectiveKind) -> Color {
+    match kind {
+        ObjectiveKind::Flag(team) => team.color(),
+        ObjectiveKind::Bomb => Color::from_rgb(250, 150, 30),
+    }
+}
+
+/// An item that blobs pick up by touching or hooking it and then carry
+/// around. Carriers drop it when they die.
+pub struct Objective {
+    pub kind: ObjectiveKind,
+    pub pos: Point2<f32>,
+    pub carrier: Option<usize>,
+    home: Point2<f32>,
+    trail: VecDeque<Point2<f32>>,
+}
+
+impl Objective {
+    pub fn new(spawn: &ObjectiveSpawn) -> Objective {
+        let home = Point2::new(spawn.pos.0, spawn.pos.1);
+        Objective {
+            kind: spawn.kind,
+            pos: home,
+            carrier: None,
+            home,
+            trail: VecDeque::with_capacity(TRAIL_LENGTH),
+        }
+    }
+
+    pub fn at_home(&self) -> bool {
+        self.carrier.is_none() && self.pos == self.home
+    }
+
+    pub fn return_home(&mut self) {
+        self.pos = self.home;
+        self.carrier = None;
+        self.trail.clear();
+    }
+
+    fn touches(&self, blob: &Blob) -> bool {
+        (blob.center - self.pos).norm() < tuning::current().blob_radius + OBJECTIVE_RADIUS
+    }
+
+    /// Find a blob that may pick up the objective and touches or hooks it. A
+    /// hook that catches the objective pulls it back to its blob.
+    fn find_carrier(&self, blobs: &mut BTreeMap<usize, Blob>) -> Option<usize> {
+        for (&id, blob) in blobs.iter_mut() {
+            if blob.health <= 0.0 || !self.kind.can_be_carried_by(blob.team) {
+                continue;
+            }
+            let hooked = match blob.hook {
+                HookState::Traveling(hook_point, _) => {
+                    (hook_point - self.pos).norm() < OBJECTIVE_RADIUS
+                }
+                _ => false,
+            };
+            if hooked {
+                blob.hook = HookState::None;
+            }
+            if hooked || self.touches(blob) {
+                return Some(id);
+            }
+        }
+        None
+    }
+
+    pub fn update(&mut self, blobs: &mut BTreeMap<usize, Blob>) {
+        // Carriers that died or left play drop the objective where they were.
+        if let Some(id) = self.carrier {
+            if blobs.get(&id).is_none_or(|blob| blob.health <= 0.0) {
+                self.carrier = None;
+                self.trail.clear();
+            }
+        }
+        if self.carrier.is_none() {
+            self.carrier = self.find_carrier(blobs);
+        }
+        // A dropped flag is returned when its own team touches it.
+        if let (ObjectiveKind::Flag(owner), None) = (self.kind, self.carrier) {
+            if !self.at_home()
+                && blobs
+                    .values()
+                    .any(|blob| blob.team == Some(owner) && self.touches(blob))
+            {
+                self.return_home();
+            }
+        }
+        if let Some(carrier) = self.carrier.and_then(|id| blobs.get(&id)) {
+            self.pos = carrier.center;
+            if self.trail.len() == TRAIL_LENGTH {
+                self.trail.pop_back();
+            }
+            self.trail.push_front(carrier.center);
+        }
+    }
+
+    /// Draw the objective, or the glow and trail on its carrier. Call before
+    /// drawing blobs so the carrier is drawn on top.
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut color = color(self.kind);
+        if self.carrier.is_none() {
+            return self.draw_item(ctx, self.pos);
+        }
+
+        let radius = tuning::current().blob_radius;
+        for (i, &p) in self.trail.iter().enumerate().skip(1) {
+            let fade = 1.0 - i as f32 / TRAIL_LENGTH as f32;
+            color.a = 0.4 * fade;
+            meshes::draw_disc(ctx, p, radius * fade, color)?;
+        }
+        color.a = 0.5;
+        meshes::draw_disc(ctx, self.pos, radius + 12.0, color)
+    }
+
+    /// Draw the item carried by a blob. Call after drawing blobs.
+    pub fn draw_carried(&self, ctx: &mut Context) -> GameResult<()> {
+        if self.carrier.is_some() {
+            let top = self.pos - Vector2::new(0.0, tuning::current().blob_radius);
+            self.draw_item(ctx, top)?;
+        }
+        Ok(())
+    }
+
+    fn draw_item(&self, ctx: &mut Context, pos: Point2<f32>) -> GameResult<()> {
+        let color = color(self.kind);
+        let item = match self.kind {
+            ObjectiveKind::Flag(_) => {
+                let top = pos - Vector2::new(0.0, 2.0 * OBJECTIVE_RADIUS);
+                graphics::MeshBuilder::new()
+                    .line(&[pos, top], 3.0, graphics::BLACK)?
+                    .polygon(
+                        graphics::DrawMode::fill(),
+                        &[
+                            top,
+                            top + Vector2::new(OBJECTIVE_RADIUS, 0.4 * OBJECTIVE_RADIUS),
+                            top + Vector2::new(0.0, 0.8 * OBJECTIVE_RADIUS),
+                        ],
+                        color,
+                    )?
+                    .build(ctx)?
+            }
+            ObjectiveKind::Bomb => return meshes::draw_disc(ctx, pos, OBJECTIVE_RADIUS, color),
+        };
+        graphics::draw(ctx, &item, graphics::DrawParam::new())
+    }
+
+    /// Draw a marker above the carrier, kept inside the screen.
+    pub fn draw_marker(&self, ctx: &mut Context) -> GameResult<()> {
+        if self.carrier.is_none() {
+            return Ok(());
+        }
+        let margin = 20.0;
+        let tip = Point2::new(
+            self.pos.x.max(margin).min(SCREEN_SIZE.0 - margin),
+            (self.pos.y - tuning::current().blob_radius - 20.0)
+                .max(margin)
+                .min(SCREEN_SIZE.1 - margin),
+        );
+        let marker = graphics::Mesh::new_polygon(
+            ctx,
+            graphics::DrawMode::fill(),
+            &[
+                tip,
+                tip + Vector2::new(10.0, -16.0),
+                tip + Vector2::new(-10.0, -16.0),
+            ],
+            color(self.kind),
+        )?;
+        graphics::draw(ctx, &marker, graphics::DrawParam::new())
+    }
+}
+
+/// Periodic positional beeping from every objective carrier.
+pub struct CarrierCues {
+    beep: SoundData,
+    beep_timer: f32,
+}
+
+impl CarrierCues {
+    pub fn new() -> CarrierCues {
+        CarrierCues {
+            beep: sound::tone(880.0, 0.12, 0.5),
+            beep_timer: 0.0,
+        }
+    }
+
+    /// Beep from each carried objective, heard from the listener position.
+    /// Call once per tick.
+    pub fn update(
+        &mut self,
+        ctx: &mut Context,
+        objectives: &[Objective],
+        listener: Point2<f32>,
+    ) -> GameResult<()> {
+        self.beep_timer -= TICK_TIME;
+        if self.beep_timer > 0.0 {
+            return Ok(());
+        }
+        self.beep_timer = BEEP_INTERVAL;
+
+        for objective in objectives.iter().filter(|o| o.carrier.is_some()) {
+            sound::play_at(ctx, &self.beep, 1.0, objective.pos, listener)?;
+        }
+        Ok(())
+    }
+}
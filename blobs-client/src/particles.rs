@@ -0,0 +1,188 @@
+use ggez::graphics::spritebatch::SpriteBatch;
+use ggez::graphics::{self, Color};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+
+use crate::TICK_TIME;
+
+// Size in pixels of the particle image, drawn scaled.
+const IMAGE_SIZE: u16 = 16;
+// World units per second squared.
+const GRAVITY: f32 = 400.0;
+
+/// How a group of particles is thrown out. Features describe their effect
+/// with an emitter and emit it into the shared particles.
+#[derive(Clone, Copy, Debug)]
+pub struct Emitter {
+    pub count: usize,
+    /// Top speed, each particle gets between 30% and all of it.
+    pub speed: f32,
+    /// Angle in radians around the emit direction that the particles fly
+    /// out over, TAU for every direction.
+    pub spread: f32,
+    /// Smallest and largest particle size.
+    pub size: (f32, f32),
+    /// Shortest and longest particle lifetime in seconds.
+    pub lifetime: (f32, f32),
+    /// Fraction of the full gravity that pulls the particles down.
+    pub gravity: f32,
+    pub color: Color,
+}
+
+impl Emitter {
+    pub fn color(self, color: Color) -> Emitter {
+        Emitter { color, ..self }
+    }
+}
+
+/// Debris of a dead blob, to be colored after its team.
+pub const DEATH_BURST: Emitter = Emitter {
+    count: 40,
+    speed: 300.0,
+    spread: std::f32::consts::TAU,
+    size: (4.0, 12.0),
+    lifetime: (0.5, 1.5),
+    gravity: 1.0,
+    color: Color::new(0.5, 0.5, 0.5, 1.0),
+};
+
+/// Sparks where a hook catches on a wall.
+pub const HOOK_SPARKS: Emitter = Emitter {
+    count: 12,
+    speed: 250.0,
+    spread: 1.5,
+    size: (2.0, 4.0),
+    lifetime: (0.1, 0.3),
+    gravity: 0.5,
+    color: Color::new(1.0, 0.8, 0.2, 1.0),
+};
+
+/// Dust kicked up where a blob bounces off a wall.
+pub const WALL_DUST: Emitter = Emitter {
+    count: 10,
+    speed: 60.0,
+    spread: 2.5,
+    size: (6.0, 14.0),
+    lifetime: (0.4, 0.8),
+    gravity: -0.05,
+    color: Color::new(0.6, 0.55, 0.5, 0.5),
+};
+
+/// Streaks left in the air by a blob rushing past the player, thrown out
+/// behind it.
+pub const WHOOSH: Emitter = Emitter {
+    count: 8,
+    speed: 120.0,
+    spread: 0.6,
+    size: (3.0, 8.0),
+    lifetime: (0.2, 0.4),
+    gravity: 0.0,
+    color: Color::new(0.85, 0.85, 0.9, 0.6),
+};
+
+struct Particle {
+    pos: Point2<f32>,
+    vel: Vector2<f32>,
+    gravity: f32,
+    rotation: f32,
+    spin: f32,
+    size: f32,
+    color: Color,
+    life: f32,
+    lifetime: f32,
+}
+
+/// Short lived bits flying around, such as the debris of a dead blob. All
+/// particles are drawn in one instanced draw call, each with its own color,
+/// scale and rotation.
+pub struct Particles {
+    particles: Vec<Particle>,
+    batch: SpriteBatch,
+}
+
+impl Particles {
+    pub fn new(ctx: &mut Context) -> GameResult<Particles> {
+        Ok(Particles {
+            particles: Vec::new(),
+            batch: SpriteBatch::new(dot_image(ctx)?),
+        })
+    }
+
+    /// Throw out the emitter's particles from pos, spread around direction.
+    pub fn emit(&mut self, emitter: &Emitter, pos: Point2<f32>, direction: Vector2<f32>) {
+        let base_angle = direction.y.atan2(direction.x);
+        for _ in 0..emitter.count {
+            let angle = base_angle + emitter.spread * (rand::random::<f32>() - 0.5);
+            let speed = emitter.speed * (0.3 + 0.7 * rand::random::<f32>());
+            let lifetime = between(emitter.lifetime);
+            self.particles.push(Particle {
+                pos,
+                vel: speed * Vector2::new(angle.cos(), angle.sin()),
+                gravity: emitter.gravity * GRAVITY,
+                rotation: angle,
+                spin: 10.0 * (rand::random::<f32>() - 0.5),
+                size: between(emitter.size),
+                color: emitter.color,
+                life: lifetime,
+                lifetime,
+            });
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    /// Call once per tick.
+    pub fn update(&mut self) {
+        for particle in self.particles.iter_mut() {
+            particle.vel.y += particle.gravity * TICK_TIME;
+            particle.pos += particle.vel * TICK_TIME;
+            particle.rotation += particle.spin * TICK_TIME;
+            particle.life -= TICK_TIME;
+        }
+        self.particles.retain(|particle| particle.life > 0.0);
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        if self.particles.is_empty() {
+            return Ok(());
+        }
+        self.batch.clear();
+        for particle in &self.particles {
+            let fade = particle.life / particle.lifetime;
+            let scale = particle.size / f32::from(IMAGE_SIZE);
+            self.batch.add(
+                graphics::DrawParam::new()
+                    .dest(particle.pos)
+                    .offset(Point2::new(0.5, 0.5))
+                    .rotation(particle.rotation)
+                    .scale(Vector2::new(scale, scale))
+                    .color(Color {
+                        a: particle.color.a * fade,
+                        ..particle.color
+                    }),
+            );
+        }
+        graphics::draw(ctx, &self.batch, graphics::DrawParam::new())
+    }
+}
+
+/// Random value in the range.
+fn between((low, high): (f32, f32)) -> f32 {
+    low + (high - low) * rand::random::<f32>()
+}
+
+/// White dot with soft edges, tinted per particle.
+fn dot_image(ctx: &mut Context) -> GameResult<graphics::Image> {
+    let radius = f32::from(IMAGE_SIZE) / 2.0;
+    let mut rgba = Vec::with_capacity(4 * usize::from(IMAGE_SIZE) * usize::from(IMAGE_SIZE));
+    for y in 0..IMAGE_SIZE {
+        for x in 0..IMAGE_SIZE {
+            let offset = Vector2::new(f32::from(x) + 0.5 - radius, f32::from(y) + 0.5 - radius);
+            let alpha = (radius - offset.norm()).clamp(0.0, 1.0);
+            rgba.extend_from_slice(&[255, 255, 255, (alpha * 255.0) as u8]);
+        }
+    }
+    graphics::Image::from_rgba8(ctx, IMAGE_SIZE, IMAGE_SIZE, &rgba)
+}
@@ -0,0 +1,349 @@
+use ggez::conf::NumSamples;
+use ggez::filesystem;
+use ggez::graphics::{self, Canvas, EmptyConst, FilterMode, Image, ImageFormat, Shader, Vertex};
+use ggez::input::keyboard::{self, KeyCode};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{SCREEN_SIZE, TICK_TIME};
+
+// Folder in the writable user directory where photos are saved.
+const PHOTO_DIR: &str = "/photos";
+// Photos are this many times the window resolution, rendered at SUPERSAMPLE
+// times that and scaled down for smooth edges.
+const EXPORT_SCALE: f32 = 2.0;
+const SUPERSAMPLE: u16 = 2;
+// Largest canvas side, within what graphics cards take as a texture.
+const MAX_CANVAS_SIZE: u16 = 8192;
+
+// World units per second at zoom 1, radians per second and zoom factor per
+// second.
+const PAN_SPEED: f32 = 600.0;
+const ROLL_SPEED: f32 = 1.0;
+const ZOOM_SPEED: f32 = 2.0;
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 8.0;
+
+// Darkness of the vignette in the corners, and how far from the middle it
+// starts, as a fraction of the distance to the corners.
+const VIGNETTE_ALPHA: f32 = 0.6;
+const VIGNETTE_START: f32 = 0.5;
+const VIGNETTE_SEGMENTS: usize = 48;
+
+const VERTEX_SHADER: &[u8] = b"#version 150 core
+
+in vec2 a_Pos;
+in vec2 a_Uv;
+
+in vec4 a_Src;
+in vec4 a_TCol1;
+in vec4 a_TCol2;
+in vec4 a_TCol3;
+in vec4 a_TCol4;
+in vec4 a_Color;
+
+layout (std140) uniform Globals {
+    mat4 u_MVP;
+};
+
+out vec2 v_Uv;
+out vec4 v_Color;
+
+void main() {
+    v_Uv = a_Uv * a_Src.zw + a_Src.xy;
+    v_Color = a_Color;
+    mat4 instance_transform = mat4(a_TCol1, a_TCol2, a_TCol3, a_TCol4);
+    gl_Position = u_MVP * instance_transform * vec4(a_Pos, 0.0, 1.0);
+}
+";
+
+// Depth of field: sharp around the middle of the picture, more and more
+// blurred towards the edges. The blur is sized relative to the picture, so
+// it looks the same at any resolution.
+const BLUR_SHADER: &[u8] = b"#version 150 core
+
+uniform sampler2D t_Texture;
+in vec2 v_Uv;
+in vec4 v_Color;
+out vec4 Target0;
+
+layout (std140) uniform Globals {
+    mat4 u_MVP;
+};
+
+const float FOCUS = 0.3;
+const float MAX_BLUR = 0.008;
+const int TAPS = 24;
+
+void main() {
+    float distance = length(v_Uv - vec2(0.5)) * 2.0;
+    float radius = MAX_BLUR * smoothstep(FOCUS, 1.2, distance);
+    vec4 sum = vec4(0.0);
+    for (int i = 0; i < TAPS; i++) {
+        // Spread over a disc along a golden angle spiral.
+        float r = sqrt((float(i) + 0.5) / float(TAPS)) * radius;
+        float angle = float(i) * 2.39996;
+        sum += texture(t_Texture, v_Uv + r * vec2(cos(angle), sin(angle)));
+    }
+    Target0 = sum / float(TAPS) * v_Color;
+}
+";
+
+/// Where a free camera looks, in photo mode and replays.
+pub struct Camera {
+    pub center: Point2<f32>,
+    pub zoom: f32,
+    roll: f32,
+}
+
+impl Camera {
+    /// Looking at the whole level, like the match does.
+    pub fn new() -> Camera {
+        Camera {
+            center: Point2::new(SCREEN_SIZE.0 / 2.0, SCREEN_SIZE.1 / 2.0),
+            zoom: 1.0,
+            roll: 0.0,
+        }
+    }
+
+    /// Move the camera for one tick. Each of pan, roll and zoom goes from
+    /// -1 to 1 for how much of its full speed to go at, with positive zoom
+    /// zooming in.
+    pub fn steer(&mut self, pan: Vector2<f32>, roll: f32, zoom: f32) {
+        // Pan along the screen, whichever way the camera is rolled.
+        let (sin, cos) = (-self.roll).sin_cos();
+        let pan = Vector2::new(cos * pan.x - sin * pan.y, sin * pan.x + cos * pan.y);
+        self.center += PAN_SPEED * TICK_TIME / self.zoom * pan;
+        self.roll += roll * ROLL_SPEED * TICK_TIME;
+        self.zoom *= ZOOM_SPEED.powf(zoom * TICK_TIME);
+        self.zoom = self.zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    /// Transform from world to screen coordinates, putting the camera
+    /// center in the middle of the screen.
+    pub fn transform(&self) -> graphics::DrawParam {
+        let middle = Point2::new(SCREEN_SIZE.0 / 2.0, SCREEN_SIZE.1 / 2.0);
+        graphics::DrawParam::new()
+            .dest(middle - self.center.coords)
+            .offset(self.center)
+            .rotation(self.roll)
+            .scale(Vector2::new(self.zoom, self.zoom))
+    }
+}
+
+/// A frozen match seen through a free camera, without the HUD, to take
+/// pictures of. The match draws its world through `draw` and `export`.
+pub struct PhotoMode {
+    camera: Camera,
+    depth_of_field: bool,
+    vignette: bool,
+    show_hint: bool,
+    blur: Shader<EmptyConst>,
+    // The view on screen before the effects, when there are any.
+    canvas: Canvas,
+}
+
+impl PhotoMode {
+    pub fn new(ctx: &mut Context) -> GameResult<PhotoMode> {
+        let blur = Shader::from_u8(ctx, VERTEX_SHADER, BLUR_SHADER, EmptyConst, "Blur", None)?;
+        Ok(PhotoMode {
+            camera: Camera::new(),
+            depth_of_field: false,
+            vignette: false,
+            show_hint: true,
+            blur,
+            canvas: Canvas::with_window_size(ctx)?,
+        })
+    }
+
+    /// Move the camera with the held keys, once per tick. The view for the
+    /// effects follows the window's size.
+    pub fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let (width, height) = graphics::drawable_size(ctx);
+        let image = self.canvas.image();
+        if (image.width(), image.height()) != (width as u16, height as u16) {
+            self.canvas = Canvas::with_window_size(ctx)?;
+        }
+        let held = |keys: &[KeyCode]| keys.iter().any(|&key| keyboard::is_key_pressed(ctx, key));
+        let axis = |minus: &[KeyCode], plus: &[KeyCode]| {
+            held(plus) as i8 as f32 - held(minus) as i8 as f32
+        };
+        let pan = Vector2::new(
+            axis(&[KeyCode::Left, KeyCode::A], &[KeyCode::Right, KeyCode::D]),
+            axis(&[KeyCode::Up, KeyCode::W], &[KeyCode::Down, KeyCode::S]),
+        );
+        let roll = axis(&[KeyCode::Q], &[KeyCode::E]);
+        let zoom = axis(&[KeyCode::X], &[KeyCode::Z]);
+        self.camera.steer(pan, roll, zoom);
+        Ok(())
+    }
+
+    /// Where the camera looks and how far it's zoomed in.
+    pub fn camera(&self) -> (Point2<f32>, f32) {
+        (self.camera.center, self.camera.zoom)
+    }
+
+    /// Toggle the effects and the hint.
+    pub fn key_down(&mut self, keycode: KeyCode) {
+        match keycode {
+            KeyCode::F => self.depth_of_field = !self.depth_of_field,
+            KeyCode::V => self.vignette = !self.vignette,
+            KeyCode::H => self.show_hint = !self.show_hint,
+            KeyCode::R => self.camera = Camera::new(),
+            _ => {}
+        }
+    }
+
+    /// Draw the world through the camera, with the chosen effects.
+    pub fn draw(
+        &self,
+        ctx: &mut Context,
+        draw_world: impl FnOnce(&mut Context) -> GameResult<()>,
+    ) -> GameResult<()> {
+        if self.depth_of_field {
+            graphics::set_canvas(ctx, Some(&self.canvas));
+            let drawn = self.draw_world(ctx, draw_world);
+            graphics::set_canvas(ctx, None);
+            drawn?;
+            self.draw_effects(ctx, &self.canvas)?;
+        } else {
+            self.draw_world(ctx, draw_world)?;
+            if self.vignette {
+                draw_vignette(ctx)?;
+            }
+        }
+        if self.show_hint {
+            let text = graphics::Text::new(
+                "PHOTO - WASD move, Z/X zoom, Q/E roll, R reset, F depth of field, \
+                 V vignette, H hide this, Space save, Esc leave",
+            );
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::new()
+                    .dest(Point2::new(10.0, 10.0))
+                    .color(graphics::BLACK),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Render the view at a higher resolution than the window and save it
+    /// as a PNG in the user data directory. Returns the saved file's path
+    /// there.
+    pub fn export(
+        &self,
+        ctx: &mut Context,
+        draw_world: impl FnOnce(&mut Context) -> GameResult<()>,
+    ) -> GameResult<String> {
+        let (width, height) = graphics::drawable_size(ctx);
+        let pixels_per_unit = graphics::window(ctx).get_hidpi_factor() as f32 * EXPORT_SCALE;
+        let size = |logical: f32| {
+            let limit = f32::from(MAX_CANVAS_SIZE / SUPERSAMPLE);
+            (logical * pixels_per_unit).clamp(1.0, limit) as u16
+        };
+        let (width, height) = (size(width), size(height));
+
+        let mut large = Canvas::new(
+            ctx,
+            width * SUPERSAMPLE,
+            height * SUPERSAMPLE,
+            NumSamples::One,
+        )?;
+        large.set_filter(FilterMode::Linear);
+        graphics::set_canvas(ctx, Some(&large));
+        let drawn = self.draw_world(ctx, draw_world);
+        graphics::set_canvas(ctx, None);
+        drawn?;
+
+        let photo = Canvas::new(ctx, width, height, NumSamples::One)?;
+        graphics::set_canvas(ctx, Some(&photo));
+        graphics::clear(ctx, graphics::WHITE);
+        let drawn = self.draw_effects(ctx, &large);
+        graphics::set_canvas(ctx, None);
+        drawn?;
+
+        // Canvas rows come out bottom up.
+        let row_len = usize::from(width) * 4;
+        let pixels: Vec<u8> = photo
+            .image()
+            .to_rgba8(ctx)?
+            .chunks(row_len)
+            .rev()
+            .flatten()
+            .cloned()
+            .collect();
+        let image = Image::from_rgba8(ctx, width, height, &pixels)?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = format!("{}/photo-{}.png", PHOTO_DIR, timestamp);
+        filesystem::create_dir(ctx, PHOTO_DIR)?;
+        image.encode(ctx, ImageFormat::Png, &path)?;
+        Ok(path)
+    }
+
+    fn draw_world(
+        &self,
+        ctx: &mut Context,
+        draw_world: impl FnOnce(&mut Context) -> GameResult<()>,
+    ) -> GameResult<()> {
+        graphics::clear(ctx, graphics::WHITE);
+        graphics::push_transform(ctx, Some(self.camera.transform().to_matrix()));
+        graphics::apply_transformations(ctx)?;
+        let drawn = draw_world(ctx);
+        graphics::pop_transform(ctx);
+        graphics::apply_transformations(ctx)?;
+        drawn
+    }
+
+    /// Draw the rendered view to fill the screen, through the effects.
+    fn draw_effects(&self, ctx: &mut Context, view: &Canvas) -> GameResult<()> {
+        let screen = graphics::screen_coordinates(ctx);
+        let scale = Vector2::new(
+            screen.w / f32::from(view.image().width()),
+            screen.h / f32::from(view.image().height()),
+        );
+        let param = graphics::DrawParam::new()
+            .dest(Point2::new(screen.x, screen.y))
+            .scale(scale);
+        if self.depth_of_field {
+            let _lock = graphics::use_shader(ctx, &self.blur);
+            graphics::draw(ctx, view, param)?;
+        } else {
+            graphics::draw(ctx, view, param)?;
+        }
+        if self.vignette {
+            draw_vignette(ctx)?;
+        }
+        Ok(())
+    }
+}
+
+/// Darken the corners of the screen, fading out towards the middle.
+fn draw_vignette(ctx: &mut Context) -> GameResult<()> {
+    let middle = Point2::new(SCREEN_SIZE.0 / 2.0, SCREEN_SIZE.1 / 2.0);
+    let corner = (SCREEN_SIZE.0.powi(2) + SCREEN_SIZE.1.powi(2)).sqrt() / 2.0;
+    let vertex = |angle: f32, radius: f32, alpha: f32| Vertex {
+        pos: [
+            middle.x + radius * angle.cos(),
+            middle.y + radius * angle.sin(),
+        ],
+        uv: [0.0, 0.0],
+        color: [0.0, 0.0, 0.0, alpha],
+    };
+    let mut vertices = Vec::with_capacity(2 * VIGNETTE_SEGMENTS);
+    let mut indices = Vec::with_capacity(6 * VIGNETTE_SEGMENTS);
+    for i in 0..VIGNETTE_SEGMENTS {
+        let angle = i as f32 * std::f32::consts::TAU / VIGNETTE_SEGMENTS as f32;
+        vertices.push(vertex(angle, VIGNETTE_START * corner, 0.0));
+        vertices.push(vertex(angle, corner, VIGNETTE_ALPHA));
+        let (inner, outer) = (2 * i as u32, 2 * i as u32 + 1);
+        let next = 2 * ((i as u32 + 1) % VIGNETTE_SEGMENTS as u32);
+        indices.extend_from_slice(&[inner, outer, next + 1, inner, next + 1, next]);
+    }
+    let mesh = graphics::Mesh::from_raw(ctx, &vertices, &indices, None)?;
+    graphics::draw(ctx, &mesh, graphics::DrawParam::new())
+}
@@ -0,0 +1,59 @@
+use ggez::graphics::{self, Color};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+
+pub use blobs_core::pickup::*;
+
+use crate::meshes;
+use crate::SCREEN_SIZE;
+
+fn color(kind: PickupKind) -> Color {
+    match kind {
+        PickupKind::SpeedBoost => Color::from_rgb(60, 180, 220),
+        PickupKind::DoubleHook => Color::from_rgb(150, 80, 200),
+        PickupKind::Shield => Color::from_rgb(240, 220, 120),
+        PickupKind::Health => Color::from_rgb(60, 180, 80),
+    }
+}
+
+fn symbol(kind: PickupKind) -> &'static str {
+    match kind {
+        PickupKind::SpeedBoost => ">",
+        PickupKind::DoubleHook => "H",
+        PickupKind::Shield => "S",
+        PickupKind::Health => "+",
+    }
+}
+
+/// Draw the pickups that can be collected.
+pub fn draw(ctx: &mut Context, pickups: &Pickups) -> GameResult<()> {
+    for (kind, pos) in pickups.available() {
+        meshes::draw_disc(ctx, pos, PICKUP_RADIUS, color(kind))?;
+        let symbol = graphics::Text::new(symbol(kind));
+        let dest = Point2::new(
+            pos.x - symbol.width(ctx) as f32 / 2.0,
+            pos.y - symbol.height(ctx) as f32 / 2.0,
+        );
+        graphics::draw(
+            ctx,
+            &symbol,
+            graphics::DrawParam::new().dest(dest).color(graphics::BLACK),
+        )?;
+    }
+    Ok(())
+}
+
+/// List the timed effects of a blob in the bottom left corner.
+pub fn draw_effects(ctx: &mut Context, effects: &Effects) -> GameResult<()> {
+    let mut dest = Point2::new(10.0, SCREEN_SIZE.1 - 30.0);
+    for (kind, time_left) in effects.active() {
+        let text = graphics::Text::new(format!("{:?} {:.0}", kind, time_left.ceil()));
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new().dest(dest).color(color(kind)),
+        )?;
+        dest.y -= 20.0;
+    }
+    Ok(())
+}
@@ -0,0 +1,283 @@
+use ggez::filesystem;
+use ggez::Context;
+use nalgebra::Point2;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::collision::wall_blob_collision;
+use crate::health::GameEvent;
+use crate::{Blob, HookState, DT, TICK_TIME};
+
+// File in the user data directory the profile is kept in.
+const FILE_NAME: &str = "profile.ron";
+
+/// How the player has moved over all their matches on this computer this
+/// season, along with the seasons before.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Season the stats are from, see season_id. Empty in profiles saved
+    /// before there were seasons.
+    pub season: String,
+    /// World units travelled while hanging on the rope.
+    pub rope_distance: f32,
+    /// Seconds spent touching neither walls nor floor.
+    pub airtime: f32,
+    /// Fastest speed reached, in world units per second.
+    pub top_speed: f32,
+    /// Seconds played on each map, by name.
+    pub maps: HashMap<String, f32>,
+    /// Stats of the earlier seasons, oldest first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub past_seasons: Vec<Profile>,
+}
+
+impl Profile {
+    /// Load the saved profile, or start a new one.
+    pub fn load(ctx: &Context) -> Profile {
+        load_from(&path(ctx))
+    }
+
+    /// Names of the most played maps, most played first.
+    pub fn favorite_maps(&self, count: usize) -> Vec<(&str, f32)> {
+        let mut maps: Vec<(&str, f32)> = self
+            .maps
+            .iter()
+            .map(|(name, &time)| (name.as_str(), time))
+            .collect();
+        maps.sort_by(|a, b| b.1.total_cmp(&a.1));
+        maps.truncate(count);
+        maps
+    }
+
+    /// Archive the stats and start over if they are from an earlier season.
+    /// Stats from before there were seasons count towards this one.
+    fn start_season(&mut self, season: &str) {
+        if self.season == season {
+            return;
+        }
+        if self.season.is_empty() {
+            self.season = season.to_string();
+            return;
+        }
+        log::info!("Season {} is over, starting {}", self.season, season);
+        let past_seasons = mem::take(&mut self.past_seasons);
+        let finished = mem::replace(
+            self,
+            Profile {
+                season: season.to_string(),
+                past_seasons,
+                ..Profile::default()
+            },
+        );
+        self.past_seasons.push(finished);
+    }
+}
+
+/// The current season, a quarter of a year in UTC, like 2026-Q4.
+pub fn season_id() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    season_of((seconds / 86400) as i64)
+}
+
+/// The season a day counted from 1970-01-01 is in.
+fn season_of(days: i64) -> String {
+    let (year, month) = year_and_month(days);
+    format!("{}-Q{}", year, (month - 1) / 3 + 1)
+}
+
+/// Year and month (1 to 12) of a day counted from 1970-01-01.
+fn year_and_month(days: i64) -> (i64, i64) {
+    // Howard Hinnant's civil_from_days, with years starting in March so the
+    // leap day comes last.
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let march_month = (5 * day_of_year + 2) / 153;
+    let month = if march_month < 10 {
+        march_month + 3
+    } else {
+        march_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month)
+}
+
+/// Name of a map in the profile, from the path of its level file.
+pub fn map_name(level_path: &str) -> String {
+    Path::new(level_path).file_stem().map_or_else(
+        || level_path.to_string(),
+        |stem| stem.to_string_lossy().into_owned(),
+    )
+}
+
+fn path(ctx: &Context) -> PathBuf {
+    filesystem::user_data_dir(ctx).join(FILE_NAME)
+}
+
+fn load_from(path: &Path) -> Profile {
+    let mut profile = match fs::read_to_string(path) {
+        Ok(source) => ron::de::from_str(&source).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid profile: {}", e);
+            Profile::default()
+        }),
+        Err(_) => Profile::default(),
+    };
+    profile.start_season(&season_id());
+    profile
+}
+
+/// Adds what the local blob does in a match to the profile. It is saved
+/// after each round and when the match is left.
+pub struct Tracker {
+    profile: Profile,
+    path: PathBuf,
+    map: String,
+    // Where the blob was last tick, while it is alive.
+    last_center: Option<Point2<f32>>,
+}
+
+impl Tracker {
+    pub fn new(ctx: &Context, map: &str) -> Tracker {
+        let path = path(ctx);
+        Tracker {
+            profile: load_from(&path),
+            path,
+            map: map.to_string(),
+            last_center: None,
+        }
+    }
+
+    /// Count time played from now on towards another map.
+    pub fn set_map(&mut self, map: &str) {
+        self.map = map.to_string();
+    }
+
+    /// Call once per tick of play with the local blob and the tick's events.
+    pub fn update(&mut self, id: usize, blob: Option<&Blob>, events: &[GameEvent]) {
+        let profile = &mut self.profile;
+        *profile.maps.entry(self.map.clone()).or_insert(0.0) += TICK_TIME;
+        let died = events
+            .iter()
+            .any(|event| matches!(event, GameEvent::Died { target, .. } if *target == id));
+        let blob = match blob {
+            Some(blob) if !died && blob.health > 0.0 => blob,
+            // Respawning isn't movement.
+            _ => {
+                self.last_center = None;
+                return;
+            }
+        };
+        if let (Some(last), HookState::Attached(_)) = (self.last_center, &blob.hook) {
+            profile.rope_distance += (blob.center - last).norm();
+        }
+        if wall_blob_collision(blob.center).is_none() && blob.perch.is_none() {
+            profile.airtime += TICK_TIME;
+        }
+        // Blob velocities are in world units per DT, ticks are TICK_TIME.
+        let speed = blob.vel.norm() * DT / TICK_TIME;
+        profile.top_speed = profile.top_speed.max(speed);
+        self.last_center = Some(blob.center);
+    }
+
+    pub fn save(&self) {
+        let result = ron::ser::to_string_pretty(&self.profile, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|source| {
+                if let Some(dir) = self.path.parent() {
+                    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                fs::write(&self.path, source).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            log::warn!("Could not save profile to {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+impl Drop for Tracker {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_from_days() {
+        assert_eq!(year_and_month(0), (1970, 1));
+        assert_eq!(year_and_month(-1), (1969, 12));
+        assert_eq!(year_and_month(11016), (2000, 2)); // 2000-02-29
+        assert_eq!(year_and_month(11017), (2000, 3));
+        assert_eq!(year_and_month(20088), (2024, 12)); // 2024-12-31
+        assert_eq!(year_and_month(20089), (2025, 1));
+        assert_eq!(year_and_month(20741), (2026, 10)); // 2026-10-15
+    }
+
+    #[test]
+    fn seasons_are_quarters() {
+        assert_eq!(season_of(0), "1970-Q1");
+        assert_eq!(season_of(20543), "2026-Q1"); // 2026-03-31
+        assert_eq!(season_of(20544), "2026-Q2"); // 2026-04-01
+        assert_eq!(season_of(20741), "2026-Q4");
+    }
+
+    #[test]
+    fn new_season_archives_the_old_one() {
+        let mut profile = Profile {
+            season: "2026-Q3".to_string(),
+            rope_distance: 120.0,
+            top_speed: 30.0,
+            past_seasons: vec![Profile {
+                season: "2026-Q2".to_string(),
+                ..Profile::default()
+            }],
+            ..Profile::default()
+        };
+        profile.maps.insert("arena".to_string(), 60.0);
+
+        profile.start_season("2026-Q3");
+        assert_eq!(profile.rope_distance, 120.0);
+        assert_eq!(profile.past_seasons.len(), 1);
+
+        profile.start_season("2026-Q4");
+        assert_eq!(profile.season, "2026-Q4");
+        assert_eq!(profile.rope_distance, 0.0);
+        assert!(profile.maps.is_empty());
+        let seasons: Vec<&str> = profile
+            .past_seasons
+            .iter()
+            .map(|p| p.season.as_str())
+            .collect();
+        assert_eq!(seasons, ["2026-Q2", "2026-Q3"]);
+        let finished = &profile.past_seasons[1];
+        assert_eq!(finished.rope_distance, 120.0);
+        assert_eq!(finished.top_speed, 30.0);
+        assert_eq!(finished.maps["arena"], 60.0);
+        assert!(finished.past_seasons.is_empty());
+    }
+
+    #[test]
+    fn stats_from_before_seasons_count_towards_this_one() {
+        let mut profile = Profile {
+            airtime: 5.0,
+            ..Profile::default()
+        };
+        profile.start_season("2026-Q4");
+        assert_eq!(profile.season, "2026-Q4");
+        assert_eq!(profile.airtime, 5.0);
+        assert!(profile.past_seasons.is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+use ggez::graphics;
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+
+use crate::SCREEN_SIZE;
+
+/// Single line text input drawn over the game.
+pub struct TextPrompt {
+    label: String,
+    text: String,
+}
+
+impl TextPrompt {
+    pub fn new(label: &str) -> TextPrompt {
+        TextPrompt {
+            label: label.to_string(),
+            text: String::new(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn input(&mut self, character: char) {
+        if !character.is_control() {
+            self.text.push(character);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        self.text.pop();
+    }
+
+    /// Append the text on the clipboard, if there is any.
+    pub fn paste(&mut self) {
+        if let Ok(text) = arboard::Clipboard::new().and_then(|mut c| c.get_text()) {
+            self.text.extend(text.chars().filter(|c| !c.is_control()));
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let area = graphics::Rect::new(
+            50.0,
+            SCREEN_SIZE.1 / 2.0 - 40.0,
+            SCREEN_SIZE.0 - 100.0,
+            80.0,
+        );
+        let background = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            area,
+            graphics::Color::new(0.9, 0.9, 0.9, 0.95),
+        )?;
+        graphics::draw(ctx, &background, graphics::DrawParam::new())?;
+
+        // Show the end of the text if it doesn't fit.
+        let max_chars = 90;
+        let shown: String = match self.text.chars().count() {
+            n if n > max_chars => self.text.chars().skip(n - max_chars).collect(),
+            _ => self.text.clone(),
+        };
+        let text = graphics::Text::new(format!("{}\n> {}_", self.label, shown));
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new()
+                .dest(Point2::new(area.x + 10.0, area.y + 10.0))
+                .color(graphics::BLACK),
+        )
+    }
+}
@@ -0,0 +1,91 @@
+use ggez::filesystem;
+use ggez::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+// File in the user data directory the ratings are kept in.
+const FILE_NAME: &str = "ratings.ron";
+
+/// Thumbs up and down a map has been given after matches.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct MapRating {
+    pub up: u32,
+    pub down: u32,
+}
+
+impl MapRating {
+    /// How likely the map is to come up when one is picked at random.
+    /// Unrated maps weigh 1, liked maps more and disliked maps less.
+    pub fn weight(self) -> f32 {
+        (self.up + 1) as f32 / (self.down + 1) as f32
+    }
+
+    pub fn summary(self) -> String {
+        format!("+{} / -{}", self.up, self.down)
+    }
+}
+
+/// Ratings of maps by their hash, added up over all matches played and kept
+/// in the user data directory, so the maps people like come up more often.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Ratings {
+    maps: HashMap<String, MapRating>,
+}
+
+impl Ratings {
+    /// Load the saved ratings, or start without any.
+    pub fn load(ctx: &Context) -> Ratings {
+        let source = match fs::read_to_string(path(ctx)) {
+            Ok(source) => source,
+            Err(_) => return Ratings::default(),
+        };
+        ron::de::from_str(&source).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid map ratings: {}", e);
+            Ratings::default()
+        })
+    }
+
+    pub fn get(&self, hash: &str) -> MapRating {
+        self.maps.get(hash).copied().unwrap_or_default()
+    }
+
+    /// Add a thumb up or down to the map and save the ratings.
+    pub fn rate(&mut self, ctx: &Context, hash: &str, up: bool) {
+        let rating = self.maps.entry(hash.to_string()).or_default();
+        if up {
+            rating.up += 1;
+        } else {
+            rating.down += 1;
+        }
+        let path = path(ctx);
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|source| {
+                fs::create_dir_all(filesystem::user_data_dir(ctx)).map_err(|e| e.to_string())?;
+                fs::write(&path, source).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            log::warn!("Could not save map ratings to {}: {}", path.display(), e);
+        }
+    }
+
+    /// Pick one of the maps at random, weighted by their ratings. Returns
+    /// its index, or None without maps.
+    pub fn pick(&self, hashes: &[String]) -> Option<usize> {
+        let weights: Vec<f32> = hashes.iter().map(|hash| self.get(hash).weight()).collect();
+        let mut left = rand::random::<f32>() * weights.iter().sum::<f32>();
+        for (i, weight) in weights.iter().enumerate() {
+            if left < *weight {
+                return Some(i);
+            }
+            left -= weight;
+        }
+        hashes.len().checked_sub(1)
+    }
+}
+
+fn path(ctx: &Context) -> PathBuf {
+    filesystem::user_data_dir(ctx).join(FILE_NAME)
+}
@@ -0,0 +1,825 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use ggez::filesystem;
+use ggez::graphics::{self, Color};
+use ggez::input::keyboard::{self, KeyCode};
+use ggez::{Context, GameError, GameResult};
+use nalgebra::{Point2, Vector2};
+use std::collections::{BTreeMap, VecDeque};
+use std::convert::TryFrom;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::attachment::Attachment;
+use crate::health::GameEvent;
+use crate::meshes;
+use crate::photo::Camera;
+use crate::rope;
+use crate::skin::Skin;
+use crate::sprites;
+use crate::team::{Team, TeamColor};
+use crate::tuning;
+use crate::{Blob, HookState, SCREEN_SIZE, TICKS_PER_SECOND};
+
+const MAGIC: &[u8; 4] = b"BLR2";
+// Share codes from before replays had events are still read.
+const OLD_MAGIC: &[u8; 4] = b"BLR1";
+// Folder in the user data directory where share codes are saved.
+const REPLAY_DIR: &str = "replays";
+// Only every other tick is recorded, to keep share codes short.
+pub const TICKS_PER_FRAME: u32 = 2;
+// Seconds of play kept for highlights.
+const HIGHLIGHT_LENGTH: u32 = 10;
+const HIGHLIGHT_FRAMES: usize = (HIGHLIGHT_LENGTH * TICKS_PER_SECOND / TICKS_PER_FRAME) as usize;
+// Size of the largest replay that can be encoded: the most frames with the
+// most blobs, all with their hooks out, and the most events. Share codes
+// that unpack to more are rejected before they fill the memory.
+const MAX_REPLAY_BYTES: u64 =
+    (MAGIC.len() + 2 + u16::MAX as usize * (1 + u8::MAX as usize * 11) + 2 + u16::MAX as usize * 5)
+        as u64;
+
+/// What a blob looked like in one replay frame.
+#[derive(Clone, Copy, Debug)]
+pub struct BlobFrame {
+    pub id: usize,
+    pub center: Point2<f32>,
+    pub hook: Option<Point2<f32>>,
+    pub team: Option<Team>,
+}
+
+impl BlobFrame {
+    fn new(id: usize, blob: &Blob) -> BlobFrame {
+        let hook = match blob.hook {
+            HookState::Attached(Attachment { point: p, .. }) | HookState::Traveling(p, _) => {
+                Some(p)
+            }
+            HookState::None => None,
+        };
+        BlobFrame {
+            id,
+            center: blob.center,
+            hook,
+            team: blob.team,
+        }
+    }
+}
+
+pub type Frame = Vec<BlobFrame>;
+
+/// Something worth finding again in a replay, marked on its timeline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReplayEvent {
+    /// The blob died.
+    Kill { target: usize },
+    /// The blob brought a flag home for its team.
+    Capture { carrier: usize, team: Option<Team> },
+}
+
+/// A recorded piece of play, made of snapshots of the blobs, with the
+/// events in it by the frame they happened before.
+pub struct Replay {
+    pub frames: Vec<Frame>,
+    pub events: Vec<(usize, ReplayEvent)>,
+}
+
+impl Replay {
+    /// Encode the replay as text that can be pasted into chat.
+    pub fn to_share_code(&self) -> GameResult<String> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        let bytes = self.to_bytes().map_err(|reason| {
+            GameError::ResourceLoadError(format!("Replay can't be shared: {}", reason))
+        })?;
+        encoder.write_all(&bytes)?;
+        let compressed = encoder.finish()?;
+        Ok(URL_SAFE_NO_PAD.encode(&compressed))
+    }
+
+    pub fn from_share_code(code: &str) -> GameResult<Replay> {
+        let invalid =
+            |reason: &str| GameError::ResourceLoadError(format!("Invalid replay code: {}", reason));
+        let compressed = URL_SAFE_NO_PAD
+            .decode(code.trim())
+            .map_err(|_| invalid("not base64"))?;
+        let mut bytes = Vec::new();
+        DeflateDecoder::new(compressed.as_slice())
+            .take(MAX_REPLAY_BYTES + 1)
+            .read_to_end(&mut bytes)
+            .map_err(|_| invalid("corrupt data"))?;
+        if bytes.len() as u64 > MAX_REPLAY_BYTES {
+            return Err(invalid("too large"));
+        }
+        Replay::from_bytes(&bytes).ok_or_else(|| invalid("unknown format"))
+    }
+
+    /// Positions are stored as whole world units, which is plenty for viewing.
+    /// Fails if there are more frames, blobs or events than fit in the format.
+    fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&count_u16(self.frames.len(), "frames")?.to_le_bytes());
+        for frame in &self.frames {
+            let blob_count = u8::try_from(frame.len())
+                .map_err(|_| format!("more than {} blobs in a frame", u8::MAX))?;
+            bytes.push(blob_count);
+            for blob in frame {
+                let flags = blob.hook.is_some() as u8 | team_byte(blob.team) << 1;
+                bytes.extend_from_slice(&id_u16(blob.id)?.to_le_bytes());
+                push_point(&mut bytes, blob.center);
+                bytes.push(flags);
+                if let Some(hook) = blob.hook {
+                    push_point(&mut bytes, hook);
+                }
+            }
+        }
+        bytes.extend_from_slice(&count_u16(self.events.len(), "events")?.to_le_bytes());
+        for &(frame, event) in &self.events {
+            let (kind, id) = match event {
+                ReplayEvent::Kill { target } => (0, target),
+                ReplayEvent::Capture { carrier, team } => (1 + team_byte(team), carrier),
+            };
+            bytes.extend_from_slice(&count_u16(frame, "frames")?.to_le_bytes());
+            bytes.push(kind);
+            bytes.extend_from_slice(&id_u16(id)?.to_le_bytes());
+        }
+        Ok(bytes)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Replay> {
+        let mut reader = ByteReader { bytes, pos: 0 };
+        let magic = reader.take(4)?;
+        if magic != MAGIC && magic != OLD_MAGIC {
+            return None;
+        }
+        let frame_count = reader.u16()?;
+        let mut frames = Vec::with_capacity(frame_count as usize);
+        for _ in 0..frame_count {
+            let blob_count = reader.u8()?;
+            let mut frame = Vec::with_capacity(blob_count as usize);
+            for _ in 0..blob_count {
+                let id = reader.u16()? as usize;
+                let center = reader.point()?;
+                let flags = reader.u8()?;
+                let hook = if flags & 1 != 0 {
+                    Some(reader.point()?)
+                } else {
+                    None
+                };
+                let team = byte_team(flags >> 1)?;
+                frame.push(BlobFrame {
+                    id,
+                    center,
+                    hook,
+                    team,
+                });
+            }
+            frames.push(frame);
+        }
+        let mut events = Vec::new();
+        if magic == MAGIC {
+            for _ in 0..reader.u16()? {
+                let frame = reader.u16()? as usize;
+                let kind = reader.u8()?;
+                let id = reader.u16()? as usize;
+                let event = match kind {
+                    0 => ReplayEvent::Kill { target: id },
+                    _ => ReplayEvent::Capture {
+                        carrier: id,
+                        team: byte_team(kind - 1)?,
+                    },
+                };
+                events.push((frame, event));
+            }
+        }
+        Some(Replay { frames, events })
+    }
+}
+
+fn count_u16(count: usize, what: &str) -> Result<u16, String> {
+    u16::try_from(count).map_err(|_| format!("more than {} {}", u16::MAX, what))
+}
+
+fn id_u16(id: usize) -> Result<u16, String> {
+    u16::try_from(id).map_err(|_| format!("blob id {} is too large", id))
+}
+
+fn team_byte(team: Option<Team>) -> u8 {
+    match team {
+        None => 0,
+        Some(Team::Red) => 1,
+        Some(Team::Blue) => 2,
+    }
+}
+
+fn byte_team(byte: u8) -> Option<Option<Team>> {
+    match byte {
+        0 => Some(None),
+        1 => Some(Some(Team::Red)),
+        2 => Some(Some(Team::Blue)),
+        _ => None,
+    }
+}
+
+/// Save a share code to a file in the user data directory, so it can be
+/// shared as a file instead.
+pub fn save_share_code(ctx: &Context, code: &str) -> GameResult<PathBuf> {
+    let dir = filesystem::user_data_dir(ctx).join(REPLAY_DIR);
+    fs::create_dir_all(&dir)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = dir.join(format!("highlight-{}.txt", timestamp));
+    fs::write(&path, code)?;
+    Ok(path)
+}
+
+/// Load a replay from a share code, or from the name of a saved share code
+/// file.
+pub fn load(ctx: &Context, code_or_file: &str) -> GameResult<Replay> {
+    let code_or_file = code_or_file.trim();
+    if code_or_file.ends_with(".txt") {
+        let path = filesystem::user_data_dir(ctx)
+            .join(REPLAY_DIR)
+            .join(code_or_file);
+        Replay::from_share_code(&fs::read_to_string(path)?)
+    } else {
+        Replay::from_share_code(code_or_file)
+    }
+}
+
+fn push_point(bytes: &mut Vec<u8>, p: Point2<f32>) {
+    bytes.extend_from_slice(&(p.x.round() as i16).to_le_bytes());
+    bytes.extend_from_slice(&(p.y.round() as i16).to_le_bytes());
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let taken = self.bytes.get(self.pos..self.pos + n)?;
+        self.pos += n;
+        Some(taken)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let b = self.take(2)?;
+        Some(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn point(&mut self) -> Option<Point2<f32>> {
+        let x = self.u16()? as i16;
+        let y = self.u16()? as i16;
+        Some(Point2::new(x as f32, y as f32))
+    }
+}
+
+/// How often frame buffers had to be allocated rather than reused.
+#[derive(Clone, Copy, Default)]
+pub struct PoolStats {
+    pub allocated: u32,
+    pub reused: u32,
+}
+
+/// Keeps the last few seconds of play so they can be shared as a highlight.
+/// Once the highlight is full, the buffer of the oldest frame is reused for
+/// the newest, so recording doesn't allocate.
+pub struct Recorder {
+    frames: VecDeque<Frame>,
+    tick: u32,
+    stats: PoolStats,
+    // Frames recorded in all, and the events of the kept frames by the
+    // count of frames recorded before them.
+    recorded: usize,
+    events: VecDeque<(usize, ReplayEvent)>,
+}
+
+impl Recorder {
+    pub fn new() -> Recorder {
+        Recorder {
+            frames: VecDeque::with_capacity(HIGHLIGHT_FRAMES),
+            tick: 0,
+            stats: PoolStats::default(),
+            recorded: 0,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Note an event that isn't a game event, like a capture, to be marked
+    /// in highlights.
+    pub fn mark(&mut self, event: ReplayEvent) {
+        self.events.push_back((self.recorded, event));
+    }
+
+    /// Call once per tick, with the tick's events.
+    pub fn record(&mut self, blobs: &BTreeMap<usize, Blob>, events: &[GameEvent]) {
+        for event in events {
+            if let GameEvent::Died { target, .. } = *event {
+                self.mark(ReplayEvent::Kill { target });
+            }
+        }
+        self.tick += 1;
+        if !self.tick.is_multiple_of(TICKS_PER_FRAME) {
+            return;
+        }
+        let mut frame = match self.frames.len() {
+            HIGHLIGHT_FRAMES => {
+                self.stats.reused += 1;
+                let mut frame = self.frames.pop_front().unwrap();
+                frame.clear();
+                frame
+            }
+            _ => {
+                self.stats.allocated += 1;
+                Vec::with_capacity(blobs.len())
+            }
+        };
+        frame.extend(blobs.iter().map(|(&id, blob)| BlobFrame::new(id, blob)));
+        frame.sort_by_key(|blob| blob.id);
+        self.frames.push_back(frame);
+        self.recorded += 1;
+        let first = self.recorded - self.frames.len();
+        while self.events.front().is_some_and(|&(frame, _)| frame < first) {
+            self.events.pop_front();
+        }
+    }
+
+    pub fn pool_stats(&self) -> PoolStats {
+        self.stats
+    }
+
+    pub fn highlight(&self) -> Replay {
+        let first = self.recorded - self.frames.len();
+        let last = self.frames.len().saturating_sub(1);
+        Replay {
+            frames: self.frames.iter().cloned().collect(),
+            events: self
+                .events
+                .iter()
+                .map(|&(frame, event)| ((frame - first).min(last), event))
+                .collect(),
+        }
+    }
+}
+
+// Playback speeds to step through with the arrow keys.
+const SPEEDS: [f32; 4] = [0.125, 0.25, 0.5, 1.0];
+// Timeline along the bottom of the screen, which can be clicked or dragged
+// to seek.
+const TIMELINE: (f32, f32, f32) = (20.0, SCREEN_SIZE.1 - 30.0, 12.0);
+// Row of buttons above the timeline, each doing what its key does.
+const BUTTONS: [KeyCode; 6] = [
+    KeyCode::PageUp,
+    KeyCode::Down,
+    KeyCode::Space,
+    KeyCode::Up,
+    KeyCode::PageDown,
+    KeyCode::C,
+];
+const BUTTON_SIZE: (f32, f32) = (130.0, 24.0);
+const BUTTONS_TOP: f32 = SCREEN_SIZE.1 - 100.0;
+// Jumping to an event lands this many frames before it, to see it coming.
+const JUMP_LEAD: usize = 15;
+// The follow camera zooms in at least this far.
+const FOLLOW_ZOOM: f32 = 2.0;
+
+/// Plays a replay over and over, with controls for going through it slowly:
+/// pausing, slow motion, stepping a frame at a time, seeking on a timeline
+/// with the replay's events marked on it and jumping between those events.
+/// Every frame is a full snapshot, so seeking jumps straight to it. The
+/// view is a free camera, or follows one of the blobs.
+pub struct Playback {
+    replay: Replay,
+    // Ticks since the start, fractional in slow motion.
+    time: f32,
+    speed: usize,
+    paused: bool,
+    // Whether the timeline is being dragged.
+    scrubbing: bool,
+    camera: Camera,
+    // The blob the camera follows, if it does.
+    following: Option<usize>,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Playback {
+        Playback {
+            replay,
+            time: 0.0,
+            speed: SPEEDS.len() - 1,
+            paused: false,
+            scrubbing: false,
+            camera: Camera::new(),
+            following: None,
+        }
+    }
+
+    fn length(&self) -> f32 {
+        (self.replay.frames.len() * TICKS_PER_FRAME as usize) as f32
+    }
+
+    fn frame(&self) -> usize {
+        (self.time / TICKS_PER_FRAME as f32) as usize
+    }
+
+    /// Call once per tick. WASD moves a free camera and Z/X zoom.
+    pub fn update(&mut self, ctx: &Context) {
+        if !self.paused && !self.scrubbing {
+            self.time += SPEEDS[self.speed];
+            if self.time >= self.length() {
+                self.time = 0.0;
+            }
+        }
+        let held = |key: KeyCode| keyboard::is_key_pressed(ctx, key) as i8 as f32;
+        let zoom = held(KeyCode::Z) - held(KeyCode::X);
+        match self.following {
+            Some(id) => {
+                self.camera.steer(Vector2::zeros(), 0.0, zoom);
+                if let Some(blob) = self.blobs().iter().find(|blob| blob.id == id) {
+                    self.camera.center = blob.center;
+                }
+            }
+            None => {
+                let pan = Vector2::new(
+                    held(KeyCode::D) - held(KeyCode::A),
+                    held(KeyCode::S) - held(KeyCode::W),
+                );
+                self.camera.steer(pan, 0.0, zoom);
+            }
+        }
+    }
+
+    /// Handle a key press, returning whether it was one of the controls.
+    pub fn key_down(&mut self, keycode: KeyCode) -> bool {
+        let frame = TICKS_PER_FRAME as f32;
+        match keycode {
+            KeyCode::Space => self.paused = !self.paused,
+            KeyCode::Up => self.speed = (self.speed + 1).min(SPEEDS.len() - 1),
+            KeyCode::Down => self.speed = self.speed.saturating_sub(1),
+            KeyCode::Right | KeyCode::Left => {
+                self.paused = true;
+                let step = if keycode == KeyCode::Right { 1.0 } else { -1.0 };
+                let current = (self.time / frame).floor();
+                self.seek_frame(current + step);
+            }
+            KeyCode::Home => self.seek_frame(0.0),
+            KeyCode::End => self.seek_frame(self.replay.frames.len() as f32 - 1.0),
+            KeyCode::PageUp | KeyCode::PageDown => self.jump(keycode == KeyCode::PageDown),
+            KeyCode::C => self.toggle_follow(),
+            KeyCode::Tab => self.follow_next(),
+            KeyCode::R => {
+                self.camera = Camera::new();
+                self.following = None;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Jump to just before the next or previous event.
+    fn jump(&mut self, forward: bool) {
+        let current = self.frame();
+        let landings = self
+            .replay
+            .events
+            .iter()
+            .map(|&(frame, _)| frame.saturating_sub(JUMP_LEAD));
+        let landing = if forward {
+            landings.filter(|&frame| frame > current).min()
+        } else {
+            landings.filter(|&frame| frame < current).max()
+        };
+        if let Some(frame) = landing {
+            self.seek_frame(frame as f32);
+        }
+    }
+
+    /// Switch between the free camera and following a blob, the one in the
+    /// latest event if there is one.
+    fn toggle_follow(&mut self) {
+        if self.following.take().is_some() {
+            return;
+        }
+        let current = self.frame();
+        let in_event = self
+            .replay
+            .events
+            .iter()
+            .rev()
+            .find(|&&(frame, _)| frame <= current + JUMP_LEAD)
+            .map(|&(_, event)| match event {
+                ReplayEvent::Kill { target } => target,
+                ReplayEvent::Capture { carrier, .. } => carrier,
+            });
+        self.following = in_event.or_else(|| self.blobs().first().map(|blob| blob.id));
+        self.camera.zoom = self.camera.zoom.max(FOLLOW_ZOOM);
+    }
+
+    /// Follow the blob after the followed one.
+    fn follow_next(&mut self) {
+        let blobs = self.blobs();
+        let next = match self.following {
+            Some(id) => blobs.iter().find(|blob| blob.id > id).or(blobs.first()),
+            None => blobs.first(),
+        };
+        if let Some(blob) = next {
+            self.following = Some(blob.id);
+            self.camera.zoom = self.camera.zoom.max(FOLLOW_ZOOM);
+        }
+    }
+
+    /// Start seeking if the timeline was clicked, or press a clicked button,
+    /// returning whether either was.
+    pub fn mouse_button_down(&mut self, point: Point2<f32>) -> bool {
+        if let Some(&keycode) = BUTTONS
+            .iter()
+            .enumerate()
+            .find(|&(i, _)| button_rect(i).contains(point))
+            .map(|(_, keycode)| keycode)
+        {
+            return self.key_down(keycode);
+        }
+        let (left, top, height) = TIMELINE;
+        let on_timeline = point.y >= top - height
+            && point.y <= top + 2.0 * height
+            && point.x >= left
+            && point.x <= SCREEN_SIZE.0 - left;
+        if on_timeline {
+            self.scrubbing = true;
+            self.scrub(point);
+        }
+        on_timeline
+    }
+
+    /// Seek while the timeline is dragged, returning whether it is.
+    pub fn mouse_motion(&mut self, point: Point2<f32>) -> bool {
+        if self.scrubbing {
+            self.scrub(point);
+        }
+        self.scrubbing
+    }
+
+    /// Stop seeking, returning whether the timeline was being dragged.
+    pub fn mouse_button_up(&mut self) -> bool {
+        std::mem::replace(&mut self.scrubbing, false)
+    }
+
+    fn scrub(&mut self, point: Point2<f32>) {
+        let (left, _, _) = TIMELINE;
+        let fraction = ((point.x - left) / (SCREEN_SIZE.0 - 2.0 * left)).clamp(0.0, 1.0);
+        self.seek_frame((fraction * self.replay.frames.len() as f32).floor());
+    }
+
+    fn seek_frame(&mut self, frame: f32) {
+        let last = self.replay.frames.len().max(1) - 1;
+        self.time = frame.clamp(0.0, last as f32) * TICKS_PER_FRAME as f32;
+    }
+
+    /// The blobs as they were at the current time, in between two frames
+    /// when playing slower than they were recorded.
+    fn blobs(&self) -> Vec<BlobFrame> {
+        let at = self.time / TICKS_PER_FRAME as f32;
+        let index = at.floor() as usize;
+        let frame = match self.replay.frames.get(index) {
+            Some(frame) => frame,
+            None => return Vec::new(),
+        };
+        let next = self.replay.frames.get(index + 1);
+        let t = at.fract();
+        let lerp = |a: Point2<f32>, b: Point2<f32>| a + t * (b - a);
+        frame
+            .iter()
+            .map(|blob| {
+                let later = next.and_then(|next| next.iter().find(|later| later.id == blob.id));
+                match later {
+                    Some(later) => BlobFrame {
+                        center: lerp(blob.center, later.center),
+                        hook: match (blob.hook, later.hook) {
+                            (Some(a), Some(b)) => Some(lerp(a, b)),
+                            (hook, _) => hook,
+                        },
+                        ..*blob
+                    },
+                    None => *blob,
+                }
+            })
+            .collect()
+    }
+
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        graphics::push_transform(ctx, Some(self.camera.transform().to_matrix()));
+        graphics::apply_transformations(ctx)?;
+        let drawn = self.draw_blobs(ctx);
+        graphics::pop_transform(ctx);
+        graphics::apply_transformations(ctx)?;
+        drawn?;
+        self.draw_buttons(ctx)?;
+        self.draw_timeline(ctx)?;
+        let text = graphics::Text::new(
+            "REPLAY - Esc to stop, drag to draw arrows (left) or circles (right), \
+             Backspace to erase\n\
+             Space to pause, Left/Right to step, Up/Down for speed, click the timeline to seek, \
+             PgUp/PgDn for events\n\
+             C to follow a blob, Tab for the next one, WASD to move, Z/X to zoom, R to reset",
+        );
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new()
+                .dest(Point2::new(10.0, 10.0))
+                .color(graphics::BLACK),
+        )
+    }
+
+    fn draw_blobs(&self, ctx: &mut Context) -> GameResult<()> {
+        for blob in self.blobs() {
+            let skin = Skin::new(blob.id, blob.team);
+            if let Some(hook) = blob.hook {
+                rope::draw(ctx, blob.center, hook, true, skin.tint)?;
+                sprites::draw_hook(ctx, blob.center, hook, skin.tint)?;
+            }
+            let color = skin.body;
+            if sprites::draw_blob_circle(ctx, blob.center, tuning::current().blob_radius, color)? {
+                continue;
+            }
+            meshes::draw_disc(ctx, blob.center, tuning::current().blob_radius, color)?;
+        }
+        Ok(())
+    }
+
+    fn button_label(&self, keycode: KeyCode) -> &'static str {
+        match keycode {
+            KeyCode::PageUp => "<< Event",
+            KeyCode::Down => "Slower",
+            KeyCode::Space if self.paused => "Play",
+            KeyCode::Space => "Pause",
+            KeyCode::Up => "Faster",
+            KeyCode::PageDown => "Event >>",
+            KeyCode::C if self.following.is_some() => "Free camera",
+            KeyCode::C => "Follow camera",
+            _ => "",
+        }
+    }
+
+    fn draw_buttons(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut builder = graphics::MeshBuilder::new();
+        for i in 0..BUTTONS.len() {
+            let rect = button_rect(i);
+            builder
+                .rectangle(
+                    graphics::DrawMode::fill(),
+                    rect,
+                    Color::new(1.0, 1.0, 1.0, 0.8),
+                )
+                .rectangle(graphics::DrawMode::stroke(2.0), rect, graphics::BLACK);
+        }
+        let mesh = builder.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::new())?;
+        for (i, &keycode) in BUTTONS.iter().enumerate() {
+            let rect = button_rect(i);
+            let text = graphics::Text::new(self.button_label(keycode));
+            let dest = Point2::new(
+                rect.x + (rect.w - text.width(ctx) as f32) / 2.0,
+                rect.y + (rect.h - text.height(ctx) as f32) / 2.0,
+            );
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::new().dest(dest).color(graphics::BLACK),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn draw_timeline(&self, ctx: &mut Context) -> GameResult<()> {
+        let (left, top, height) = TIMELINE;
+        let width = SCREEN_SIZE.0 - 2.0 * left;
+        let fraction = if self.length() > 0.0 {
+            self.time / self.length()
+        } else {
+            0.0
+        };
+        let area = graphics::Rect::new(left, top, width, height);
+        let played = graphics::Rect::new(left, top, width * fraction, height);
+        let marker = Point2::new(left + width * fraction, top + height / 2.0);
+        let mut bar = graphics::MeshBuilder::new();
+        bar.rectangle(
+            graphics::DrawMode::fill(),
+            area,
+            Color::new(0.0, 0.0, 0.0, 0.3),
+        )
+        .rectangle(
+            graphics::DrawMode::fill(),
+            played,
+            Color::new(1.0, 1.0, 1.0, 0.8),
+        );
+        // Kills are short black ticks, captures taller ones in the team's
+        // color.
+        let frames = self.replay.frames.len().max(1) as f32;
+        for &(frame, event) in &self.replay.events {
+            let x = left + width * frame as f32 / frames;
+            let (reach, color) = match event {
+                ReplayEvent::Kill { .. } => (0.5 * height, graphics::BLACK),
+                ReplayEvent::Capture { team, .. } => (
+                    1.5 * height,
+                    team.map_or(graphics::BLACK, |team| team.color()),
+                ),
+            };
+            let tick = [Point2::new(x, top - reach), Point2::new(x, top + height)];
+            bar.line(&tick, 3.0, color)?;
+        }
+        let bar = bar
+            .rectangle(graphics::DrawMode::stroke(2.0), area, graphics::BLACK)
+            .circle(
+                graphics::DrawMode::fill(),
+                marker,
+                height,
+                0.5,
+                graphics::BLACK,
+            )
+            .build(ctx)?;
+        graphics::draw(ctx, &bar, graphics::DrawParam::new())?;
+
+        let seconds = |ticks: f32| ticks / TICKS_PER_SECOND as f32;
+        let state = if self.paused { "paused" } else { "playing" };
+        let camera = match self.following {
+            Some(id) => format!("following blob {}", id),
+            None => "free camera".to_string(),
+        };
+        let label = graphics::Text::new(format!(
+            "{:.2} / {:.2} s, frame {} of {}, {} at {}x, {}",
+            seconds(self.time),
+            seconds(self.length()),
+            self.frame() + 1,
+            self.replay.frames.len(),
+            state,
+            SPEEDS[self.speed],
+            camera
+        ));
+        graphics::draw(
+            ctx,
+            &label,
+            graphics::DrawParam::new()
+                .dest(Point2::new(left, top - 2.0 * height))
+                .color(graphics::BLACK),
+        )
+    }
+}
+
+/// Where the button at the index is, in the row of buttons centered above
+/// the timeline.
+fn button_rect(i: usize) -> graphics::Rect {
+    let (width, height) = BUTTON_SIZE;
+    let gap = 8.0;
+    let row = BUTTONS.len() as f32 * (width + gap) - gap;
+    let left = (SCREEN_SIZE.0 - row) / 2.0 + i as f32 * (width + gap);
+    graphics::Rect::new(left, BUTTONS_TOP, width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(blobs: usize) -> Frame {
+        (0..blobs)
+            .map(|id| BlobFrame {
+                id,
+                center: Point2::new(id as f32, 500.0),
+                hook: Some(Point2::new(0.0, 0.0)),
+                team: Some(Team::Blue),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn share_codes_round_trip() {
+        let replay = Replay {
+            frames: vec![frame(3), frame(255)],
+            events: vec![(1, ReplayEvent::Kill { target: 2 })],
+        };
+        let decoded = Replay::from_share_code(&replay.to_share_code().unwrap()).unwrap();
+        assert_eq!(decoded.frames.len(), 2);
+        assert_eq!(decoded.frames[1].len(), 255);
+        assert_eq!(decoded.frames[1][254].center, Point2::new(254.0, 500.0));
+        assert_eq!(decoded.events, replay.events);
+    }
+
+    #[test]
+    fn refuses_frames_that_dont_fit() {
+        let replay = Replay {
+            frames: vec![frame(256)],
+            events: Vec::new(),
+        };
+        assert!(replay.to_share_code().is_err());
+    }
+}
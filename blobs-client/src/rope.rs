@@ -0,0 +1,86 @@
+use ggez::graphics::{self, Color};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+
+use crate::batch::Batch;
+use crate::tuning;
+
+// Number of straight pieces the rope is drawn with.
+const SEGMENTS: usize = 16;
+// Weight of the rope per unit of length, relative to the spring force. The
+// higher, the more a loose rope hangs.
+const WEIGHT: f32 = 2.0;
+// Tension is never taken as lower than this, so slightly stretched ropes
+// don't sag without bound.
+const MIN_TENSION: f32 = 20.0;
+const SLACK_WIDTH: f32 = 4.0;
+const TAUT_WIDTH: f32 = 1.5;
+// Stretch beyond the rest length at which the rope is drawn thinnest.
+const FULL_STRETCH: f32 = 500.0;
+
+/// Add the rope to a batch, like `draw` draws it.
+pub fn add(
+    batch: &mut Batch,
+    from: Point2<f32>,
+    to: Point2<f32>,
+    attached: bool,
+    color: Color,
+) -> GameResult<()> {
+    let (points, width) = shape(from, to, attached);
+    batch.line(&points, width, color)
+}
+
+/// Draw the rope from a blob to its hook. An attached rope hangs down when
+/// it's slack and straightens and thins as it's stretched, showing how hard
+/// it pulls. A rope still on its way out is drawn straight.
+pub fn draw(
+    ctx: &mut Context,
+    from: Point2<f32>,
+    to: Point2<f32>,
+    attached: bool,
+    color: Color,
+) -> GameResult<()> {
+    let (points, width) = shape(from, to, attached);
+    let rope = graphics::Mesh::new_line(ctx, &points, width, color)?;
+    graphics::draw(ctx, &rope, graphics::DrawParam::new())
+}
+
+/// Points along the rope and its width.
+fn shape(from: Point2<f32>, to: Point2<f32>, attached: bool) -> (Vec<Point2<f32>>, f32) {
+    let span = (to - from).norm();
+    let (sag, width) = if attached {
+        (sag(span), width(span))
+    } else {
+        (0.0, SLACK_WIDTH)
+    };
+    let points = (0..=SEGMENTS)
+        .map(|i| {
+            let t = i as f32 / SEGMENTS as f32;
+            // A parabola, close to a catenary for ropes that don't hang much.
+            from + t * (to - from) + 4.0 * sag * t * (1.0 - t) * Vector2::y()
+        })
+        .collect();
+    (points, width)
+}
+
+/// How far the middle of a rope spanning the given distance hangs down.
+fn sag(span: f32) -> f32 {
+    if span <= 0.0 {
+        return 0.0;
+    }
+    let tuning = tuning::current();
+    let slack = tuning.spring_eq_len - span;
+    if slack > 0.0 {
+        // A loose rope's length is about span + 8 sag² / (3 span).
+        (3.0 * span * slack / 8.0).sqrt()
+    } else {
+        // A rope under tension T with weight w per length sags w span² / 8T.
+        let tension = (tuning.spring_const * -slack).max(MIN_TENSION);
+        (WEIGHT * span * span / (8.0 * tension)).min(span / 2.0)
+    }
+}
+
+fn width(span: f32) -> f32 {
+    let stretch = ((span - tuning::current().spring_eq_len) / FULL_STRETCH).clamp(0.0, 1.0);
+    SLACK_WIDTH + (TAUT_WIDTH - SLACK_WIDTH) * stretch
+}
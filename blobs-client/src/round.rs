@@ -0,0 +1,100 @@
+use ggez::{Context, GameResult};
+use std::collections::HashMap;
+
+use crate::health::GameEvent;
+use crate::scene::draw_centered;
+use crate::{SCREEN_SIZE, TICK_TIME};
+
+// Seconds of countdown before a round starts.
+const COUNTDOWN_TIME: f32 = 3.0;
+
+/// What a blob did during a round.
+#[derive(Clone, Default)]
+pub struct Stats {
+    pub kills: u32,
+    /// Deaths other than suicides.
+    pub deaths: u32,
+    pub suicides: u32,
+    pub damage_dealt: f32,
+}
+
+/// One round of play. Play starts after a countdown, and stats are collected
+/// for the results screen.
+pub struct Round {
+    number: u32,
+    // Seconds left until play starts.
+    countdown: f32,
+    stats: HashMap<usize, Stats>,
+}
+
+impl Round {
+    pub fn new(number: u32) -> Round {
+        Round {
+            number,
+            countdown: COUNTDOWN_TIME,
+            stats: HashMap::new(),
+        }
+    }
+
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.countdown <= 0.0
+    }
+
+    /// Stats per blob id, ordered by id.
+    pub fn stats(&self) -> Vec<(usize, Stats)> {
+        let mut stats: Vec<_> = self
+            .stats
+            .iter()
+            .map(|(&id, stats)| (id, stats.clone()))
+            .collect();
+        stats.sort_by_key(|&(id, _)| id);
+        stats
+    }
+
+    /// Stats of a single blob, zero if it hasn't done anything yet.
+    pub fn stats_of(&self, id: usize) -> Stats {
+        self.stats.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Count down to the start of play. Call once per tick.
+    pub fn tick(&mut self) {
+        self.countdown = (self.countdown - TICK_TIME).max(0.0);
+    }
+
+    /// Collect stats from the events of a tick.
+    pub fn record(&mut self, events: &[GameEvent]) {
+        for event in events {
+            match *event {
+                GameEvent::Damaged { amount, source, .. } => {
+                    if let Some(attacker) = source.attacker() {
+                        self.stats.entry(attacker).or_default().damage_dealt += amount;
+                    }
+                }
+                GameEvent::Died {
+                    target,
+                    suicide: true,
+                    ..
+                } => self.stats.entry(target).or_default().suicides += 1,
+                GameEvent::Died { target, killer, .. } => {
+                    self.stats.entry(target).or_default().deaths += 1;
+                    if let Some(killer) = killer {
+                        self.stats.entry(killer).or_default().kills += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draw the countdown before play starts.
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        if self.is_playing() {
+            return Ok(());
+        }
+        let text = format!("Round {}\n{:.0}", self.number, self.countdown.ceil());
+        draw_centered(ctx, &text, 48.0, SCREEN_SIZE.1 / 2.0 - 60.0)
+    }
+}
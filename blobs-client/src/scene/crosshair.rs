@@ -0,0 +1,207 @@
+use ggez::graphics::{self, Color};
+use ggez::input::keyboard::{KeyCode, KeyMods};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+
+use crate::attachment::{Anchor, Attachment};
+use crate::crosshair::{Crosshair, COLORS, GAPS, SHAPES, SIZES, THICKNESSES};
+use crate::scene::settings::choose;
+use crate::scene::{draw_centered, MenuList, Scene, Transition};
+use crate::settings;
+use crate::{HookState, SCREEN_SIZE};
+
+const SHAPE: usize = 0;
+const SIZE: usize = 1;
+const GAP: usize = 2;
+const THICKNESS: usize = 3;
+const COLOR: usize = 4;
+const OUTLINE: usize = 5;
+const TRAVELING_COLOR: usize = 6;
+const HOOKED_COLOR: usize = 7;
+const RESET: usize = 8;
+const BACK: usize = 9;
+
+// The preview is drawn larger than in play, so it's easier to see.
+const PREVIEW_SCALE: f32 = 2.0;
+// Backgrounds the preview is drawn on, to check it shows up on both.
+const PREVIEW_BACKGROUNDS: [Color; 2] = [
+    Color {
+        r: 0.9,
+        g: 0.9,
+        b: 0.9,
+        a: 1.0,
+    },
+    Color {
+        r: 0.2,
+        g: 0.2,
+        b: 0.2,
+        a: 1.0,
+    },
+];
+
+/// Screen for changing how the crosshair looks, with a preview of it for
+/// each hook state. Changes are saved right away, like on the settings
+/// screen.
+pub struct CrosshairScene {
+    items: MenuList,
+    crosshair: Crosshair,
+}
+
+impl CrosshairScene {
+    pub fn new() -> CrosshairScene {
+        let mut scene = CrosshairScene {
+            items: MenuList::new(Vec::new()),
+            crosshair: settings::current().crosshair,
+        };
+        scene.update_items();
+        scene
+    }
+
+    fn update_items(&mut self) {
+        let c = &self.crosshair;
+        let color_name = |color: Option<(u8, u8, u8)>| match color {
+            None => "Unchanged".to_string(),
+            Some(color) => COLORS
+                .iter()
+                .find(|(_, rgb)| *rgb == color)
+                .map(|(name, _)| name.to_string())
+                .unwrap_or_else(|| format!("{:?}", color)),
+        };
+        self.items.set_items(vec![
+            format!("Shape: {}", c.shape.name()),
+            format!("Size: {:.0}", c.size),
+            format!("Gap: {:.0}", c.gap),
+            format!("Thickness: {:.0}", c.thickness),
+            format!("Color: {}", color_name(Some(c.color))),
+            format!("Outline: {}", if c.outline { "On" } else { "Off" }),
+            format!("While the hook flies: {}", color_name(c.traveling_color)),
+            format!("While hooked: {}", color_name(c.hooked_color)),
+            "Reset to default".to_string(),
+            "Back".to_string(),
+        ]);
+    }
+
+    /// Step the selected option back or forth.
+    fn change(&mut self, row: usize, forward: bool) {
+        let colors: Vec<(u8, u8, u8)> = COLORS.iter().map(|&(_, rgb)| rgb).collect();
+        let hook_colors: Vec<Option<(u8, u8, u8)>> = std::iter::once(None)
+            .chain(colors.iter().copied().map(Some))
+            .collect();
+        let c = &mut self.crosshair;
+        match row {
+            SHAPE => c.shape = choose(&SHAPES, c.shape, forward),
+            SIZE => c.size = choose(&SIZES, c.size, forward),
+            GAP => c.gap = choose(&GAPS, c.gap, forward),
+            THICKNESS => c.thickness = choose(&THICKNESSES, c.thickness, forward),
+            COLOR => c.color = choose(&colors, c.color, forward),
+            OUTLINE => c.outline = !c.outline,
+            TRAVELING_COLOR => c.traveling_color = choose(&hook_colors, c.traveling_color, forward),
+            HOOKED_COLOR => c.hooked_color = choose(&hook_colors, c.hooked_color, forward),
+            _ => {}
+        }
+    }
+
+    fn save(&self) {
+        settings::save(settings::Settings {
+            crosshair: self.crosshair,
+            ..settings::current()
+        });
+    }
+
+    fn draw_preview(&self, ctx: &mut Context, y: f32) -> GameResult<()> {
+        let states = [
+            ("Idle", HookState::None),
+            (
+                "Hook flying",
+                HookState::Traveling(Point2::origin(), Vector2::zeros()),
+            ),
+            (
+                "Hooked",
+                HookState::Attached(Attachment::rope(Anchor::Wall, Point2::origin())),
+            ),
+        ];
+        let cell = 120.0;
+        let left = (SCREEN_SIZE.0 - cell * states.len() as f32) / 2.0;
+        for (row, &background) in PREVIEW_BACKGROUNDS.iter().enumerate() {
+            let top = y + row as f32 * cell;
+            let panel = graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(left, top, cell * states.len() as f32, cell),
+                background,
+            )?;
+            graphics::draw(ctx, &panel, graphics::DrawParam::new())?;
+            for (i, (_, hook)) in states.iter().enumerate() {
+                let center = Point2::new(left + (i as f32 + 0.5) * cell, top + cell / 2.0);
+                let transform = graphics::DrawParam::new()
+                    .dest(center - PREVIEW_SCALE * center.coords)
+                    .scale(Vector2::new(PREVIEW_SCALE, PREVIEW_SCALE))
+                    .to_matrix();
+                graphics::push_transform(ctx, Some(transform));
+                graphics::apply_transformations(ctx)?;
+                let drawn = self.crosshair.draw(ctx, center, hook);
+                graphics::pop_transform(ctx);
+                graphics::apply_transformations(ctx)?;
+                drawn?;
+            }
+        }
+        for (i, (name, _)) in states.iter().enumerate() {
+            let text = graphics::Text::new(*name);
+            let x = left + (i as f32 + 0.5) * cell - text.width(ctx) as f32 / 2.0;
+            let dest = Point2::new(x, y + cell * PREVIEW_BACKGROUNDS.len() as f32 + 8.0);
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::new().dest(dest).color(graphics::BLACK),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Scene for CrosshairScene {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        draw_centered(ctx, "Crosshair", 48.0, 30.0)?;
+        self.items.draw(ctx, 100.0)?;
+        self.draw_preview(ctx, 570.0)?;
+        let text = graphics::Text::new("Left/Right to change, Enter to toggle, Esc to go back");
+        let dest = Point2::new(
+            (SCREEN_SIZE.0 - text.width(ctx) as f32) / 2.0,
+            SCREEN_SIZE.1 - 40.0,
+        );
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new().dest(dest).color(graphics::BLACK),
+        )
+    }
+
+    fn key_down(&mut self, _ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods) -> Transition {
+        let row = self.items.selected();
+        match keycode {
+            KeyCode::Escape => return Transition::Pop(1),
+            KeyCode::Left | KeyCode::Right => {
+                self.change(row, keycode == KeyCode::Right);
+                self.save();
+            }
+            _ => match self.items.key_down(keycode) {
+                Some(BACK) => return Transition::Pop(1),
+                Some(OUTLINE) => {
+                    self.change(row, true);
+                    self.save();
+                }
+                Some(RESET) => {
+                    self.crosshair = Crosshair::default();
+                    self.save();
+                }
+                _ => {}
+            },
+        }
+        self.update_items();
+        Transition::None
+    }
+}
@@ -0,0 +1,1136 @@
+use ggez::input::keyboard::{self, KeyCode, KeyMods};
+use ggez::input::mouse::{self, MouseButton};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::afk::AfkWatch;
+use crate::attachment::{Anchor, Attachment};
+use crate::background::Background;
+use crate::balance;
+use crate::batch::Batch;
+use crate::bot::{Bot, Input, Mimic};
+use crate::chat::{Channel, Chat};
+use crate::console::{self, Command};
+use crate::cvar;
+use crate::debug::{self, DebugOverlay};
+use crate::entity::{self, Entity};
+use crate::flyby::Flybys;
+use crate::health::{Damage, DamageSource, GameEvent, MAX_HEALTH};
+use crate::hints::Hints;
+use crate::hud;
+use crate::killfeed::KillFeed;
+use crate::level::{self, Level};
+use crate::logging;
+use crate::minimap::Minimap;
+use crate::mode::{self, GameMode};
+use crate::music::Track;
+use crate::nameplate;
+use crate::objective::{CarrierCues, Objective};
+use crate::particles::{self, Particles};
+use crate::photo::PhotoMode;
+use crate::pickup;
+use crate::profile::{self, Tracker};
+use crate::projectile::Projectile;
+use crate::prompt::TextPrompt;
+use crate::replay::{self, Playback, Recorder, ReplayEvent};
+use crate::respawn::Respawner;
+use crate::round::Round;
+use crate::scene::paused::Paused;
+use crate::scene::results::Results;
+use crate::scene::{self, Scene, Transition};
+use crate::schedule::{Stage, Workers};
+use crate::scoreboard::{self, Row};
+use crate::settings;
+use crate::shake::{self, Shake};
+use crate::skin::Skin;
+use crate::sound::SoundEffects;
+use crate::spawn_menu::{Spawn, SpawnMenu};
+use crate::speedometer::Speedometer;
+use crate::stress;
+use crate::team::Team;
+use crate::telestrator::Telestrator;
+use crate::thumbnail;
+use crate::toast::Toast;
+use crate::tuning::{self, Watcher};
+use crate::ui;
+use crate::workshop::{self, Transfer, Workshop};
+use crate::world::{Impact, Rules, World};
+use crate::{bugreport, Blob, HookState, LOCAL_ID, SCREEN_SIZE};
+
+// Wall hits slower than this kick up no dust, so resting blobs don't.
+const DUST_SPEED: f32 = 50.0;
+
+/// What to do with the text typed into the prompt.
+enum PromptAction {
+    LoadReplay,
+    RunCommand,
+}
+
+/// What the local player does in a tick. Clicks are queued as they come in
+/// and the mouse and perch key are read once at the start of the tick, so the
+/// simulation never reads live input and plays out the same given the same
+/// inputs.
+#[derive(Default)]
+struct LocalInput {
+    // Hook shots and releases, in the order they were made.
+    actions: Vec<Input>,
+    projectiles: u32,
+    // Where the mouse points, in world coordinates.
+    aim_at: Option<Point2<f32>>,
+    holding_perch: bool,
+}
+
+impl LocalInput {
+    fn apply(&self, blob: &mut Blob) {
+        for action in &self.actions {
+            action.apply(blob);
+        }
+        if let Some(aim_at) = self.aim_at {
+            // TODO: Ensure that aim_vec can never be (0, 0)
+            blob.aim_vec = (aim_at - blob.center).normalize();
+        }
+        blob.holding_perch = self.holding_perch;
+    }
+}
+
+/// A match being played on a level.
+pub struct Game {
+    world: World,
+    background: Background,
+    minimap: Minimap,
+    show_minimap: bool,
+    mode: Box<dyn GameMode>,
+    objectives: Vec<Objective>,
+    carrier_cues: CarrierCues,
+    particles: Particles,
+    sound_effects: SoundEffects,
+    flybys: Flybys,
+    speedometer: Speedometer,
+    hints: Hints,
+    profile: Tracker,
+    tuning: Watcher,
+    kill_feed: KillFeed,
+    respawner: Respawner,
+    recorder: Recorder,
+    // Threads for the systems of the effects stage besides the first.
+    workers: Workers,
+    playback: Option<Playback>,
+    telestrator: Telestrator,
+    photo: Option<PhotoMode>,
+    debug_overlay: Option<DebugOverlay>,
+    spawn_menu: Option<SpawnMenu>,
+    prompt: Option<(PromptAction, TextPrompt)>,
+    toast: Toast,
+    chat: Chat,
+    // Blobs voted out of the match, left out of later rounds too.
+    kicked: HashSet<usize>,
+    // Bots added with /practicebot, by blob id.
+    practice_bots: BTreeMap<usize, Mimic>,
+    // Blobs swinging around on their own, added from the command line.
+    bots: BTreeMap<usize, Bot>,
+    // Input of the local player since the last tick.
+    input: LocalInput,
+    // All randomness in the simulation comes from this generator, so a match
+    // played again with the same seed and inputs comes out the same.
+    seed: u64,
+    rng: StdRng,
+    // Blobs and entities, drawn together and kept while the match is frozen.
+    batch: Batch,
+    shake: Shake,
+    afk: AfkWatch,
+    round: Round,
+    workshop: Workshop,
+    // Stress run started from the console that hasn't finished yet.
+    stress_run: Option<stress::Run>,
+}
+
+impl Game {
+    pub fn new(ctx: &mut Context, level_path: &str) -> GameResult<Game> {
+        let level = level::load(ctx, level_path)?;
+        let objectives = level.objectives.iter().map(Objective::new).collect();
+        let mode = mode::create(&level.mode, &level);
+        let minimap = Minimap::new(ctx, &level)?;
+        let background = Background::new(ctx, &level.backgrounds);
+        let mut world = World::new(level);
+        world.blobs = spawn_blobs(&world.level);
+        let seed = rand::random();
+        log::info!("Simulation seed {}", seed);
+        Ok(Game {
+            world,
+            background,
+            minimap,
+            show_minimap: false,
+            mode,
+            objectives,
+            carrier_cues: CarrierCues::new(),
+            particles: Particles::new(ctx)?,
+            sound_effects: SoundEffects::new(),
+            flybys: Flybys::new(),
+            speedometer: Speedometer::new(),
+            hints: Hints::new(),
+            profile: Tracker::new(ctx, &profile::map_name(level_path)),
+            tuning: Watcher::new(ctx),
+            kill_feed: KillFeed::new(),
+            respawner: Respawner::new(),
+            recorder: Recorder::new(),
+            workers: Workers::new(2),
+            playback: None,
+            telestrator: Telestrator::new(),
+            photo: None,
+            debug_overlay: None,
+            spawn_menu: None,
+            prompt: None,
+            toast: Toast::new(),
+            chat: Chat::new(ctx),
+            kicked: HashSet::new(),
+            practice_bots: BTreeMap::new(),
+            bots: BTreeMap::new(),
+            input: LocalInput::default(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            batch: Batch::new(),
+            shake: Shake::new(),
+            afk: AfkWatch::new(),
+            round: Round::new(1),
+            workshop: Workshop::new(ctx),
+            stress_run: None,
+        })
+    }
+}
+
+/// Put the blobs in place for the start of a round.
+fn spawn_blobs(level: &Level) -> BTreeMap<usize, Blob> {
+    let mut blobs = BTreeMap::new();
+    blobs.insert(
+        0,
+        Blob {
+            hook: HookState::Attached(Attachment::rope(Anchor::Wall, Point2::new(400.0, 0.0))),
+            ..Blob::new(level.spawn_point(Some(Team::Red)), Some(Team::Red))
+        },
+    );
+    blobs.insert(
+        10,
+        Blob {
+            vel: Vector2::new(10.0, 10.0),
+            hook: HookState::Attached(Attachment::rope(Anchor::Wall, Point2::new(0.0, 0.0))),
+            ..Blob::new(level.spawn_point(Some(Team::Blue)), Some(Team::Blue))
+        },
+    );
+    blobs
+}
+
+impl Game {
+    /// Draw what's in the level, without the HUD. The background layers are
+    /// placed for a camera looking at the given point.
+    fn draw_world(&mut self, ctx: &mut Context, camera: Point2<f32>, zoom: f32) -> GameResult<()> {
+        self.background.draw(ctx, camera, zoom)?;
+        level::draw(ctx, &self.world.level)?;
+        self.mode.draw(ctx)?;
+        pickup::draw(ctx, &self.world.pickups)?;
+        for objective in &self.objectives {
+            objective.draw(ctx)?;
+        }
+        if self.batch.is_empty() {
+            for (&id, blob) in &self.world.blobs {
+                let camouflage = self.mode.camouflage(id);
+                crate::draw_blob(&mut self.batch, blob, id, id == LOCAL_ID, camouflage)?;
+                if self.world.level.is_spawn_protected(blob.team, blob.center) {
+                    let color = (240, 220, 120).into();
+                    self.batch
+                        .ring(blob.center, tuning::current().blob_radius + 6.0, 3.0, color);
+                }
+            }
+            for entity in &self.world.entities {
+                entity::add(&mut self.batch, entity);
+            }
+        }
+        self.batch.draw(ctx)?;
+        self.particles.draw(ctx)?;
+        for objective in &self.objectives {
+            objective.draw_carried(ctx)?;
+        }
+        Ok(())
+    }
+
+    /// Note that the player did something, and bring their blob back if it
+    /// was taken out for being away.
+    fn note_input(&mut self) {
+        if let Some(team) = self.afk.input() {
+            let spawn_point = self.world.level.spawn_point(team);
+            self.world
+                .blobs
+                .insert(LOCAL_ID, Blob::new(spawn_point, team));
+            self.toast
+                .show("You were moved to the spectators while away. Welcome back!".to_string());
+        }
+    }
+
+    /// Kill the local blob at the start of the next tick, for /kill.
+    fn kill_local(&mut self) {
+        if self.world.blobs.contains_key(&LOCAL_ID) {
+            self.world.damage.push(Damage {
+                target: LOCAL_ID,
+                amount: MAX_HEALTH,
+                source: DamageSource::Suicide,
+            });
+        }
+    }
+
+    /// Save a high resolution picture of the photo mode view.
+    fn take_photo(&mut self, ctx: &mut Context) {
+        let photo = match self.photo.take() {
+            Some(photo) => photo,
+            None => return,
+        };
+        let (camera, zoom) = photo.camera();
+        let message = match photo.export(ctx, |ctx| self.draw_world(ctx, camera, zoom)) {
+            Ok(path) => format!("Saved photo {}", path),
+            Err(e) => format!("Could not save the photo: {}", e),
+        };
+        self.photo = Some(photo);
+        self.toast.show(message);
+    }
+
+    /// Reset the match and count down to the next round.
+    fn next_round(&mut self) {
+        self.world.blobs = spawn_blobs(&self.world.level);
+        let kicked = &self.kicked;
+        self.world.blobs.retain(|id, _| !kicked.contains(id));
+        if self.afk.is_spectating() {
+            self.world.blobs.remove(&LOCAL_ID);
+        }
+        let bot_ids: Vec<usize> = self.practice_bots.keys().copied().collect();
+        for id in bot_ids {
+            self.spawn_practice_bot(id);
+        }
+        self.mode = mode::create(&self.world.level.mode, &self.world.level);
+        self.objectives = self
+            .world
+            .level
+            .objectives
+            .iter()
+            .map(Objective::new)
+            .collect();
+        self.world.reset();
+        self.particles.clear();
+        self.kill_feed = KillFeed::new();
+        self.respawner = Respawner::new();
+        self.round = Round::new(self.round.number() + 1);
+    }
+
+    /// Copy a share code of the last few seconds to the clipboard and save it
+    /// in the user data directory.
+    fn share_highlight(&mut self, ctx: &mut Context) {
+        let code = match self.recorder.highlight().to_share_code() {
+            Ok(code) => code,
+            Err(e) => return self.toast.show(e.to_string()),
+        };
+        let copied = arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(code.clone()))
+            .is_ok();
+        let message = match (replay::save_share_code(ctx, &code), copied) {
+            (Ok(path), true) => format!(
+                "Highlight copied to clipboard and saved to {}",
+                path.display()
+            ),
+            (Ok(path), false) => format!("Highlight saved to {}", path.display()),
+            (Err(_), true) => "Highlight copied to clipboard".to_string(),
+            (Err(e), false) => format!("Couldn't share highlight: {}", e),
+        };
+        self.toast.show(message);
+    }
+
+    fn run_command(&mut self, ctx: &mut Context, command: Command) {
+        match command {
+            Command::BugReport => {
+                let message = bugreport::report(ctx, &self.snapshot(), &self.recorder.highlight());
+                self.toast.show(message);
+            }
+            Command::ReloadBalance => {
+                let message = match balance::load(ctx) {
+                    Ok(()) => "Balance reloaded".to_string(),
+                    Err(e) => format!("Balance not reloaded: {}", e),
+                };
+                log::info!("{}", message);
+                self.toast.show(message);
+            }
+            Command::UploadMap => {
+                self.workshop.upload(&self.world.level);
+                self.toast.show("Uploading map...".to_string());
+            }
+            Command::DownloadMap(hash) => {
+                self.workshop.download(&hash);
+                self.toast.show("Downloading map...".to_string());
+            }
+            Command::Say(message) => self.say(Channel::All, &message, false),
+            Command::Me(message) => self.say(Channel::All, &message, true),
+            Command::Team(message) => {
+                match self.world.blobs.get(&LOCAL_ID).and_then(|blob| blob.team) {
+                    Some(team) => self.say(Channel::Team(team), &message, false),
+                    None => self.toast.show("You are not on a team".to_string()),
+                }
+            }
+            Command::Whisper { to, message } => match self.find_player(&to) {
+                Some(id) => self.say(Channel::Whisper(id), &message, false),
+                None => self.toast.show(format!("No player named {}", to)),
+            },
+            Command::VoteKick(name) => match self.find_player(&name) {
+                Some(LOCAL_ID) => self.toast.show("You can't kick yourself".to_string()),
+                Some(id) => {
+                    let player_count = self.world.blobs.len() + self.respawner.waiting().count();
+                    if self.chat.vote_kick(LOCAL_ID, id, player_count) {
+                        self.world.blobs.remove(&id);
+                        self.respawner.remove(id);
+                        self.kicked.insert(id);
+                    }
+                }
+                None => self.toast.show(format!("No player named {}", name)),
+            },
+            Command::Kill => self.kill_local(),
+            Command::Set { name, value } => match cvar::set(&name, &value) {
+                Ok(message) | Err(message) => self.toast.show(message),
+            },
+            Command::Get(name) => match cvar::find(&name) {
+                Some(cvar) => self.toast.show(cvar.describe()),
+                None => self.toast.show(format!("No cvar named {}", name)),
+            },
+            Command::Cvars(text) => {
+                let text = text.unwrap_or_default();
+                let mut count = 0;
+                for cvar in cvar::matching(&text) {
+                    log::info!("{}", cvar.describe());
+                    count += 1;
+                }
+                self.toast
+                    .show(format!("Listed {} cvars in the log", count));
+            }
+            Command::StressMap { blobs, seconds } => {
+                if self.stress_run.is_some() {
+                    self.toast.show("A stress run is still going".to_string());
+                    return;
+                }
+                self.stress_run = Some(stress::Run::start(
+                    &self.world.level,
+                    self.mode.hostile_base_entry(),
+                    blobs,
+                    seconds,
+                ));
+                self.toast
+                    .show(format!("Stress run with {} blobs started", blobs));
+            }
+            Command::PracticeBot(replay) => {
+                let replay = match replay {
+                    Some(code_or_file) => replay::load(ctx, &code_or_file),
+                    None => Ok(self.recorder.highlight()),
+                };
+                let message = match replay {
+                    Ok(replay) => match Mimic::new(&replay, LOCAL_ID) {
+                        Some(bot) => {
+                            let id = self.add_practice_bot(bot);
+                            format!("Blob {} plays like you did", id)
+                        }
+                        None => "You never shoot your hook in that replay".to_string(),
+                    },
+                    Err(e) => e.to_string(),
+                };
+                self.toast.show(message);
+            }
+        }
+    }
+
+    /// Start the simulation's randomness over from the seed, to play out a
+    /// match the same way as one started with it before. Call before adding
+    /// bots.
+    pub fn set_seed(&mut self, seed: u64) {
+        log::info!("Simulation seed {}", seed);
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// The local player's input for this tick: what was queued since the last
+    /// one, and where the mouse and perch key are now.
+    fn take_input(&mut self, ctx: &Context) -> LocalInput {
+        let mut input = std::mem::take(&mut self.input);
+        input.aim_at = Some(scene::screen_point(ctx, mouse::position(ctx).into()));
+        input.holding_perch =
+            self.prompt.is_none() && keyboard::is_key_pressed(ctx, settings::current().keys.perch);
+        input
+    }
+
+    /// An id no blob in the match has had.
+    fn free_id(&self) -> usize {
+        let ids = self.world.blobs.keys().chain(self.respawner.waiting());
+        ids.chain(&self.kicked).max().map_or(0, |&id| id + 1)
+    }
+
+    /// Add bots that swing around the level, on alternating teams.
+    pub fn add_bots(&mut self, count: usize) {
+        for i in 0..count {
+            let id = self.free_id();
+            let team = Some(if i % 2 == 0 { Team::Blue } else { Team::Red });
+            self.world
+                .blobs
+                .insert(id, Blob::new(self.world.level.spawn_point(team), team));
+            self.bots.insert(id, Bot::new(&mut self.rng));
+        }
+    }
+
+    /// Add a practice bot to the match, on the other team from the player.
+    /// Returns the id of its blob.
+    fn add_practice_bot(&mut self, bot: Mimic) -> usize {
+        let id = self.free_id();
+        self.practice_bots.insert(id, bot);
+        self.spawn_practice_bot(id);
+        id
+    }
+
+    /// Put what was picked in the spawn menu into the match.
+    fn spawn(&mut self, spawn: Spawn) {
+        match spawn {
+            Spawn::Bot { pos, team } => {
+                let id = self.free_id();
+                log::info!("Spawned bot {} at ({:.0}, {:.0})", id, pos.x, pos.y);
+                self.world.blobs.insert(id, Blob::new(pos, team));
+                self.bots.insert(id, Bot::new(&mut self.rng));
+            }
+            Spawn::Pickup(spawn) => {
+                log::info!(
+                    "Spawned {:?} at ({:.0}, {:.0})",
+                    spawn.kind,
+                    spawn.pos.0,
+                    spawn.pos.1
+                );
+                self.world.pickups.add(&spawn);
+            }
+            Spawn::Projectile { pos, vel } => {
+                log::info!(
+                    "Spawned a projectile at ({:.0}, {:.0}) going ({:.1}, {:.1})",
+                    pos.x,
+                    pos.y,
+                    vel.x,
+                    vel.y
+                );
+                let projectile = Projectile::new(LOCAL_ID, pos, vel);
+                self.world.entities.push(Entity::Projectile(projectile));
+            }
+        }
+    }
+
+    /// Put a practice bot's blob at its spawn and start its run over.
+    fn spawn_practice_bot(&mut self, id: usize) {
+        let team = self
+            .world
+            .blobs
+            .get(&LOCAL_ID)
+            .and_then(|blob| blob.team)
+            .map(Team::opponent);
+        let spawn = self.world.level.spawn_point(team);
+        self.world.blobs.insert(id, Blob::new(spawn, team));
+        if let Some(bot) = self.practice_bots.get_mut(&id) {
+            bot.restart();
+        }
+    }
+
+    fn say(&mut self, channel: Channel, message: &str, emote: bool) {
+        if let Err(e) = self.chat.send(LOCAL_ID, channel, message, emote) {
+            self.toast.show(e);
+        }
+    }
+
+    /// Find a player in the match, alive or waiting to respawn.
+    fn find_player(&self, name: &str) -> Option<usize> {
+        let ids = self
+            .world
+            .blobs
+            .keys()
+            .chain(self.respawner.waiting())
+            .copied();
+        console::find_player(name, ids)
+    }
+
+    /// Log and save the report of a finished stress run.
+    fn finish_stress_run(&mut self, ctx: &mut Context, report: Result<stress::Report, String>) {
+        let report = match report {
+            Ok(report) => report,
+            Err(e) => {
+                log::warn!("Stress run failed: {}", e);
+                self.toast.show(format!("Stress run failed: {}", e));
+                return;
+            }
+        };
+        for problem in &report.problems {
+            log::info!(
+                "{:?} {} times near ({:.0}, {:.0})",
+                problem.kind,
+                problem.count,
+                problem.pos.0,
+                problem.pos.1
+            );
+        }
+        let message = match stress::save(ctx, &report) {
+            Ok(path) => format!("{}, saved to {}", report.summary(), path.display()),
+            Err(e) => format!("{}, not saved: {}", report.summary(), e),
+        };
+        log::info!("{}", message);
+        self.toast.show(message);
+    }
+
+    /// React to finished map uploads and downloads.
+    fn finish_transfer(&mut self, ctx: &mut Context, transfer: Transfer) {
+        let message = match transfer {
+            Transfer::Uploaded { hash } => {
+                let command = format!("/download {}", hash);
+                let _ = arboard::Clipboard::new()
+                    .and_then(|mut clipboard| clipboard.set_text(command.clone()));
+                format!("Map uploaded, others can play it with {}", command)
+            }
+            Transfer::Downloaded { hash } => match level::load(ctx, &workshop::level_path(&hash)) {
+                Ok(level) => {
+                    if let Err(e) = thumbnail::create(ctx, &level) {
+                        log::warn!("No thumbnail for map {}: {}", hash, e);
+                    }
+                    match Minimap::new(ctx, &level) {
+                        Ok(minimap) => self.minimap = minimap,
+                        Err(e) => log::warn!("No minimap for map {}: {}", hash, e),
+                    }
+                    self.background = Background::new(ctx, &level.backgrounds);
+                    let short_hash: String = hash.chars().take(8).collect();
+                    self.profile.set_map(&format!("Downloaded {}", short_hash));
+                    self.world.level = level;
+                    self.next_round();
+                    format!("Playing downloaded map {}", hash)
+                }
+                Err(e) => e.to_string(),
+            },
+            Transfer::Failed(e) => format!("Map transfer failed: {}", e),
+        };
+        log::info!("{}", message);
+        self.toast.show(message);
+    }
+
+    /// Scoreboard rows of everyone in the match, best first.
+    fn scoreboard_rows(&self) -> Vec<Row> {
+        let mut rows: Vec<Row> = self
+            .world
+            .blobs
+            .iter()
+            .chain(self.respawner.waiting_blobs())
+            .map(|(&id, blob)| Row {
+                id,
+                team: blob.team,
+                stats: self.round.stats_of(id),
+            })
+            .collect();
+        scoreboard::sort(&mut rows);
+        rows
+    }
+
+    /// Describe the current state of the game, for bug reports.
+    fn snapshot(&self) -> String {
+        let mut lines = vec![
+            format!("Mode: {:?}", self.world.level.mode),
+            format!("Winner: {:?}", self.mode.winner()),
+            format!("Seed: {}", self.seed),
+        ];
+        let mut ids: Vec<_> = self.world.blobs.keys().collect();
+        ids.sort();
+        for id in ids {
+            let blob = &self.world.blobs[id];
+            lines.push(format!(
+                "Blob {}: team {:?}, center ({:.1}, {:.1}), vel ({:.1}, {:.1}), health {:.1}, \
+                 invulnerable {:.1}, hook {:?}",
+                id,
+                blob.team,
+                blob.center.x,
+                blob.center.y,
+                blob.vel.x,
+                blob.vel.y,
+                blob.health,
+                blob.invulnerable,
+                blob.hook
+            ));
+        }
+        for id in self.respawner.waiting() {
+            lines.push(format!("Blob {}: waiting to respawn", id));
+        }
+        for objective in &self.objectives {
+            lines.push(format!(
+                "Objective {:?}: pos ({:.1}, {:.1}), carrier {:?}",
+                objective.kind, objective.pos.x, objective.pos.y, objective.carrier
+            ));
+        }
+        lines.push(format!("Entities: {}", self.world.entities.len()));
+        lines.push(format!("Cvars:\n{}", cvar::replicated()));
+        lines.join("\n") + "\n"
+    }
+}
+
+impl Scene for Game {
+    fn update(&mut self, ctx: &mut Context) -> GameResult<Transition> {
+        if let Some(transfer) = self.workshop.poll() {
+            self.finish_transfer(ctx, transfer);
+        }
+        if let Some(report) = self.stress_run.as_ref().and_then(stress::Run::poll) {
+            self.stress_run = None;
+            self.finish_stress_run(ctx, report);
+        }
+        self.toast.update();
+        self.chat.update();
+        self.background.update();
+        if let Some(result) = self.tuning.update(ctx) {
+            let message = match result {
+                Ok(()) => "Tuning reloaded".to_string(),
+                Err(e) => format!("Tuning not reloaded: {}", e),
+            };
+            log::info!("{}", message);
+            self.toast.show(message);
+        }
+        if let Some(photo) = &mut self.photo {
+            photo.update(ctx)?;
+            return Ok(Transition::None);
+        }
+        self.batch.clear();
+        if let Some(playback) = &mut self.playback {
+            playback.update(ctx);
+            self.telestrator.update();
+            return Ok(Transition::None);
+        }
+        if let Some(overlay) = &mut self.debug_overlay {
+            if !overlay.advance() {
+                return Ok(Transition::None);
+            }
+            overlay.tick();
+        }
+        logging::next_frame();
+        // Taken before the countdown check, so clicks made while waiting for
+        // the round don't all land when it starts.
+        let input = self.take_input(ctx);
+        self.round.tick();
+        if !self.round.is_playing() {
+            return Ok(Transition::None);
+        }
+        let local_team = self.world.blobs.get(&LOCAL_ID).map(|blob| blob.team);
+        if self.afk.update(local_team) {
+            self.world.blobs.remove(&LOCAL_ID);
+            self.world.damage.retain(|damage| damage.target != LOCAL_ID);
+            self.toast
+                .show("Moved to the spectators for being away".to_string());
+        }
+        if let Some(blob) = self.world.blobs.get_mut(&LOCAL_ID) {
+            input.apply(blob);
+            for _ in 0..input.projectiles {
+                self.world
+                    .entities
+                    .push(Entity::Projectile(Projectile::fire(LOCAL_ID, blob)));
+            }
+        }
+        for (id, bot) in self.practice_bots.iter_mut() {
+            if let Some(blob) = self.world.blobs.get_mut(id) {
+                bot.update(blob);
+            }
+        }
+        for (id, bot) in self.bots.iter_mut() {
+            if let Some(blob) = self.world.blobs.get_mut(id) {
+                bot.update(blob, &mut self.rng);
+            }
+        }
+        self.world.step(Rules {
+            hostile_entry: self.mode.hostile_base_entry(),
+            allows_damage: self.mode.allows_damage(),
+        });
+        for impact in &self.world.impacts {
+            match *impact {
+                Impact::Hook { point, back } => {
+                    self.particles.emit(&particles::HOOK_SPARKS, point, back)
+                }
+                Impact::Blob { speed, wall, .. } => {
+                    self.shake.impact(speed);
+                    if speed > DUST_SPEED {
+                        if let Some((point, normal)) = wall {
+                            self.particles.emit(&particles::WALL_DUST, point, normal);
+                        }
+                    }
+                }
+            }
+        }
+        for event in &self.world.events {
+            if let GameEvent::Died { target, .. } = *event {
+                self.shake.add(shake::DEATH);
+                if let Some(bot) = self.practice_bots.get_mut(&target) {
+                    bot.restart();
+                }
+            }
+        }
+        self.shake.update();
+        for objective in self.objectives.iter_mut() {
+            objective.update(&mut self.world.blobs);
+        }
+        let carriers: Vec<Option<usize>> = self.objectives.iter().map(|o| o.carrier).collect();
+        self.mode.update(
+            &mut self.world.blobs,
+            &mut self.objectives,
+            &self.world.level,
+            &self.world.grid,
+            &self.world.events,
+        );
+        // Only a capture sends a carried objective straight home.
+        for (objective, carrier) in self.objectives.iter().zip(carriers) {
+            if let (Some(carrier), None) = (carrier, objective.carrier) {
+                if objective.at_home() {
+                    let team = self.world.blobs.get(&carrier).and_then(|blob| blob.team);
+                    self.recorder.mark(ReplayEvent::Capture { carrier, team });
+                }
+            }
+        }
+        self.round.record(&self.world.events);
+        self.respawner.update(
+            &mut self.world.blobs,
+            &self.world.level,
+            &self.world.events,
+            self.mode.suicide_respawn_penalty(),
+        );
+        // Effects and bookkeeping that don't feed back into the simulation.
+        let (blobs, events) = (&self.world.blobs, &self.world.events);
+        let effects = &mut self.particles;
+        let kill_feed = &mut self.kill_feed;
+        let recorder = &mut self.recorder;
+        Stage::new()
+            .add(move || {
+                effects.update();
+                for event in events {
+                    if let GameEvent::Died { target, pos, .. } = *event {
+                        let color = blobs
+                            .get(&target)
+                            .map(|blob| Skin::new(target, blob.team).body)
+                            .unwrap_or(particles::DEATH_BURST.color);
+                        effects.emit(&particles::DEATH_BURST.color(color), pos, Vector2::y());
+                    }
+                }
+            })
+            .add(move || kill_feed.update(events))
+            .add(move || recorder.record(blobs, events))
+            .run(&self.workers);
+        if let Some(local_blob) = self.world.blobs.get(&LOCAL_ID) {
+            self.sound_effects.play(
+                ctx,
+                &self.world.events,
+                &self.world.blobs,
+                local_blob.center,
+            )?;
+            self.carrier_cues
+                .update(ctx, &self.objectives, local_blob.center)?;
+        }
+        self.flybys
+            .update(ctx, &self.world.blobs, &mut self.particles)?;
+        self.speedometer.update(self.world.blobs.get(&LOCAL_ID));
+        self.hints.update(self.world.blobs.get(&LOCAL_ID));
+        self.profile.update(
+            LOCAL_ID,
+            self.world.blobs.get(&LOCAL_ID),
+            &self.world.events,
+        );
+        if let Some(winner) = self.mode.winner() {
+            self.profile.save();
+            let results = Results::new(ctx, &self.round, &winner, &self.world.level.hash);
+            self.next_round();
+            return Ok(Transition::Push(Box::new(results)));
+        }
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        if settings::current().low_latency {
+            // Show the aim where the mouse is now rather than where it was
+            // at the last tick. Only for drawing, the next tick aims anew.
+            let mouse_pos = scene::screen_point(ctx, mouse::position(ctx).into());
+            if let Some(blob) = self.world.blobs.get_mut(&LOCAL_ID) {
+                blob.aim_vec = (mouse_pos - blob.center).normalize();
+            }
+        }
+        if let Some(playback) = &self.playback {
+            playback.draw(ctx)?;
+            self.telestrator.draw(ctx)?;
+            return self.toast.draw(ctx);
+        }
+        if let Some(photo) = self.photo.take() {
+            let (camera, zoom) = photo.camera();
+            let drawn = photo.draw(ctx, |ctx| self.draw_world(ctx, camera, zoom));
+            self.photo = Some(photo);
+            drawn?;
+            return self.toast.draw(ctx);
+        }
+        let shake = std::mem::replace(&mut self.shake, Shake::new());
+        let drawn = shake.draw(ctx, |ctx| {
+            let middle = Point2::new(SCREEN_SIZE.0 / 2.0, SCREEN_SIZE.1 / 2.0);
+            self.draw_world(ctx, middle, 1.0)?;
+            for objective in &self.objectives {
+                objective.draw_marker(ctx)?;
+            }
+            let show_own_nameplate = settings::current().show_own_nameplate;
+            for (&id, blob) in &self.world.blobs {
+                let hidden = self.mode.camouflage(id) > 0.0;
+                if id == LOCAL_ID && show_own_nameplate || id != LOCAL_ID && !hidden {
+                    nameplate::draw(ctx, id, blob)?;
+                }
+            }
+            Ok(())
+        });
+        self.shake = shake;
+        drawn?;
+        self.mode.draw_status(ctx)?;
+        let (width, height) = SCREEN_SIZE;
+        let top_right = Point2::new(width, 0.0);
+        let bottom_left = Point2::new(0.0, height);
+        let bottom_right = Point2::new(width, height);
+        let center = Point2::new(width / 2.0, height / 2.0);
+        ui::draw_scaled(ctx, top_right, |ctx| self.kill_feed.draw(ctx))?;
+        if let Some(local_blob) = self.world.blobs.get(&LOCAL_ID) {
+            ui::draw_scaled(ctx, bottom_left, |ctx| {
+                pickup::draw_effects(ctx, &local_blob.effects)
+            })?;
+        }
+        if self.show_minimap {
+            ui::draw_scaled(ctx, bottom_right, |ctx| {
+                let mode = &self.mode;
+                let shown = self
+                    .world
+                    .blobs
+                    .iter()
+                    .filter(|&(&id, _)| id == LOCAL_ID || mode.camouflage(id) == 0.0);
+                self.minimap.draw(ctx, shown, &self.objectives)
+            })?;
+        }
+        hud::draw(
+            ctx,
+            LOCAL_ID,
+            self.world.blobs.get(&LOCAL_ID),
+            self.mode.as_ref(),
+        )?;
+        ui::draw_scaled(ctx, bottom_left, |ctx| self.chat.draw(ctx))?;
+        ui::draw_scaled(ctx, center, |ctx| self.round.draw(ctx))?;
+        ui::draw_scaled(ctx, center, |ctx| self.afk.draw_warning(ctx))?;
+        let top_center = Point2::new(width / 2.0, 0.0);
+        ui::draw_scaled(ctx, top_center, |ctx| self.hints.draw(ctx))?;
+        if self.prompt.is_none()
+            && keyboard::is_key_pressed(ctx, settings::current().keys.scoreboard)
+        {
+            let rows = self.scoreboard_rows();
+            ui::draw_scaled(ctx, center, |ctx| scoreboard::draw(ctx, &rows))?;
+        }
+        if let Some(overlay) = &self.debug_overlay {
+            overlay.draw(
+                ctx,
+                &self.world.blobs,
+                &self.world.level,
+                self.recorder.pool_stats(),
+            )?;
+        }
+        if let Some(menu) = &self.spawn_menu {
+            menu.draw(ctx)?;
+        }
+        if let Some((_, prompt)) = &self.prompt {
+            prompt.draw(ctx)?;
+        }
+        let bottom_center = Point2::new(width / 2.0, height);
+        ui::draw_scaled(ctx, bottom_center, |ctx| self.speedometer.draw(ctx))?;
+        ui::draw_scaled(ctx, bottom_center, |ctx| self.toast.draw(ctx))?;
+        if self.shows_crosshair() {
+            let mouse_pos = scene::screen_point(ctx, mouse::position(ctx).into());
+            let hook = self
+                .world
+                .blobs
+                .get(&LOCAL_ID)
+                .map_or(&HookState::None, |blob| &blob.hook);
+            let crosshair = settings::current().crosshair;
+            ui::draw_scaled(ctx, mouse_pos, |ctx| crosshair.draw(ctx, mouse_pos, hook))?;
+        }
+        Ok(())
+    }
+
+    fn key_down(&mut self, ctx: &mut Context, keycode: KeyCode, keymods: KeyMods) -> Transition {
+        self.note_input();
+        if let Some((action, prompt)) = &mut self.prompt {
+            match keycode {
+                KeyCode::Escape => self.prompt = None,
+                KeyCode::Back => prompt.backspace(),
+                KeyCode::V if keymods.contains(KeyMods::CTRL) => prompt.paste(),
+                KeyCode::Return => {
+                    let text = prompt.text().to_string();
+                    match action {
+                        PromptAction::LoadReplay => match replay::load(ctx, &text) {
+                            Ok(replay) => {
+                                self.playback = Some(Playback::new(replay));
+                                self.telestrator.clear();
+                            }
+                            Err(e) => self.toast.show(e.to_string()),
+                        },
+                        PromptAction::RunCommand => match Command::parse(&text) {
+                            Ok(command) => self.run_command(ctx, command),
+                            Err(e) => self.toast.show(e),
+                        },
+                    }
+                    self.prompt = None;
+                }
+                _ => {}
+            }
+            return Transition::None;
+        }
+        let keys = settings::current().keys;
+        if let Some(photo) = &mut self.photo {
+            match keycode {
+                KeyCode::Escape => self.photo = None,
+                key if key == keys.photo_mode => self.photo = None,
+                KeyCode::Space => self.take_photo(ctx),
+                _ => photo.key_down(keycode),
+            }
+            return Transition::None;
+        }
+        if let Some(playback) = &mut self.playback {
+            if playback.key_down(keycode) {
+                return Transition::None;
+            }
+        }
+        if let Some(overlay) = &mut self.debug_overlay {
+            if overlay.key_down(keycode) {
+                return Transition::None;
+            }
+        }
+        if let Some(menu) = &mut self.spawn_menu {
+            if menu.key_down(keycode) {
+                return Transition::None;
+            }
+        }
+        match keycode {
+            KeyCode::Escape if self.playback.is_some() => self.playback = None,
+            KeyCode::Back if self.playback.is_some() => self.telestrator.clear(),
+            KeyCode::Escape => {
+                let paused = Paused::new(self.snapshot(), self.recorder.highlight());
+                return Transition::Push(Box::new(paused));
+            }
+            KeyCode::F3 => {
+                self.debug_overlay = match self.debug_overlay {
+                    Some(_) => None,
+                    None => Some(DebugOverlay::new()),
+                }
+            }
+            KeyCode::F4 if debug::dev_tools() && self.playback.is_none() => {
+                self.spawn_menu = match self.spawn_menu {
+                    Some(_) => None,
+                    None => Some(SpawnMenu::new()),
+                }
+            }
+            key if key == keys.share_highlight => self.share_highlight(ctx),
+            key if key == keys.photo_mode && self.playback.is_none() => match PhotoMode::new(ctx) {
+                Ok(photo) => self.photo = Some(photo),
+                Err(e) => self
+                    .toast
+                    .show(format!("Could not start photo mode: {}", e)),
+            },
+            key if key == keys.minimap => self.show_minimap = !self.show_minimap,
+            key if key == keys.kill && self.playback.is_none() => self.kill_local(),
+            key if key == keys.dismiss_hint => {
+                self.hints.dismiss();
+            }
+            key if key == keys.load_replay => {
+                self.prompt = Some((
+                    PromptAction::LoadReplay,
+                    TextPrompt::new(
+                        "Paste a replay code (Ctrl+V) or type a saved file name, then press Enter",
+                    ),
+                ))
+            }
+            // The key's character, a slash by default, arrives as text input
+            // and starts the command.
+            key if key == keys.console => {
+                self.prompt = Some((
+                    PromptAction::RunCommand,
+                    TextPrompt::new("Console: type a command such as /bugreport"),
+                ))
+            }
+            key if key == keys.chat => {
+                self.prompt = Some((
+                    PromptAction::RunCommand,
+                    TextPrompt::new("Say something, or type a command such as /me"),
+                ))
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+
+    fn text_input(&mut self, _ctx: &mut Context, character: char) {
+        if let Some((_, prompt)) = &mut self.prompt {
+            prompt.input(character);
+        }
+    }
+
+    fn is_menu(&self) -> bool {
+        false
+    }
+
+    fn music(&self) -> Track {
+        Track::Game
+    }
+
+    fn shows_crosshair(&self) -> bool {
+        self.photo.is_none() && self.playback.is_none()
+    }
+
+    fn mouse_motion(&mut self, _ctx: &mut Context, x: f32, y: f32) {
+        self.note_input();
+        let cursor_pos = Point2::new(x, y);
+        if let Some(playback) = &mut self.playback {
+            if !playback.mouse_motion(cursor_pos) {
+                self.telestrator.mouse_motion(cursor_pos);
+            }
+            return;
+        }
+        // Shown right away, the next tick aims anew.
+        // TODO: Ensure that aim_vec can never be (0, 0)
+        self.world
+            .blobs
+            .entry(LOCAL_ID)
+            .and_modify(|blob| blob.aim_vec = (cursor_pos - blob.center).normalize());
+    }
+
+    fn mouse_button_down(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        self.note_input();
+        if self.photo.is_some() {
+            return;
+        }
+        if let Some(playback) = &mut self.playback {
+            let point = Point2::new(x, y);
+            if button != MouseButton::Left || !playback.mouse_button_down(point) {
+                self.telestrator.mouse_button_down(button, point);
+            }
+            return;
+        }
+        if let (Some(menu), MouseButton::Left) = (&self.spawn_menu, button) {
+            let spawn = menu.spawn(Point2::new(x, y));
+            return self.spawn(spawn);
+        }
+        // Played out at the next tick.
+        if button == MouseButton::Right {
+            self.input.actions.push(Input::Release);
+        } else if button == MouseButton::Left {
+            if let Some(blob) = self.world.blobs.get(&LOCAL_ID) {
+                // TODO: Ensure that aim_vec can never be (0, 0)
+                let direction = (Point2::new(x, y) - blob.center).normalize();
+                self.input.actions.push(Input::Hook(direction));
+            }
+        } else if button == MouseButton::Middle {
+            self.input.projectiles += 1;
+        }
+    }
+
+    fn mouse_button_up(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if let Some(playback) = &mut self.playback {
+            if button != MouseButton::Left || !playback.mouse_button_up() {
+                self.telestrator.mouse_button_up(button, Point2::new(x, y));
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use ggez::filesystem;
+use ggez::graphics::{self, Image};
+use ggez::input::keyboard::{KeyCode, KeyMods};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+
+use crate::level;
+use crate::ratings::Ratings;
+use crate::scene::game::Game;
+use crate::scene::{draw_centered, MenuList, Scene, Transition};
+use crate::thumbnail::{self, THUMBNAIL_SIZE};
+use crate::SCREEN_SIZE;
+
+const LEVEL_DIR: &str = "/levels";
+
+/// Pick a level to play, or a random one weighted by how the levels were
+/// rated after matches.
+pub struct Lobby {
+    level_paths: Vec<String>,
+    names: Vec<String>,
+    hashes: Vec<String>,
+    thumbnails: Vec<Option<Image>>,
+    items: MenuList,
+    error: Option<String>,
+    ratings: Ratings,
+    // Whether a match was started, after which the ratings may have changed.
+    ratings_stale: bool,
+}
+
+/// Paths of the levels shipped with the game, in order.
+pub fn level_paths(ctx: &mut Context) -> Vec<String> {
+    let mut level_paths: Vec<String> = filesystem::read_dir(ctx, LEVEL_DIR)
+        .map(|paths| {
+            paths
+                .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+                .map(|path| path.to_string_lossy().replace('\\', "/"))
+                .collect()
+        })
+        .unwrap_or_default();
+    level_paths.sort();
+    level_paths
+}
+
+impl Lobby {
+    pub fn new(ctx: &mut Context) -> Lobby {
+        let level_paths = level_paths(ctx);
+        let names: Vec<String> = level_paths
+            .iter()
+            .map(|path| {
+                path.trim_start_matches(LEVEL_DIR)
+                    .trim_start_matches('/')
+                    .trim_end_matches(".ron")
+                    .to_string()
+            })
+            .collect();
+        let mut hashes = Vec::new();
+        let mut thumbnails = Vec::new();
+        for path in &level_paths {
+            let level = level::load(ctx, path).ok();
+            hashes.push(
+                level
+                    .as_ref()
+                    .map(|level| level.hash.clone())
+                    .unwrap_or_default(),
+            );
+            thumbnails.push(level.and_then(|level| {
+                thumbnail::load_or_create(ctx, &level)
+                    .map_err(|e| log::warn!("No thumbnail for {}: {}", path, e))
+                    .ok()
+            }));
+        }
+        let mut lobby = Lobby {
+            level_paths,
+            names,
+            hashes,
+            thumbnails,
+            items: MenuList::new(Vec::new()),
+            error: None,
+            ratings: Ratings::load(ctx),
+            ratings_stale: false,
+        };
+        lobby.update_items();
+        lobby
+    }
+
+    fn update_items(&mut self) {
+        let mut items: Vec<String> = self
+            .names
+            .iter()
+            .zip(&self.hashes)
+            .map(|(name, hash)| {
+                let rating = self.ratings.get(hash);
+                if rating.up + rating.down == 0 {
+                    name.clone()
+                } else {
+                    format!("{}  ({})", name, rating.summary())
+                }
+            })
+            .collect();
+        items.push("Random, favoring the best rated".to_string());
+        self.items.set_items(items);
+    }
+}
+
+impl Scene for Lobby {
+    fn update(&mut self, ctx: &mut Context) -> GameResult<Transition> {
+        if self.ratings_stale {
+            self.ratings = Ratings::load(ctx);
+            self.ratings_stale = false;
+            self.update_items();
+        }
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        draw_centered(ctx, "Choose a level", 48.0, 150.0)?;
+        self.items.draw(ctx, 260.0)?;
+        if let Some(Some(thumbnail)) = self.thumbnails.get(self.items.selected()) {
+            let size = f32::from(THUMBNAIL_SIZE);
+            let dest = Point2::new((SCREEN_SIZE.0 - size) / 2.0, SCREEN_SIZE.1 - size - 100.0);
+            graphics::draw(ctx, thumbnail, graphics::DrawParam::new().dest(dest))?;
+        }
+        let hint = self
+            .error
+            .clone()
+            .unwrap_or_else(|| "Enter to play, Esc to go back".to_string());
+        let text = graphics::Text::new(hint);
+        let dest = Point2::new(
+            (SCREEN_SIZE.0 - text.width(ctx) as f32) / 2.0,
+            SCREEN_SIZE.1 - 60.0,
+        );
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new().dest(dest).color(graphics::BLACK),
+        )
+    }
+
+    fn key_down(&mut self, ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods) -> Transition {
+        if keycode == KeyCode::Escape {
+            return Transition::Pop(1);
+        }
+        let chosen = match self.items.key_down(keycode) {
+            Some(i) if i == self.level_paths.len() => self.ratings.pick(&self.hashes),
+            chosen => chosen,
+        };
+        match chosen {
+            Some(i) => match Game::new(ctx, &self.level_paths[i]) {
+                Ok(game) => {
+                    self.error = None;
+                    self.ratings_stale = true;
+                    Transition::Push(Box::new(game))
+                }
+                Err(e) => {
+                    self.error = Some(e.to_string());
+                    Transition::None
+                }
+            },
+            None => Transition::None,
+        }
+    }
+}
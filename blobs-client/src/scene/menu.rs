@@ -0,0 +1,97 @@
+use ggez::graphics;
+use ggez::input::keyboard::{KeyCode, KeyMods};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+use std::sync::mpsc::Receiver;
+
+use crate::scene::lobby::Lobby;
+use crate::scene::profile::ProfileScene;
+use crate::scene::screensaver::Screensaver;
+use crate::scene::settings::SettingsScene;
+use crate::scene::{draw_centered, MenuList, Scene, Transition};
+use crate::update;
+use crate::SCREEN_SIZE;
+
+const PLAY: usize = 0;
+const PROFILE: usize = 1;
+const SETTINGS: usize = 2;
+const QUIT: usize = 3;
+
+/// The first screen, shown at startup.
+pub struct MainMenu {
+    items: MenuList,
+    update_check: Option<Receiver<String>>,
+    update_notice: Option<String>,
+    // Bots swinging behind the menu, if there is a level to swing in.
+    background: Option<Screensaver>,
+}
+
+impl MainMenu {
+    pub fn new(ctx: &mut Context) -> MainMenu {
+        let background = Screensaver::new(ctx)
+            .map_err(|e| log::warn!("No menu background: {}", e))
+            .ok();
+        MainMenu {
+            items: MenuList::new(vec![
+                "Play".to_string(),
+                "Profile".to_string(),
+                "Settings".to_string(),
+                "Quit".to_string(),
+            ]),
+            update_check: update::check_in_background(),
+            update_notice: None,
+            background,
+        }
+    }
+}
+
+impl Scene for MainMenu {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult<Transition> {
+        if let Some(notice) = self.update_check.as_ref().and_then(|r| r.try_recv().ok()) {
+            self.update_notice = Some(notice);
+        }
+        if let Some(background) = &mut self.background {
+            background.tick();
+        }
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        if let Some(background) = &mut self.background {
+            background.draw_world(ctx)?;
+            // Fade it out, so the menu stands out.
+            let veil = graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(0.0, 0.0, SCREEN_SIZE.0, SCREEN_SIZE.1),
+                graphics::Color::new(1.0, 1.0, 1.0, 0.7),
+            )?;
+            graphics::draw(ctx, &veil, graphics::DrawParam::new())?;
+        }
+        draw_centered(ctx, "Blobs!", 72.0, 200.0)?;
+        self.items.draw(ctx, 400.0)?;
+        if let Some(notice) = &self.update_notice {
+            let text = graphics::Text::new(notice.as_str());
+            let dest = Point2::new(
+                (SCREEN_SIZE.0 - text.width(ctx) as f32) / 2.0,
+                SCREEN_SIZE.1 - 60.0,
+            );
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::new().dest(dest).color(graphics::BLACK),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn key_down(&mut self, ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods) -> Transition {
+        match self.items.key_down(keycode) {
+            Some(PLAY) => Transition::Push(Box::new(Lobby::new(ctx))),
+            Some(PROFILE) => Transition::Push(Box::new(ProfileScene::new(ctx))),
+            Some(SETTINGS) => Transition::Push(Box::new(SettingsScene::new())),
+            Some(QUIT) => Transition::Quit,
+            _ => Transition::None,
+        }
+    }
+}
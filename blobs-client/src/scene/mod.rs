@@ -0,0 +1,399 @@
+use ggez::event::{self, EventHandler};
+use ggez::graphics::{self, Color};
+use ggez::input::keyboard::{KeyCode, KeyMods};
+use ggez::input::mouse::{self, MouseButton};
+use ggez::timer;
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+
+use crate::framerate::FrameLimiter;
+use crate::music::{Music, Track};
+use crate::screenshot;
+use crate::text::{self, Effect, Style};
+use crate::timestep::Accumulator;
+use crate::toast::Toast;
+use crate::SCREEN_SIZE;
+
+// Fills the window around the level when their shapes differ.
+const LETTERBOX_COLOR: Color = Color {
+    r: 0.15,
+    g: 0.15,
+    b: 0.15,
+    a: 1.0,
+};
+
+pub mod crosshair;
+pub mod game;
+pub mod lobby;
+pub mod menu;
+pub mod paused;
+pub mod profile;
+pub mod results;
+pub mod screensaver;
+pub mod settings;
+
+/// What the scene stack should do after a scene handled something.
+pub enum Transition {
+    None,
+    /// Put a new scene on top.
+    Push(Box<dyn Scene>),
+    /// Remove this many scenes from the top.
+    Pop(usize),
+    /// Go back to the first scene, the main menu.
+    PopAll,
+    Quit,
+}
+
+/// A screen of the game, such as a menu or a match, that owns its own
+/// update, drawing and input handling.
+pub trait Scene {
+    /// Step the scene forward one tick.
+    fn update(&mut self, ctx: &mut Context) -> GameResult<Transition>;
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()>;
+
+    fn key_down(&mut self, ctx: &mut Context, keycode: KeyCode, keymods: KeyMods) -> Transition;
+
+    fn text_input(&mut self, _ctx: &mut Context, _character: char) {}
+
+    fn mouse_motion(&mut self, _ctx: &mut Context, _x: f32, _y: f32) {}
+
+    fn mouse_button_down(&mut self, _ctx: &mut Context, _button: MouseButton, _x: f32, _y: f32) {}
+
+    fn mouse_button_up(&mut self, _ctx: &mut Context, _button: MouseButton, _x: f32, _y: f32) {}
+
+    /// Menus are drawn at a lower frame rate, to save power.
+    fn is_menu(&self) -> bool {
+        true
+    }
+
+    /// Background music to play while this scene is shown.
+    fn music(&self) -> Track {
+        Track::Menu
+    }
+
+    /// Overlays are drawn on top of the scene below them, e.g. a pause menu
+    /// on top of the paused match.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
+    /// Scenes that aim with the mouse hide the cursor and draw the
+    /// crosshair instead.
+    fn shows_crosshair(&self) -> bool {
+        false
+    }
+}
+
+/// Stack of scenes. Only the top scene is updated and gets input. F12 takes
+/// a screenshot in any scene.
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+    frame_limiter: FrameLimiter,
+    focused: bool,
+    music: Music,
+    timestep: Accumulator,
+    // Set by F12, the screenshot is taken when the next frame is drawn.
+    screenshot: bool,
+    toast: Toast,
+}
+
+impl SceneStack {
+    pub fn new(ctx: &mut Context, first: Box<dyn Scene>) -> SceneStack {
+        SceneStack {
+            scenes: vec![first],
+            frame_limiter: FrameLimiter::new(),
+            focused: true,
+            music: Music::new(ctx),
+            timestep: Accumulator::new(),
+            screenshot: false,
+            toast: Toast::new(),
+        }
+    }
+
+    pub fn push(&mut self, scene: Box<dyn Scene>) {
+        self.scenes.push(scene);
+    }
+
+    fn apply(&mut self, ctx: &mut Context, transition: Transition) {
+        match transition {
+            Transition::None => {}
+            Transition::Push(scene) => self.scenes.push(scene),
+            Transition::Pop(count) => {
+                // The first scene is never removed.
+                let len = self.scenes.len().saturating_sub(count).max(1);
+                self.scenes.truncate(len);
+            }
+            Transition::PopAll => self.scenes.truncate(1),
+            Transition::Quit => {
+                crate::settings::remember_window_position(ctx);
+                event::quit(ctx)
+            }
+        }
+    }
+
+    fn top(&mut self) -> &mut Box<dyn Scene> {
+        self.scenes.last_mut().expect("scene stack is never empty")
+    }
+
+    /// Index of the lowest scene that is drawn: the top scene that isn't an
+    /// overlay.
+    fn first_drawn(&self) -> usize {
+        self.scenes
+            .iter()
+            .rposition(|scene| !scene.is_overlay())
+            .unwrap_or(0)
+    }
+}
+
+impl EventHandler for SceneStack {
+    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        // Every tick is consumed, even by scenes that don't simulate anything,
+        // so a match doesn't fast forward after sitting below a menu.
+        self.timestep.add(timer::delta(ctx));
+        while self.timestep.tick() {
+            let transition = self.top().update(ctx)?;
+            self.apply(ctx, transition);
+            // Overlays such as the pause menu keep the music of the scene
+            // below them.
+            let track = self.scenes[self.first_drawn()].music();
+            self.music.update(track);
+            self.toast.update();
+        }
+        let hidden = self.top().shows_crosshair();
+        if mouse::cursor_hidden(ctx) != hidden {
+            mouse::set_cursor_hidden(ctx, hidden);
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        graphics::clear(ctx, graphics::WHITE);
+        let first_drawn = self.first_drawn();
+        for scene in &mut self.scenes[first_drawn..] {
+            scene.draw(ctx)?;
+        }
+        draw_letterbox(ctx)?;
+        if std::mem::replace(&mut self.screenshot, false) {
+            match screenshot::save(ctx) {
+                Ok(path) => {
+                    log::info!("Saved screenshot {}", path);
+                    self.toast.show(format!("Saved screenshot {}", path));
+                }
+                Err(e) => {
+                    log::warn!("Could not save screenshot: {}", e);
+                    self.toast.show(format!("Could not save screenshot: {}", e));
+                }
+            }
+        }
+        self.toast.draw(ctx)?;
+        graphics::present(ctx)?;
+        let max_fps = crate::settings::current().max_fps(self.focused, self.top().is_menu());
+        self.frame_limiter.wait(max_fps);
+        Ok(())
+    }
+
+    fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        keycode: KeyCode,
+        keymods: KeyMods,
+        _repeat: bool,
+    ) {
+        if keycode == KeyCode::Return && keymods.contains(KeyMods::ALT) {
+            if let Err(e) = crate::settings::toggle_fullscreen(ctx) {
+                log::warn!("Could not switch fullscreen: {}", e);
+            }
+            return;
+        }
+        if keycode == KeyCode::F12 {
+            self.screenshot = true;
+            return;
+        }
+        let transition = self.top().key_down(ctx, keycode, keymods);
+        self.apply(ctx, transition);
+    }
+
+    fn quit_event(&mut self, ctx: &mut Context) -> bool {
+        crate::settings::remember_window_position(ctx);
+        false
+    }
+
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        if let Err(e) = fit_screen(ctx, width, height) {
+            log::warn!("Could not fit the screen to the window: {}", e);
+        }
+    }
+
+    fn focus_event(&mut self, _ctx: &mut Context, gained: bool) {
+        self.focused = gained;
+    }
+
+    fn text_input_event(&mut self, ctx: &mut Context, character: char) {
+        self.top().text_input(ctx, character);
+    }
+
+    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        let p = screen_point(ctx, Point2::new(x, y));
+        self.top().mouse_motion(ctx, p.x, p.y);
+    }
+
+    fn mouse_button_down_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        let p = screen_point(ctx, Point2::new(x, y));
+        self.top().mouse_button_down(ctx, button, p.x, p.y);
+    }
+
+    fn mouse_button_up_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        let p = screen_point(ctx, Point2::new(x, y));
+        self.top().mouse_button_up(ctx, button, p.x, p.y);
+    }
+}
+
+/// Vertical list of choices, picked with the arrow keys and Enter.
+pub struct MenuList {
+    items: Vec<String>,
+    selected: usize,
+}
+
+impl MenuList {
+    pub fn new(items: Vec<String>) -> MenuList {
+        MenuList { items, selected: 0 }
+    }
+
+    /// Replace the item texts, keeping the selection.
+    pub fn set_items(&mut self, items: Vec<String>) {
+        self.selected = self.selected.min(items.len().max(1) - 1);
+        self.items = items;
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Move the selection. Returns the index of the chosen item when Enter
+    /// is pressed.
+    pub fn key_down(&mut self, keycode: KeyCode) -> Option<usize> {
+        match keycode {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(self.items.len().max(1) - 1),
+            KeyCode::Return if !self.items.is_empty() => return Some(self.selected),
+            _ => {}
+        }
+        None
+    }
+
+    /// Draw the items centered horizontally, starting at y.
+    pub fn draw(&self, ctx: &mut Context, y: f32) -> GameResult<()> {
+        self.draw_scrolled(ctx, y, self.items.len())
+    }
+
+    /// Draw at most the given number of items, scrolled to keep the
+    /// selected one near the middle.
+    pub fn draw_scrolled(&self, ctx: &mut Context, y: f32, visible: usize) -> GameResult<()> {
+        let first = self
+            .selected
+            .saturating_sub(visible / 2)
+            .min(self.items.len().saturating_sub(visible));
+        let mut y = y;
+        for (i, item) in self.items.iter().enumerate().skip(first).take(visible) {
+            let text = graphics::Text::new(
+                graphics::TextFragment::new(item.as_str()).scale(graphics::Scale::uniform(28.0)),
+            );
+            let width = text.width(ctx) as f32;
+            let x = (SCREEN_SIZE.0 - width) / 2.0;
+            if i == self.selected {
+                let highlight = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    graphics::Rect::new(x - 12.0, y - 4.0, width + 24.0, 36.0),
+                    Color::from_rgb(220, 220, 220),
+                )?;
+                graphics::draw(ctx, &highlight, graphics::DrawParam::new())?;
+            }
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::new()
+                    .dest(Point2::new(x, y))
+                    .color(graphics::BLACK),
+            )?;
+            y += 44.0;
+        }
+        Ok(())
+    }
+}
+
+/// Draw large text centered horizontally.
+pub fn draw_centered(ctx: &mut Context, string: &str, size: f32, y: f32) -> GameResult<()> {
+    let style = Style::new(size).effect(Effect::Shadow(Color::from_rgb(200, 200, 200)));
+    let (width, _) = text::size(ctx, string, style);
+    text::draw(
+        ctx,
+        string,
+        Point2::new((SCREEN_SIZE.0 - width) / 2.0, y),
+        style,
+    )
+}
+
+/// Set the screen coordinates for a window of the given size, so the whole
+/// level shows at its own aspect ratio, centered. The window's extra width
+/// or height is left over on both sides as bars.
+pub fn fit_screen(ctx: &mut Context, width: f32, height: f32) -> GameResult<()> {
+    if width <= 0.0 || height <= 0.0 {
+        return Ok(());
+    }
+    let scale = (width / SCREEN_SIZE.0).min(height / SCREEN_SIZE.1);
+    let (w, h) = (width / scale, height / scale);
+    graphics::set_screen_coordinates(
+        ctx,
+        graphics::Rect::new((SCREEN_SIZE.0 - w) / 2.0, (SCREEN_SIZE.1 - h) / 2.0, w, h),
+    )
+}
+
+/// Cover whatever the window shows beyond the level, so nothing drawn past
+/// its edges sticks out into the bars.
+fn draw_letterbox(ctx: &mut Context) -> GameResult<()> {
+    let screen = graphics::screen_coordinates(ctx);
+    let level = graphics::Rect::new(0.0, 0.0, SCREEN_SIZE.0, SCREEN_SIZE.1);
+    let bars = [
+        graphics::Rect::new(screen.x, screen.y, level.x - screen.x, screen.h),
+        graphics::Rect::new(
+            level.right(),
+            screen.y,
+            screen.right() - level.right(),
+            screen.h,
+        ),
+        graphics::Rect::new(level.x, screen.y, level.w, level.y - screen.y),
+        graphics::Rect::new(
+            level.x,
+            level.bottom(),
+            level.w,
+            screen.bottom() - level.bottom(),
+        ),
+    ];
+    let mut builder = graphics::MeshBuilder::new();
+    let mut any = false;
+    for bar in bars.iter().filter(|bar| bar.w > 0.0 && bar.h > 0.0) {
+        builder.rectangle(graphics::DrawMode::fill(), *bar, LETTERBOX_COLOR);
+        any = true;
+    }
+    if !any {
+        return Ok(());
+    }
+    let mesh = builder.build(ctx)?;
+    graphics::draw(ctx, &mesh, graphics::DrawParam::new())
+}
+
+/// Convert a point in window pixels, such as the mouse position, to screen
+/// coordinates. The whole level is always shown, whatever the window size.
+pub fn screen_point(ctx: &Context, p: Point2<f32>) -> Point2<f32> {
+    let (width, height) = graphics::drawable_size(ctx);
+    let screen = graphics::screen_coordinates(ctx);
+    if width <= 0.0 || height <= 0.0 {
+        return p;
+    }
+    Point2::new(
+        screen.x + p.x * screen.w / width,
+        screen.y + p.y * screen.h / height,
+    )
+}
@@ -0,0 +1,90 @@
+use ggez::graphics::{self, Color};
+use ggez::input::keyboard::{KeyCode, KeyMods};
+use ggez::{Context, GameResult};
+
+use crate::bugreport;
+use crate::replay::Replay;
+use crate::scene::settings::SettingsScene;
+use crate::scene::{draw_centered, MenuList, Scene, Transition};
+use crate::SCREEN_SIZE;
+
+const RESUME: usize = 0;
+const SETTINGS: usize = 1;
+const REPORT_BUG: usize = 2;
+const QUIT_TO_MENU: usize = 3;
+
+/// Pause menu shown on top of a match. The match is frozen while it is
+/// shown, since only the top scene is updated.
+pub struct Paused {
+    items: MenuList,
+    // The match as it was paused, for a bug report.
+    snapshot: String,
+    highlight: Replay,
+    // How the last bug report went.
+    message: Option<String>,
+}
+
+impl Paused {
+    pub fn new(snapshot: String, highlight: Replay) -> Paused {
+        Paused {
+            items: MenuList::new(vec![
+                "Resume".to_string(),
+                "Settings".to_string(),
+                "Report a bug".to_string(),
+                "Quit to menu".to_string(),
+            ]),
+            snapshot,
+            highlight,
+            message: None,
+        }
+    }
+}
+
+impl Scene for Paused {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let shade = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, 0.0, SCREEN_SIZE.0, SCREEN_SIZE.1),
+            Color::new(0.0, 0.0, 0.0, 0.5),
+        )?;
+        graphics::draw(ctx, &shade, graphics::DrawParam::new())?;
+        let panel = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(SCREEN_SIZE.0 / 2.0 - 200.0, 260.0, 400.0, 380.0),
+            Color::new(0.95, 0.95, 0.95, 1.0),
+        )?;
+        graphics::draw(ctx, &panel, graphics::DrawParam::new())?;
+        draw_centered(ctx, "Paused", 48.0, 300.0)?;
+        self.items.draw(ctx, 400.0)?;
+        if let Some(message) = &self.message {
+            draw_centered(ctx, message, 16.0, 580.0)?;
+        }
+        Ok(())
+    }
+
+    fn key_down(&mut self, ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods) -> Transition {
+        if keycode == KeyCode::Escape {
+            return Transition::Pop(1);
+        }
+        match self.items.key_down(keycode) {
+            Some(RESUME) => Transition::Pop(1),
+            Some(SETTINGS) => Transition::Push(Box::new(SettingsScene::new())),
+            Some(REPORT_BUG) => {
+                self.message = Some(bugreport::report(ctx, &self.snapshot, &self.highlight));
+                Transition::None
+            }
+            Some(QUIT_TO_MENU) => Transition::PopAll,
+            _ => Transition::None,
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,123 @@
+use ggez::input::keyboard::{KeyCode, KeyMods};
+use ggez::{Context, GameResult};
+
+use crate::profile::Profile;
+use crate::scene::{draw_centered, Scene, Transition};
+use crate::settings;
+use crate::tuning;
+
+// Most played maps shown.
+const FAVORITE_MAPS: usize = 3;
+
+/// The player's movement over all their matches, from the main menu. Left
+/// and right page through the seasons.
+pub struct ProfileScene {
+    profile: Profile,
+    // Index of the past season shown, or None for the current one.
+    shown: Option<usize>,
+}
+
+impl ProfileScene {
+    pub fn new(ctx: &Context) -> ProfileScene {
+        ProfileScene {
+            profile: Profile::load(ctx),
+            shown: None,
+        }
+    }
+
+    fn shown(&self) -> &Profile {
+        match self.shown {
+            Some(i) => &self.profile.past_seasons[i],
+            None => &self.profile,
+        }
+    }
+
+    /// Show the season before the one shown, or the one after if later.
+    fn page(&mut self, later: bool) {
+        let past = self.profile.past_seasons.len();
+        self.shown = match (self.shown, later) {
+            (None, false) if past > 0 => Some(past - 1),
+            (Some(i), false) if i > 0 => Some(i - 1),
+            (Some(i), true) if i + 1 < past => Some(i + 1),
+            (Some(_), true) => None,
+            (shown, _) => shown,
+        };
+    }
+}
+
+/// Seconds as hours, minutes and seconds.
+fn duration(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+impl Scene for ProfileScene {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let p = self.shown();
+        let unit = settings::current().speed_unit;
+        let blob_width = 2.0 * tuning::current().blob_radius;
+        let season = match self.shown {
+            Some(_) => format!("Season {}", p.season),
+            None => format!("Season {} (current)", p.season),
+        };
+        let mut lines = vec![
+            season,
+            String::new(),
+            format!(
+                "Swung {:.0} blob widths on the rope",
+                p.rope_distance / blob_width
+            ),
+            format!("Airtime {}", duration(p.airtime)),
+            format!("Top speed {:.0} {}", unit.convert(p.top_speed), unit.name()),
+            String::new(),
+        ];
+        let favorites = p.favorite_maps(FAVORITE_MAPS);
+        if favorites.is_empty() {
+            let none = match self.shown {
+                Some(_) => "No matches played",
+                None => "No matches played yet",
+            };
+            lines.push(none.to_string());
+        } else {
+            lines.push("Favorite maps".to_string());
+            for (name, time) in favorites {
+                lines.push(format!("{}  {}", name, duration(time)));
+            }
+        }
+
+        draw_centered(ctx, "Profile", 48.0, 150.0)?;
+        for (i, line) in lines.iter().enumerate() {
+            draw_centered(ctx, line, 24.0, 260.0 + 36.0 * i as f32)?;
+        }
+        let help = if self.profile.past_seasons.is_empty() {
+            "Esc to go back"
+        } else {
+            "Left and right for other seasons, Esc to go back"
+        };
+        draw_centered(ctx, help, 18.0, 700.0)
+    }
+
+    fn key_down(&mut self, _ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods) -> Transition {
+        match keycode {
+            KeyCode::Escape | KeyCode::Return => Transition::Pop(1),
+            KeyCode::Left => {
+                self.page(false);
+                Transition::None
+            }
+            KeyCode::Right => {
+                self.page(true);
+                Transition::None
+            }
+            _ => Transition::None,
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use ggez::graphics::{self, Color};
+use ggez::input::keyboard::{KeyCode, KeyMods};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+
+use crate::mode::Winner;
+use crate::ratings::{MapRating, Ratings};
+use crate::round::{Round, Stats};
+use crate::scene::{draw_centered, Scene, Transition};
+use crate::SCREEN_SIZE;
+
+/// Winner and per-blob stats of a finished round, where the map can be
+/// given a thumb up or down.
+pub struct Results {
+    round_number: u32,
+    announcement: String,
+    stats: Vec<(usize, Stats)>,
+    level_hash: String,
+    ratings: Ratings,
+    // The thumb given to the map after this round, once it has been rated.
+    rated: Option<bool>,
+}
+
+impl Results {
+    pub fn new(ctx: &Context, round: &Round, winner: &Winner, level_hash: &str) -> Results {
+        Results {
+            round_number: round.number(),
+            announcement: winner.announcement(),
+            stats: round.stats(),
+            level_hash: level_hash.to_string(),
+            ratings: Ratings::load(ctx),
+            rated: None,
+        }
+    }
+
+    fn rating(&self) -> MapRating {
+        self.ratings.get(&self.level_hash)
+    }
+}
+
+impl Scene for Results {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let area = graphics::Rect::new(200.0, 200.0, SCREEN_SIZE.0 - 400.0, SCREEN_SIZE.1 - 400.0);
+        let background = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            area,
+            Color::new(0.9, 0.9, 0.9, 0.95),
+        )?;
+        graphics::draw(ctx, &background, graphics::DrawParam::new())?;
+
+        let title = format!("Round {}: {}", self.round_number, self.announcement);
+        draw_centered(ctx, &title, 36.0, area.y + 20.0)?;
+
+        let column = |value: &dyn Fn(&Stats) -> String| -> Vec<String> {
+            self.stats.iter().map(|(_, stats)| value(stats)).collect()
+        };
+        let columns = [
+            (
+                "Blob",
+                self.stats.iter().map(|(id, _)| id.to_string()).collect(),
+            ),
+            ("Kills", column(&|stats| stats.kills.to_string())),
+            ("Deaths", column(&|stats| stats.deaths.to_string())),
+            ("Suicides", column(&|stats| stats.suicides.to_string())),
+            (
+                "Damage",
+                column(&|stats| format!("{:.0}", stats.damage_dealt)),
+            ),
+        ];
+        let column_width = (area.w - 80.0) / columns.len() as f32;
+        for (i, (heading, values)) in columns.iter().enumerate() {
+            let text = graphics::Text::new(format!("{}\n{}", heading, values.join("\n")));
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::new()
+                    .dest(Point2::new(
+                        area.x + 40.0 + i as f32 * column_width,
+                        area.y + 90.0,
+                    ))
+                    .color(graphics::BLACK),
+            )?;
+        }
+        let rating = match self.rated {
+            None => format!(
+                "Rate this map: Up for a thumb up, Down for a thumb down ({})",
+                self.rating().summary()
+            ),
+            Some(up) => format!(
+                "You gave this map a thumb {} ({})",
+                if up { "up" } else { "down" },
+                self.rating().summary()
+            ),
+        };
+        let text = graphics::Text::new(rating);
+        let x = (SCREEN_SIZE.0 - text.width(ctx) as f32) / 2.0;
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new()
+                .dest(Point2::new(x, area.y + area.h - 70.0))
+                .color(graphics::BLACK),
+        )?;
+        let hint = graphics::Text::new("Enter for the next round, Esc to leave the match");
+        let x = (SCREEN_SIZE.0 - hint.width(ctx) as f32) / 2.0;
+        graphics::draw(
+            ctx,
+            &hint,
+            graphics::DrawParam::new()
+                .dest(Point2::new(x, area.y + area.h - 40.0))
+                .color(graphics::BLACK),
+        )
+    }
+
+    fn key_down(&mut self, ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods) -> Transition {
+        match keycode {
+            KeyCode::Up | KeyCode::Down if self.rated.is_none() => {
+                let up = keycode == KeyCode::Up;
+                self.ratings.rate(ctx, &self.level_hash, up);
+                self.rated = Some(up);
+                Transition::None
+            }
+            KeyCode::Return => Transition::Pop(1),
+            // Back to the lobby, below the match.
+            KeyCode::Escape => Transition::Pop(2),
+            _ => Transition::None,
+        }
+    }
+}
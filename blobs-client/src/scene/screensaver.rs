@@ -0,0 +1,122 @@
+use ggez::input::keyboard::{KeyCode, KeyMods};
+use ggez::{Context, GameError, GameResult};
+use nalgebra::Point2;
+use std::collections::BTreeMap;
+
+use crate::background::Background;
+use crate::batch::Batch;
+use crate::bot::Bot;
+use crate::level::{self, Level};
+use crate::particles::{self, Particles};
+use crate::physics::{self, Bodies};
+use crate::scene::lobby;
+use crate::scene::{Scene, Transition};
+use crate::team::Team;
+use crate::{Blob, SCREEN_SIZE};
+
+const BOTS: usize = 5;
+
+/// Bot blobs swinging around a random level, with nothing to win and no HUD.
+/// Shown behind the main menu and on its own with `--screensaver`, where any
+/// key goes back to the menu.
+pub struct Screensaver {
+    level: Level,
+    background: Background,
+    blobs: BTreeMap<usize, Blob>,
+    bots: BTreeMap<usize, Bot>,
+    bodies: Bodies,
+    particles: Particles,
+    batch: Batch,
+}
+
+impl Screensaver {
+    pub fn new(ctx: &mut Context) -> GameResult<Screensaver> {
+        let level_paths = lobby::level_paths(ctx);
+        let level = match level_paths.get(random_index(level_paths.len())) {
+            Some(path) => level::load(ctx, path)?,
+            None => {
+                return Err(GameError::ResourceNotFound(
+                    "No levels for the screensaver".to_string(),
+                    Vec::new(),
+                ))
+            }
+        };
+        let mut blobs = BTreeMap::new();
+        let mut bots = BTreeMap::new();
+        for id in 0..BOTS {
+            let x = (id + 1) as f32 * SCREEN_SIZE.0 / (BOTS + 1) as f32;
+            let team = match id % 3 {
+                0 => None,
+                1 => Some(Team::Red),
+                _ => Some(Team::Blue),
+            };
+            blobs.insert(id, Blob::new(Point2::new(x, SCREEN_SIZE.1 / 3.0), team));
+            bots.insert(id, Bot::new(&mut rand::thread_rng()));
+        }
+        Ok(Screensaver {
+            background: Background::new(ctx, &level.backgrounds),
+            level,
+            blobs,
+            bots,
+            bodies: Bodies::new(),
+            particles: Particles::new(ctx)?,
+            batch: Batch::new(),
+        })
+    }
+
+    /// Step the blobs forward one tick.
+    pub fn tick(&mut self) {
+        for (id, bot) in self.bots.iter_mut() {
+            if let Some(blob) = self.blobs.get_mut(id) {
+                bot.update(blob, &mut rand::thread_rng());
+            }
+        }
+        physics::step(&mut self.blobs, &mut self.bodies);
+        for blob in self.blobs.values_mut() {
+            if let Some(hook_point) = blob.update() {
+                let back = blob.center - hook_point;
+                self.particles
+                    .emit(&particles::HOOK_SPARKS, hook_point, back);
+            }
+        }
+        self.particles.update();
+        self.background.update();
+        self.batch.clear();
+    }
+
+    pub fn draw_world(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let middle = Point2::new(SCREEN_SIZE.0 / 2.0, SCREEN_SIZE.1 / 2.0);
+        self.background.draw(ctx, middle, 1.0)?;
+        level::draw(ctx, &self.level)?;
+        if self.batch.is_empty() {
+            for (&id, blob) in &self.blobs {
+                crate::draw_blob(&mut self.batch, blob, id, false, 0.0)?;
+            }
+        }
+        self.batch.draw(ctx)?;
+        self.particles.draw(ctx)
+    }
+}
+
+fn random_index(len: usize) -> usize {
+    (rand::random::<f32>() * len as f32) as usize
+}
+
+impl Scene for Screensaver {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult<Transition> {
+        self.tick();
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        self.draw_world(ctx)
+    }
+
+    fn key_down(&mut self, _ctx: &mut Context, _keycode: KeyCode, _keymods: KeyMods) -> Transition {
+        Transition::Pop(1)
+    }
+
+    fn is_menu(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,311 @@
+use ggez::graphics;
+use ggez::input::keyboard::{KeyCode, KeyMods};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+
+use crate::scene::crosshair::CrosshairScene;
+use crate::scene::{draw_centered, MenuList, Scene, Transition};
+use crate::settings::{self, Settings, BACKGROUND_FPS_CAPS, FPS_CAPS, UI_SCALES, WINDOW_SIZES};
+use crate::speedometer::UNITS;
+use crate::SCREEN_SIZE;
+
+const WINDOW_SIZE: usize = 0;
+const FULLSCREEN: usize = 1;
+const MONITOR: usize = 2;
+const VSYNC: usize = 3;
+const LOW_LATENCY: usize = 4;
+const FPS_CAP: usize = 5;
+const BACKGROUND_FPS_CAP: usize = 6;
+const BATTERY_SAVER: usize = 7;
+const MASTER_VOLUME: usize = 8;
+const EFFECTS_VOLUME: usize = 9;
+const MUSIC_VOLUME: usize = 10;
+const OWN_NAMEPLATE: usize = 11;
+const SCREEN_SHAKE: usize = 12;
+const UI_SCALE: usize = 13;
+const SPEEDOMETER: usize = 14;
+const SPEED_UNIT: usize = 15;
+const SPEED_ARROW: usize = 16;
+const HINTS: usize = 17;
+const UPDATE_CHECK: usize = 18;
+const CROSSHAIR: usize = 19;
+const SHARE_HIGHLIGHT_KEY: usize = 20;
+const LOAD_REPLAY_KEY: usize = 21;
+const CONSOLE_KEY: usize = 22;
+const CHAT_KEY: usize = 23;
+const SCOREBOARD_KEY: usize = 24;
+const MINIMAP_KEY: usize = 25;
+const PHOTO_MODE_KEY: usize = 26;
+const PERCH_KEY: usize = 27;
+const KILL_KEY: usize = 28;
+const DISMISS_HINT_KEY: usize = 29;
+const BACK: usize = 30;
+const KEY_ROWS: [usize; 10] = [
+    SHARE_HIGHLIGHT_KEY,
+    LOAD_REPLAY_KEY,
+    CONSOLE_KEY,
+    CHAT_KEY,
+    SCOREBOARD_KEY,
+    MINIMAP_KEY,
+    PHOTO_MODE_KEY,
+    PERCH_KEY,
+    KILL_KEY,
+    DISMISS_HINT_KEY,
+];
+
+const VOLUME_STEP: f32 = 0.1;
+// Rows that fit between the title and the hint, the rest scroll.
+const VISIBLE_ROWS: usize = 18;
+
+/// Options screen. Left and Right change the selected option, Enter toggles
+/// it or waits for a new key to bind. Changes are saved right away.
+pub struct SettingsScene {
+    items: MenuList,
+    settings: Settings,
+    // The key binding row waiting for a key press.
+    rebinding: Option<usize>,
+    // Monitors connected when a key was last pressed.
+    monitors: usize,
+    error: Option<String>,
+}
+
+impl SettingsScene {
+    pub fn new() -> SettingsScene {
+        let mut scene = SettingsScene {
+            items: MenuList::new(Vec::new()),
+            settings: settings::current(),
+            rebinding: None,
+            monitors: 0,
+            error: None,
+        };
+        scene.update_items();
+        scene
+    }
+
+    fn update_items(&mut self) {
+        let s = &self.settings;
+        let on_off = |on: bool| if on { "On" } else { "Off" };
+        let key = |row: usize, keycode: KeyCode| {
+            if self.rebinding == Some(row) {
+                "press a key".to_string()
+            } else {
+                format!("{:?}", keycode)
+            }
+        };
+        self.items.set_items(vec![
+            format!("Window size: {0:.0}x{0:.0}", s.window_size),
+            format!("Fullscreen: {}", on_off(s.fullscreen)),
+            match s.monitor {
+                None => "Fullscreen monitor: Automatic".to_string(),
+                Some(i) => format!("Fullscreen monitor: {}", i + 1),
+            },
+            format!("Vsync: {} (after restart)", on_off(s.vsync)),
+            format!("Low latency: {} (after restart)", on_off(s.low_latency)),
+            match s.fps_cap {
+                0 => "Frame rate cap: Off".to_string(),
+                fps => format!("Frame rate cap: {}", fps),
+            },
+            format!("Background frame rate: {}", s.background_fps_cap),
+            format!("Battery saver: {}", on_off(s.battery_saver)),
+            format!("Master volume: {:.0}%", s.master_volume * 100.0),
+            format!("Effects volume: {:.0}%", s.effects_volume * 100.0),
+            format!("Music volume: {:.0}%", s.music_volume * 100.0),
+            format!("Own nameplate: {}", on_off(s.show_own_nameplate)),
+            format!("Screen shake: {}", on_off(s.screen_shake)),
+            format!("UI scale: {:.0}%", s.ui_scale * 100.0),
+            format!("Speedometer: {}", on_off(s.speedometer)),
+            format!("Speed unit: {}", s.speed_unit.name()),
+            format!("Direction arrow: {}", on_off(s.speed_arrow)),
+            format!("Tutorial hints: {}", on_off(s.hints)),
+            format!("Check for updates: {}", on_off(s.update_check)),
+            "Crosshair...".to_string(),
+            format!(
+                "Share highlight: {}",
+                key(SHARE_HIGHLIGHT_KEY, s.keys.share_highlight)
+            ),
+            format!("Load replay: {}", key(LOAD_REPLAY_KEY, s.keys.load_replay)),
+            format!("Console: {}", key(CONSOLE_KEY, s.keys.console)),
+            format!("Chat: {}", key(CHAT_KEY, s.keys.chat)),
+            format!("Scoreboard: {}", key(SCOREBOARD_KEY, s.keys.scoreboard)),
+            format!("Minimap: {}", key(MINIMAP_KEY, s.keys.minimap)),
+            format!("Photo mode: {}", key(PHOTO_MODE_KEY, s.keys.photo_mode)),
+            format!("Hang on walls: {}", key(PERCH_KEY, s.keys.perch)),
+            format!("Kill yourself: {}", key(KILL_KEY, s.keys.kill)),
+            format!(
+                "Don't show hint again: {}",
+                key(DISMISS_HINT_KEY, s.keys.dismiss_hint)
+            ),
+            "Back".to_string(),
+        ]);
+    }
+
+    fn binding(&mut self, row: usize) -> Option<&mut KeyCode> {
+        let keys = &mut self.settings.keys;
+        match row {
+            SHARE_HIGHLIGHT_KEY => Some(&mut keys.share_highlight),
+            LOAD_REPLAY_KEY => Some(&mut keys.load_replay),
+            CONSOLE_KEY => Some(&mut keys.console),
+            CHAT_KEY => Some(&mut keys.chat),
+            SCOREBOARD_KEY => Some(&mut keys.scoreboard),
+            MINIMAP_KEY => Some(&mut keys.minimap),
+            PHOTO_MODE_KEY => Some(&mut keys.photo_mode),
+            PERCH_KEY => Some(&mut keys.perch),
+            KILL_KEY => Some(&mut keys.kill),
+            DISMISS_HINT_KEY => Some(&mut keys.dismiss_hint),
+            _ => None,
+        }
+    }
+
+    /// Bind the key to the row's action. An action that already used the key
+    /// gets the row's old key, so no key does two things.
+    fn rebind(&mut self, row: usize, keycode: KeyCode) {
+        let old = match self.binding(row) {
+            Some(binding) => std::mem::replace(binding, keycode),
+            None => return,
+        };
+        for other in KEY_ROWS {
+            if other == row {
+                continue;
+            }
+            if let Some(binding) = self.binding(other) {
+                if *binding == keycode {
+                    *binding = old;
+                }
+            }
+        }
+    }
+
+    /// Step the selected option back or forth.
+    fn change(&mut self, row: usize, forward: bool) {
+        let step = if forward { VOLUME_STEP } else { -VOLUME_STEP };
+        let s = &mut self.settings;
+        match row {
+            WINDOW_SIZE => s.window_size = choose(&WINDOW_SIZES, s.window_size, forward),
+            FPS_CAP => s.fps_cap = choose(&FPS_CAPS, s.fps_cap, forward),
+            BACKGROUND_FPS_CAP => {
+                s.background_fps_cap = choose(&BACKGROUND_FPS_CAPS, s.background_fps_cap, forward)
+            }
+            BATTERY_SAVER => s.battery_saver = !s.battery_saver,
+            FULLSCREEN => s.fullscreen = !s.fullscreen,
+            MONITOR => {
+                let choices: Vec<Option<usize>> = std::iter::once(None)
+                    .chain((0..self.monitors).map(Some))
+                    .collect();
+                s.monitor = choose(&choices, s.monitor, forward);
+            }
+            VSYNC => s.vsync = !s.vsync,
+            LOW_LATENCY => s.low_latency = !s.low_latency,
+            OWN_NAMEPLATE => s.show_own_nameplate = !s.show_own_nameplate,
+            SCREEN_SHAKE => s.screen_shake = !s.screen_shake,
+            UI_SCALE => s.ui_scale = choose(&UI_SCALES, s.ui_scale, forward),
+            SPEEDOMETER => s.speedometer = !s.speedometer,
+            SPEED_UNIT => s.speed_unit = choose(&UNITS, s.speed_unit, forward),
+            SPEED_ARROW => s.speed_arrow = !s.speed_arrow,
+            HINTS => s.hints = !s.hints,
+            UPDATE_CHECK => s.update_check = !s.update_check,
+            MASTER_VOLUME => s.master_volume = (s.master_volume + step).clamp(0.0, 1.0),
+            EFFECTS_VOLUME => s.effects_volume = (s.effects_volume + step).clamp(0.0, 1.0),
+            MUSIC_VOLUME => s.music_volume = (s.music_volume + step).clamp(0.0, 1.0),
+            _ => {}
+        }
+    }
+
+    fn save(&mut self, ctx: &mut Context) {
+        let window_changed = {
+            let old = settings::current();
+            old.window_size != self.settings.window_size
+                || old.fullscreen != self.settings.fullscreen
+                || (self.settings.fullscreen && old.monitor != self.settings.monitor)
+        };
+        settings::save(self.settings);
+        self.error = None;
+        if window_changed {
+            if let Err(e) = settings::apply_window(ctx, &self.settings) {
+                self.error = Some(format!("Could not change the window: {}", e));
+            }
+        }
+    }
+}
+
+/// The choice before or after the current one, staying at the ends. Values
+/// that aren't among the choices start over from the first.
+pub fn choose<T: Copy + PartialEq>(choices: &[T], current: T, forward: bool) -> T {
+    let i = choices.iter().position(|&choice| choice == current);
+    let i = match (i, forward) {
+        (Some(i), true) => (i + 1).min(choices.len() - 1),
+        (Some(i), false) => i.saturating_sub(1),
+        (None, _) => 0,
+    };
+    choices[i]
+}
+
+impl Scene for SettingsScene {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult<Transition> {
+        // Alt+Enter switches fullscreen from anywhere, this screen included.
+        let fullscreen = settings::current().fullscreen;
+        if self.settings.fullscreen != fullscreen {
+            self.settings.fullscreen = fullscreen;
+            self.update_items();
+        }
+        // The crosshair is changed on its own screen, and hints are
+        // dismissed during matches.
+        let current = settings::current();
+        self.settings.crosshair = current.crosshair;
+        self.settings.dismissed_hints = current.dismissed_hints;
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        draw_centered(ctx, "Settings", 48.0, 30.0)?;
+        self.items.draw_scrolled(ctx, 100.0, VISIBLE_ROWS)?;
+        let hint = match &self.error {
+            Some(error) => error.as_str(),
+            None if self.rebinding.is_some() => "Press a key to bind, or Esc to cancel",
+            None => "Left/Right to change, Enter to toggle or rebind, Alt+Enter for fullscreen, Esc to go back",
+        };
+        let text = graphics::Text::new(hint);
+        let dest = Point2::new(
+            (SCREEN_SIZE.0 - text.width(ctx) as f32) / 2.0,
+            SCREEN_SIZE.1 - 40.0,
+        );
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new().dest(dest).color(graphics::BLACK),
+        )
+    }
+
+    fn key_down(&mut self, ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods) -> Transition {
+        if let Some(row) = self.rebinding.take() {
+            if keycode != KeyCode::Escape {
+                self.rebind(row, keycode);
+                self.save(ctx);
+            }
+            self.update_items();
+            return Transition::None;
+        }
+        let row = self.items.selected();
+        self.monitors = settings::monitor_count(ctx);
+        match keycode {
+            KeyCode::Escape => return Transition::Pop(1),
+            KeyCode::Left | KeyCode::Right => {
+                self.change(row, keycode == KeyCode::Right);
+                self.save(ctx);
+            }
+            _ => match self.items.key_down(keycode) {
+                Some(BACK) => return Transition::Pop(1),
+                Some(CROSSHAIR) => return Transition::Push(Box::new(CrosshairScene::new())),
+                Some(FULLSCREEN) | Some(VSYNC) | Some(LOW_LATENCY) | Some(BATTERY_SAVER)
+                | Some(OWN_NAMEPLATE) | Some(SCREEN_SHAKE) | Some(SPEEDOMETER)
+                | Some(SPEED_ARROW) | Some(HINTS) => {
+                    self.change(row, true);
+                    self.save(ctx);
+                }
+                Some(row) if self.binding(row).is_some() => self.rebinding = Some(row),
+                _ => {}
+            },
+        }
+        self.update_items();
+        Transition::None
+    }
+}
@@ -0,0 +1,73 @@
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+type System<'a> = Box<dyn FnOnce() + Send + 'a>;
+
+/// Threads that stages hand their systems to. They are started once and
+/// kept, so running a stage every tick doesn't start any threads.
+pub struct Workers {
+    // None if the threads couldn't be started, in which case stages run
+    // their systems one after another on the calling thread.
+    pool: Option<ThreadPool>,
+}
+
+impl Workers {
+    pub fn new(threads: usize) -> Workers {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("system worker {}", i))
+            .build();
+        match pool {
+            Ok(pool) => Workers { pool: Some(pool) },
+            Err(e) => {
+                log::warn!("Running systems on the main thread: {}", e);
+                Workers { pool: None }
+            }
+        }
+    }
+}
+
+/// A group of systems that can run at the same time. Each system is a
+/// closure borrowing exactly the data it reads and writes, so the borrow
+/// checker rules out two systems touching the same data mutably. Systems
+/// that depend on each other go in separate stages, run one after another.
+///
+/// The core simulation doesn't go through stages: it stays on the main
+/// thread in a fixed order, so matches play out the same way every time.
+pub struct Stage<'a> {
+    systems: Vec<System<'a>>,
+}
+
+impl<'a> Stage<'a> {
+    pub fn new() -> Stage<'a> {
+        Stage {
+            systems: Vec::new(),
+        }
+    }
+
+    pub fn add(mut self, system: impl FnOnce() + Send + 'a) -> Stage<'a> {
+        self.systems.push(Box::new(system));
+        self
+    }
+
+    /// Run all systems, the first on this thread and the others on the
+    /// workers, and wait for them to finish.
+    pub fn run(self, workers: &Workers) {
+        let mut systems = self.systems.into_iter();
+        let first = match systems.next() {
+            Some(system) => system,
+            None => return,
+        };
+        match &workers.pool {
+            Some(pool) => pool.in_place_scope(|scope| {
+                for system in systems {
+                    scope.spawn(move |_| system());
+                }
+                first();
+            }),
+            None => {
+                first();
+                systems.for_each(|system| system());
+            }
+        }
+    }
+}
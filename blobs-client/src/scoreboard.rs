@@ -0,0 +1,90 @@
+use ggez::graphics::{self, Color};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+
+use crate::round::Stats;
+use crate::team::{Team, TeamColor};
+use crate::{LOCAL_ID, SCREEN_SIZE};
+
+const ROW_HEIGHT: f32 = 24.0;
+
+/// A player's line on the scoreboard.
+pub struct Row {
+    pub id: usize,
+    pub team: Option<Team>,
+    pub stats: Stats,
+}
+
+/// Sort rows by score: most kills first, then fewest deaths.
+pub fn sort(rows: &mut [Row]) {
+    rows.sort_by(|a, b| {
+        b.stats
+            .kills
+            .cmp(&a.stats.kills)
+            .then(a.stats.deaths.cmp(&b.stats.deaths))
+            .then(a.id.cmp(&b.id))
+    });
+}
+
+/// Draw the scoreboard over the match, one row per player. The local
+/// player's row is highlighted.
+pub fn draw(ctx: &mut Context, rows: &[Row]) -> GameResult<()> {
+    let height = 80.0 + ROW_HEIGHT * rows.len() as f32;
+    let area = graphics::Rect::new(250.0, 150.0, SCREEN_SIZE.0 - 500.0, height);
+    let background = graphics::Mesh::new_rectangle(
+        ctx,
+        graphics::DrawMode::fill(),
+        area,
+        Color::new(0.9, 0.9, 0.9, 0.9),
+    )?;
+    graphics::draw(ctx, &background, graphics::DrawParam::new())?;
+
+    let columns = [
+        ("Name", area.x + 20.0),
+        ("Kills", area.x + 200.0),
+        ("Deaths", area.x + 290.0),
+        ("Suicides", area.x + 380.0),
+    ];
+    for &(heading, x) in &columns {
+        let text = graphics::Text::new(heading);
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new()
+                .dest(Point2::new(x, area.y + 20.0))
+                .color(graphics::BLACK),
+        )?;
+    }
+
+    let mut y = area.y + 60.0;
+    for row in rows {
+        if row.id == LOCAL_ID {
+            let highlight = graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(area.x + 10.0, y - 4.0, area.w - 20.0, ROW_HEIGHT),
+                Color::from_rgb(255, 255, 255),
+            )?;
+            graphics::draw(ctx, &highlight, graphics::DrawParam::new())?;
+        }
+        let color = row.team.map(|team| team.color()).unwrap_or(graphics::BLACK);
+        let values = [
+            format!("Blob {}", row.id),
+            row.stats.kills.to_string(),
+            row.stats.deaths.to_string(),
+            row.stats.suicides.to_string(),
+        ];
+        for (value, &(_, x)) in values.iter().zip(&columns) {
+            let text = graphics::Text::new(value.as_str());
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::new()
+                    .dest(Point2::new(x, y))
+                    .color(color),
+            )?;
+        }
+        y += ROW_HEIGHT;
+    }
+    Ok(())
+}
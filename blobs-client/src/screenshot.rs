@@ -0,0 +1,31 @@
+use ggez::filesystem;
+use ggez::graphics::{self, Image, ImageFormat};
+use ggez::{Context, GameResult};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Folder in the writable user directory where screenshots are saved.
+const SCREENSHOT_DIR: &str = "/screenshots";
+
+/// Save what is on the screen, as drawn so far this frame, as a PNG in the
+/// user data directory. Returns the saved file's path there.
+pub fn save(ctx: &mut Context) -> GameResult<String> {
+    let screen = graphics::screenshot(ctx)?;
+    // The screen comes out upside down.
+    let row_len = usize::from(screen.width()) * 4;
+    let pixels: Vec<u8> = screen
+        .to_rgba8(ctx)?
+        .chunks(row_len)
+        .rev()
+        .flatten()
+        .cloned()
+        .collect();
+    let image = Image::from_rgba8(ctx, screen.width(), screen.height(), &pixels)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let path = format!("{}/screenshot-{}.png", SCREENSHOT_DIR, timestamp);
+    filesystem::create_dir(ctx, SCREENSHOT_DIR)?;
+    image.encode(ctx, ImageFormat::Png, &path)?;
+    Ok(path)
+}
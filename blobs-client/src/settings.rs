@@ -0,0 +1,598 @@
+use directories::ProjectDirs;
+use ggez::conf::{FullscreenType, WindowMode, WindowSetup};
+use ggez::graphics;
+use ggez::input::keyboard::KeyCode;
+use ggez::{Context, GameResult};
+use ron::value::RawValue;
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use winit::dpi::LogicalPosition;
+
+use crate::crosshair::Crosshair;
+use crate::speedometer::SpeedUnit;
+use crate::{AUTHOR, GAME_ID, SCREEN_SIZE};
+
+const FILE_NAME: &str = "settings.ron";
+// Frame rate cap in low latency mode, where vsync is off.
+const LOW_LATENCY_FPS: u32 = 240;
+// Menus don't move much, so they don't need many frames.
+const MENU_FPS: u32 = 60;
+const BATTERY_SAVER_FPS: u32 = 30;
+const BATTERY_SAVER_BACKGROUND_FPS: u32 = 5;
+/// Frame rate caps to choose from, 0 meaning no cap.
+pub const FPS_CAPS: [u32; 6] = [0, 30, 60, 120, 144, 240];
+/// Frame rate caps to choose from for when the window isn't focused.
+pub const BACKGROUND_FPS_CAPS: [u32; 4] = [5, 10, 30, 60];
+/// UI scales to choose from, on top of the automatic scale.
+pub const UI_SCALES: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+/// Windows can't be resized smaller than this.
+pub const MIN_WINDOW_SIZE: f32 = 300.0;
+/// Windows can't be made larger than this from the command line.
+pub const MAX_WINDOW_SIZE: f32 = 8192.0;
+/// Window sizes to start with. The level is square, so are the windows,
+/// until they are resized.
+pub const WINDOW_SIZES: [f32; 4] = [600.0, 800.0, 1000.0, 1200.0];
+
+/// Version of the settings file format. Bump it when a change to Settings
+/// needs old files converted, and add a step to MIGRATIONS.
+const VERSION: u32 = 1;
+/// Steps that each upgrade a settings file by one version, indexed by the
+/// version they upgrade from. They work on the fields of the file before
+/// they are read into Settings, so they can rename, drop or rewrite fields
+/// whose type changed. New fields need no step, they get their defaults.
+const MIGRATIONS: [Migration; VERSION as usize] = [from_unversioned];
+
+type Migration = fn(&mut Fields) -> Result<(), String>;
+
+/// The fields of a settings file as RON source, by name.
+type Fields = BTreeMap<String, String>;
+
+/// Keys for the in-game actions that can be rebound. Escape always pauses.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub share_highlight: KeyCode,
+    pub load_replay: KeyCode,
+    pub console: KeyCode,
+    pub chat: KeyCode,
+    /// Held to show the scoreboard.
+    pub scoreboard: KeyCode,
+    pub minimap: KeyCode,
+    pub photo_mode: KeyCode,
+    /// Held to hang on to walls.
+    pub perch: KeyCode,
+    /// Same as /kill, for getting out of a corner.
+    pub kill: KeyCode,
+    /// Never show the tutorial hint that is up again.
+    pub dismiss_hint: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> KeyBindings {
+        KeyBindings {
+            share_highlight: KeyCode::F5,
+            load_replay: KeyCode::F6,
+            console: KeyCode::Slash,
+            chat: KeyCode::Return,
+            scoreboard: KeyCode::Tab,
+            minimap: KeyCode::M,
+            photo_mode: KeyCode::F8,
+            perch: KeyCode::LShift,
+            kill: KeyCode::K,
+            dismiss_hint: KeyCode::H,
+        }
+    }
+}
+
+/// Options chosen by the player on the settings screen. Missing fields in
+/// the settings file get their default, so older files keep working.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Format version of the file the settings were read from.
+    pub version: u32,
+    pub window_size: f32,
+    pub fullscreen: bool,
+    /// Index of the monitor to go fullscreen on, or None for the one the
+    /// window is on.
+    pub monitor: Option<usize>,
+    /// Where the window was when the game was last closed.
+    pub window_position: Option<(f32, f32)>,
+    /// Only takes effect when the game is started.
+    pub vsync: bool,
+    /// Turns vsync off in favour of a frame rate cap and aims with the
+    /// mouse position from right before each frame is drawn.
+    pub low_latency: bool,
+    /// Highest frame rate during play, 0 for no cap.
+    pub fps_cap: u32,
+    /// Highest frame rate while the window isn't focused.
+    pub background_fps_cap: u32,
+    /// Low frame rates everywhere, to save battery.
+    pub battery_saver: bool,
+    pub master_volume: f32,
+    pub effects_volume: f32,
+    pub music_volume: f32,
+    /// Draw a nameplate above the local blob too.
+    pub show_own_nameplate: bool,
+    /// Shake the view on heavy hits and deaths.
+    pub screen_shake: bool,
+    /// Size of the HUD and overlays relative to the automatic size.
+    pub ui_scale: f32,
+    /// Show how fast the local blob goes.
+    pub speedometer: bool,
+    pub speed_unit: SpeedUnit,
+    /// Point an arrow the way the blob moves, next to the speedometer.
+    pub speed_arrow: bool,
+    /// Show tips for new players during matches.
+    pub hints: bool,
+    /// Hints not to show again, one bit each.
+    pub dismissed_hints: u32,
+    /// Look for a newer release when the game starts.
+    pub update_check: bool,
+    pub crosshair: Crosshair,
+    pub keys: KeyBindings,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            version: VERSION,
+            window_size: SCREEN_SIZE.0,
+            fullscreen: false,
+            monitor: None,
+            window_position: None,
+            vsync: true,
+            low_latency: false,
+            fps_cap: 0,
+            background_fps_cap: 10,
+            battery_saver: false,
+            master_volume: 1.0,
+            effects_volume: 1.0,
+            music_volume: 0.7,
+            show_own_nameplate: false,
+            screen_shake: true,
+            ui_scale: 1.0,
+            speedometer: false,
+            speed_unit: SpeedUnit::UnitsPerSecond,
+            speed_arrow: true,
+            hints: true,
+            dismissed_hints: 0,
+            update_check: true,
+            crosshair: Crosshair::default(),
+            keys: KeyBindings::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn window_setup(&self) -> WindowSetup {
+        WindowSetup::default()
+            .title("Blobs!")
+            .vsync(self.vsync && !self.low_latency)
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        let fullscreen_type = if self.fullscreen {
+            FullscreenType::Desktop
+        } else {
+            FullscreenType::Windowed
+        };
+        // The settings only have square windows, but the command line can
+        // ask for any shape while its size is in use.
+        let (width, height) = match OVERRIDES.with(|overrides| overrides.get().window_size) {
+            Some((width, height)) if width == self.window_size => (width, height),
+            _ => (self.window_size, self.window_size),
+        };
+        WindowMode::default()
+            .dimensions(width, height)
+            .min_dimensions(MIN_WINDOW_SIZE, MIN_WINDOW_SIZE)
+            .resizable(true)
+            .fullscreen_type(fullscreen_type)
+    }
+
+    /// Highest frame rate to draw at, if frames need to be limited. Menus
+    /// and windows in the background are drawn at lower rates.
+    pub fn max_fps(&self, focused: bool, menu: bool) -> Option<u32> {
+        let mut caps = Vec::new();
+        if self.battery_saver {
+            caps.push(BATTERY_SAVER_FPS);
+        }
+        if self.fps_cap > 0 {
+            caps.push(self.fps_cap);
+        }
+        if self.low_latency {
+            caps.push(LOW_LATENCY_FPS);
+        }
+        if menu {
+            caps.push(MENU_FPS);
+        }
+        if !focused {
+            caps.push(if self.battery_saver {
+                BATTERY_SAVER_BACKGROUND_FPS
+            } else {
+                self.background_fps_cap
+            });
+        }
+        caps.into_iter().min()
+    }
+
+    /// Volume multiplier for sound effects.
+    pub fn sound_volume(&self) -> f32 {
+        self.master_volume * self.effects_volume
+    }
+
+    /// Volume multiplier for the background music.
+    pub fn music_volume(&self) -> f32 {
+        self.master_volume * self.music_volume
+    }
+}
+
+/// Window options given on the command line, which apply to this run only.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Overrides {
+    pub fullscreen: Option<bool>,
+    /// Width and height, since the window need not be square for this run.
+    pub window_size: Option<(f32, f32)>,
+}
+
+thread_local! {
+    static CURRENT: Cell<Settings> = Cell::new(Settings::default());
+    // The settings as in the file, without the overrides.
+    static SAVED: Cell<Settings> = Cell::new(Settings::default());
+    static OVERRIDES: Cell<Overrides> = Cell::new(Overrides::default());
+}
+
+/// Apply options for this run on top of the current settings, without them
+/// ending up in the settings file. Returns the settings with them applied.
+pub fn set_overrides(overrides: Overrides) -> Settings {
+    OVERRIDES.with(|current| current.set(overrides));
+    let mut settings = current();
+    if let Some(fullscreen) = overrides.fullscreen {
+        settings.fullscreen = fullscreen;
+    }
+    if let Some((width, _)) = overrides.window_size {
+        settings.window_size = width;
+    }
+    CURRENT.with(|current| current.set(settings));
+    settings
+}
+
+/// The settings to write to the file when the given ones are saved. Options
+/// overridden for this run keep their saved value, unless the player has
+/// since chosen something else, which ends the override.
+fn without_overrides(settings: Settings) -> Settings {
+    let saved = SAVED.with(|saved| saved.get());
+    let mut overrides = OVERRIDES.with(|overrides| overrides.get());
+    let mut persisted = settings;
+    match overrides.fullscreen {
+        Some(fullscreen) if fullscreen == settings.fullscreen => {
+            persisted.fullscreen = saved.fullscreen;
+        }
+        _ => overrides.fullscreen = None,
+    }
+    match overrides.window_size {
+        Some((width, _)) if width == settings.window_size => {
+            persisted.window_size = saved.window_size;
+        }
+        _ => overrides.window_size = None,
+    }
+    OVERRIDES.with(|current| current.set(overrides));
+    persisted
+}
+
+/// The settings file lives in the same user data directory that ggez uses,
+/// found without a context since the window settings are needed to build it.
+fn path() -> Option<PathBuf> {
+    ProjectDirs::from("", AUTHOR, GAME_ID).map(|dirs| dirs.data_local_dir().join(FILE_NAME))
+}
+
+/// Reads the fields of a RON struct without knowing their types.
+struct RawFields(Fields);
+
+impl<'de> Deserialize<'de> for RawFields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<RawFields, D::Error> {
+        struct FieldsVisitor;
+
+        impl<'de> Visitor<'de> for FieldsVisitor {
+            type Value = RawFields;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a struct")
+            }
+
+            // A struct without fields.
+            fn visit_unit<E: de::Error>(self) -> Result<RawFields, E> {
+                Ok(RawFields(Fields::new()))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawFields, A::Error> {
+                let mut fields = Fields::new();
+                while let Some((name, value)) = map.next_entry::<String, Box<RawValue>>()? {
+                    fields.insert(name, value.get_ron().trim().to_string());
+                }
+                Ok(RawFields(fields))
+            }
+        }
+
+        deserializer.deserialize_any(FieldsVisitor)
+    }
+}
+
+/// Settings files written before the format was versioned already have the
+/// fields of version 1.
+fn from_unversioned(_fields: &mut Fields) -> Result<(), String> {
+    Ok(())
+}
+
+/// Put fields back together as a struct. Each field gets a line of its
+/// own, as a value may end in a comment.
+fn to_source<'a>(fields: impl IntoIterator<Item = (&'a String, &'a String)>) -> String {
+    let fields: String = fields
+        .into_iter()
+        .map(|(name, value)| format!("{}: {},\n", name, value))
+        .collect();
+    format!("(\n{})", fields)
+}
+
+/// What was read from a settings file.
+struct Parsed {
+    settings: Settings,
+    /// Version of the file.
+    version: u32,
+    /// Fields that couldn't be read and got their defaults instead.
+    dropped: Vec<String>,
+}
+
+/// Read settings of any version, upgrading older ones step by step. Fields
+/// that still can't be read are left at their defaults, so one bad field
+/// doesn't cost all the others.
+fn parse(source: &str) -> Result<Parsed, String> {
+    let mut fields = ron::de::from_str::<RawFields>(source)
+        .map_err(|e| e.to_string())?
+        .0;
+    // Files from before versioning have none.
+    let version = match fields.get("version") {
+        Some(version) => ron::de::from_str(version).map_err(|e| e.to_string())?,
+        None => 0,
+    };
+    if version > VERSION {
+        log::warn!(
+            "Settings file is version {}, newer than this game's {}",
+            version,
+            VERSION
+        );
+    }
+    for (from, step) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        step(&mut fields).map_err(|e| format!("Could not upgrade from version {}: {}", from, e))?;
+    }
+    let mut dropped = Vec::new();
+    if ron::de::from_str::<Settings>(&to_source(&fields)).is_err() {
+        fields.retain(|name, value| {
+            let readable = ron::de::from_str::<Settings>(&to_source([(name, &*value)])).is_ok();
+            if !readable {
+                dropped.push(name.clone());
+            }
+            readable
+        });
+    }
+    let mut settings: Settings =
+        ron::de::from_str(&to_source(&fields)).map_err(|e| e.to_string())?;
+    settings.version = VERSION;
+    Ok(Parsed {
+        settings,
+        version,
+        dropped,
+    })
+}
+
+/// Keep a copy of a settings file before it's overwritten, so nothing is
+/// lost if it couldn't be read or upgraded properly. The label tells the
+/// copies apart.
+fn back_up(path: &Path, label: &str) {
+    let backup = path.with_extension(format!("{}.ron.bak", label));
+    match fs::copy(path, &backup) {
+        Ok(_) => log::info!("Backed up old settings to {}", backup.display()),
+        Err(e) => log::warn!("Could not back up old settings: {}", e),
+    }
+}
+
+/// Load the saved settings and make them current. Missing files give the
+/// defaults. Files of another version, or with fields that couldn't be
+/// read, are backed up and saved in the current format. Files that can't be
+/// read at all are backed up and give the defaults.
+pub fn load() -> Settings {
+    let path = path();
+    let settings = match path.as_ref().map(|path| (path, fs::read_to_string(path))) {
+        Some((path, Ok(source))) => match parse(&source) {
+            Ok(parsed) => {
+                if !parsed.dropped.is_empty() {
+                    log::warn!(
+                        "Reset unreadable settings to their defaults: {}",
+                        parsed.dropped.join(", ")
+                    );
+                    back_up(path, "unreadable");
+                    save(parsed.settings);
+                } else if parsed.version != VERSION {
+                    back_up(path, &format!("v{}", parsed.version));
+                    save(parsed.settings);
+                }
+                parsed.settings
+            }
+            Err(e) => {
+                log::warn!("Ignoring invalid settings file: {}", e);
+                back_up(path, "invalid");
+                Settings::default()
+            }
+        },
+        _ => Settings::default(),
+    };
+    CURRENT.with(|current| current.set(settings));
+    SAVED.with(|saved| saved.set(settings));
+    settings
+}
+
+pub fn current() -> Settings {
+    CURRENT.with(|current| current.get())
+}
+
+/// Make the settings current and save them to the settings file.
+pub fn save(settings: Settings) {
+    CURRENT.with(|current| current.set(settings));
+    let settings = without_overrides(settings);
+    SAVED.with(|saved| saved.set(settings));
+    let path = match path() {
+        Some(path) => path,
+        None => return,
+    };
+    let result = ron::ser::to_string_pretty(&settings, ron::ser::PrettyConfig::default())
+        .map_err(|e| e.to_string())
+        .and_then(|source| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            fs::write(&path, source).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        log::warn!("Could not save settings to {}: {}", path.display(), e);
+    }
+}
+
+/// Resize the window to the settings, keeping the whole level in view.
+/// Fullscreen covers the chosen monitor, and a window goes back to where it
+/// was last time.
+pub fn apply_window(ctx: &mut Context, settings: &Settings) -> GameResult<()> {
+    graphics::set_mode(ctx, settings.window_mode())?;
+    let window = graphics::window(ctx);
+    let hidpi_factor = window.get_hidpi_factor();
+    if settings.fullscreen {
+        let monitor = settings.monitor.and_then(|i| {
+            let monitor = window.get_available_monitors().nth(i);
+            if monitor.is_none() {
+                log::warn!("No monitor {}, staying on the current one", i + 1);
+            }
+            monitor
+        });
+        if let Some(monitor) = monitor {
+            // Like ggez's desktop fullscreen, but on the chosen monitor
+            // rather than the current one.
+            window.set_inner_size(monitor.get_dimensions().to_logical(hidpi_factor));
+            window.set_position(monitor.get_position().to_logical(hidpi_factor));
+        }
+    } else if let Some((x, y)) = settings.window_position {
+        let position = LogicalPosition::new(f64::from(x), f64::from(y));
+        // A monitor that was unplugged could leave the window where it can't
+        // be seen, so then the system gets to place it.
+        let corner = position.to_physical(hidpi_factor);
+        let on_screen = window.get_available_monitors().any(|monitor| {
+            let (left, top): (f64, f64) = monitor.get_position().into();
+            let (width, height): (f64, f64) = monitor.get_dimensions().into();
+            (left..left + width).contains(&corner.x) && (top..top + height).contains(&corner.y)
+        });
+        if on_screen {
+            window.set_position(position);
+        } else {
+            log::warn!(
+                "Window position ({}, {}) is off every monitor, not using it",
+                x,
+                y
+            );
+        }
+    }
+    let (width, height) = graphics::drawable_size(ctx);
+    crate::scene::fit_screen(ctx, width, height)
+}
+
+/// Switch between a window and borderless fullscreen, and keep the choice.
+pub fn toggle_fullscreen(ctx: &mut Context) -> GameResult<()> {
+    remember_window_position(ctx);
+    let mut settings = current();
+    settings.fullscreen = !settings.fullscreen;
+    save(settings);
+    apply_window(ctx, &settings)
+}
+
+/// Number of monitors to choose from for fullscreen.
+pub fn monitor_count(ctx: &Context) -> usize {
+    graphics::window(ctx).get_available_monitors().count()
+}
+
+/// Save where the window is, to put it back there next time. Fullscreen
+/// windows are where their monitor is, so only windows are remembered.
+pub fn remember_window_position(ctx: &Context) {
+    let mut settings = current();
+    if settings.fullscreen {
+        return;
+    }
+    if let Some(position) = graphics::window(ctx).get_position() {
+        settings.window_position = Some((position.x as f32, position.y as f32));
+        save(settings);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreadable_fields_get_their_defaults() {
+        let source = "Settings(
+            version: 1,
+            window_size: 600.0, // small screen
+            speed_unit: KilometresPerFortnight,
+            keys: (console: F2),
+        )";
+        let parsed = parse(source).unwrap();
+        assert_eq!(parsed.version, 1);
+        assert_eq!(parsed.dropped, vec!["speed_unit".to_string()]);
+        assert_eq!(parsed.settings.window_size, 600.0);
+        assert_eq!(parsed.settings.keys.console, KeyCode::F2);
+    }
+
+    #[test]
+    fn reads_what_it_saves() {
+        let settings = Settings {
+            fullscreen: true,
+            window_position: Some((10.0, 20.0)),
+            ..Settings::default()
+        };
+        let source =
+            ron::ser::to_string_pretty(&settings, ron::ser::PrettyConfig::default()).unwrap();
+        let parsed = parse(&source).unwrap();
+        assert!(parsed.dropped.is_empty());
+        assert!(parsed.settings.fullscreen);
+        assert_eq!(parsed.settings.window_position, Some((10.0, 20.0)));
+        assert!(parse("()").unwrap().dropped.is_empty());
+    }
+
+    #[test]
+    fn command_line_options_are_not_saved() {
+        let saved = Settings::default();
+        SAVED.with(|current| current.set(saved));
+        CURRENT.with(|current| current.set(saved));
+        let settings = set_overrides(Overrides {
+            fullscreen: Some(true),
+            window_size: Some((600.0, 400.0)),
+        });
+        assert!(settings.fullscreen);
+        assert_eq!(
+            (settings.window_mode().width, settings.window_mode().height),
+            (600.0, 400.0)
+        );
+        let persisted = without_overrides(Settings {
+            window_size: 800.0,
+            ..settings
+        });
+        assert_eq!(persisted.fullscreen, saved.fullscreen);
+        // A size chosen by the player is theirs to keep.
+        assert_eq!(persisted.window_size, 800.0);
+        assert_eq!(
+            OVERRIDES.with(|overrides| overrides.get().window_size),
+            None
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use ggez::graphics::{self, DrawParam};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+
+use crate::settings;
+use crate::{SCREEN_SIZE, TICK_TIME};
+
+// Furthest the view moves, in world units, and turns, in radians, at full
+// trauma.
+const MAX_OFFSET: f32 = 14.0;
+const MAX_ROLL: f32 = 0.03;
+// Trauma lost per second.
+const DECAY: f32 = 1.5;
+// How fast the view wobbles, in radians per second.
+const SPEED: f32 = 45.0;
+// Wall hits slower than this don't shake, and hits this fast give full
+// trauma. Trauma grows with the hit's energy, so with the speed squared.
+const MIN_IMPACT_SPEED: f32 = 80.0;
+const FULL_IMPACT_SPEED: f32 = 250.0;
+/// Trauma from a blob dying.
+pub const DEATH: f32 = 0.5;
+
+/// Shaking of the view after heavy hits. Hits add trauma, which wears off
+/// over time, and the view shakes with the square of it, so small hits
+/// barely show. Players can turn it off in the settings.
+pub struct Shake {
+    trauma: f32,
+    time: f32,
+}
+
+impl Shake {
+    pub fn new() -> Shake {
+        Shake {
+            trauma: 0.0,
+            time: 0.0,
+        }
+    }
+
+    pub fn add(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.0);
+    }
+
+    /// Shake for a blob hitting a wall at the given speed.
+    pub fn impact(&mut self, speed: f32) {
+        let energy = speed * speed - MIN_IMPACT_SPEED * MIN_IMPACT_SPEED;
+        let full = FULL_IMPACT_SPEED * FULL_IMPACT_SPEED - MIN_IMPACT_SPEED * MIN_IMPACT_SPEED;
+        if energy > 0.0 {
+            self.add(energy / full);
+        }
+    }
+
+    /// Call once per tick.
+    pub fn update(&mut self) {
+        self.trauma = (self.trauma - DECAY * TICK_TIME).max(0.0);
+        self.time += TICK_TIME;
+    }
+
+    /// Draw with the view shaken, unless shaking is off.
+    pub fn draw(
+        &self,
+        ctx: &mut Context,
+        draw: impl FnOnce(&mut Context) -> GameResult<()>,
+    ) -> GameResult<()> {
+        let param = match self.param() {
+            Some(param) => param,
+            None => return draw(ctx),
+        };
+        graphics::push_transform(ctx, Some(param.to_matrix()));
+        graphics::apply_transformations(ctx)?;
+        let drawn = draw(ctx);
+        graphics::pop_transform(ctx);
+        graphics::apply_transformations(ctx)?;
+        drawn
+    }
+
+    fn param(&self) -> Option<DrawParam> {
+        if self.trauma <= 0.0 || !settings::current().screen_shake {
+            return None;
+        }
+        let amount = self.trauma * self.trauma;
+        // Sums of waves that don't line up, so the shaking doesn't repeat.
+        let wave = |a: f32, b: f32| {
+            0.6 * (self.time * SPEED * a).sin() + 0.4 * (self.time * SPEED * b + 1.0).sin()
+        };
+        let shift = MAX_OFFSET * amount * Vector2::new(wave(1.0, 2.3), wave(1.3, 1.9));
+        // Turns around the middle of the screen.
+        let middle = Point2::new(SCREEN_SIZE.0 / 2.0, SCREEN_SIZE.1 / 2.0);
+        Some(
+            DrawParam::new()
+                .dest(Point2::from(shift))
+                .offset(middle)
+                .rotation(MAX_ROLL * amount * wave(0.7, 1.7)),
+        )
+    }
+}
@@ -0,0 +1,67 @@
+use ggez::graphics::Color;
+
+use crate::team::{Team, TeamColor};
+
+// Colors of blobs without a team, handed out by blob id.
+const PALETTE: [(u8, u8, u8); 8] = [
+    (230, 140, 40),
+    (70, 170, 80),
+    (150, 80, 190),
+    (40, 160, 170),
+    (220, 190, 50),
+    (220, 100, 160),
+    (200, 60, 60),
+    (60, 90, 200),
+];
+
+/// What is drawn on top of the blob's body color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    Plain,
+    /// A darker core that wobbles with the outline.
+    Core,
+    /// A few dark spots.
+    Spots,
+}
+
+const PATTERNS: [Pattern; 3] = [Pattern::Plain, Pattern::Core, Pattern::Spots];
+
+/// How a blob looks. Blobs on a team wear the team color and tell each
+/// other apart by pattern, other blobs get a color of their own.
+#[derive(Clone, Copy, Debug)]
+pub struct Skin {
+    pub body: Color,
+    /// Pattern color, a darker shade of the body.
+    pub accent: Color,
+    /// Rope and aim color, a lighter shade of the body.
+    pub tint: Color,
+    pub pattern: Pattern,
+}
+
+impl Skin {
+    pub fn new(id: usize, team: Option<Team>) -> Skin {
+        let (body, pattern) = match team {
+            Some(team) => (team.color(), PATTERNS[id % PATTERNS.len()]),
+            None => (
+                Color::from(PALETTE[id % PALETTE.len()]),
+                PATTERNS[id / PALETTE.len() % PATTERNS.len()],
+            ),
+        };
+        Skin {
+            body,
+            accent: mix(body, Color::new(0.0, 0.0, 0.0, 1.0), 0.35),
+            tint: mix(body, Color::new(1.0, 1.0, 1.0, 1.0), 0.4),
+            pattern,
+        }
+    }
+}
+
+fn mix(from: Color, to: Color, amount: f32) -> Color {
+    let lerp = |a: f32, b: f32| a + (b - a) * amount;
+    Color::new(
+        lerp(from.r, to.r),
+        lerp(from.g, to.g),
+        lerp(from.b, to.b),
+        from.a,
+    )
+}
@@ -0,0 +1,174 @@
+use ggez::audio::{self, SoundData, SoundSource};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+use std::collections::BTreeMap;
+
+use crate::health::GameEvent;
+use crate::settings;
+use crate::{Blob, LOCAL_ID};
+
+const SAMPLE_RATE: u32 = 22050;
+// Sounds closer than this are played at full volume, further ones fade
+// with the distance, until they can't be heard at all.
+const FULL_VOLUME_DISTANCE: f32 = 200.0;
+const HEARING_DISTANCE: f32 = 1500.0;
+// Horizontal offset at which a sound is heard only in one ear.
+const FULL_PAN_DISTANCE: f32 = 500.0;
+
+/// Sounds played in response to game events.
+pub struct SoundEffects {
+    hit: SoundData,
+    hit_confirm: SoundData,
+    death: SoundData,
+}
+
+impl SoundEffects {
+    pub fn new() -> SoundEffects {
+        SoundEffects {
+            hit: tone(220.0, 0.08, 0.6),
+            hit_confirm: tone(1320.0, 0.05, 0.4),
+            death: tone(110.0, 0.4, 0.8),
+        }
+    }
+
+    pub fn play(
+        &self,
+        ctx: &mut Context,
+        events: &[GameEvent],
+        blobs: &BTreeMap<usize, Blob>,
+        listener: Point2<f32>,
+    ) -> GameResult<()> {
+        for event in events {
+            match *event {
+                GameEvent::Damaged {
+                    target,
+                    amount,
+                    source,
+                } => {
+                    if let Some(blob) = blobs.get(&target) {
+                        let volume = (amount / 20.0).min(1.0);
+                        play_at(ctx, &self.hit, volume, blob.center, listener)?;
+                    }
+                    if source.attacker() == Some(LOCAL_ID) {
+                        play_at(ctx, &self.hit_confirm, 1.0, listener, listener)?;
+                    }
+                }
+                GameEvent::Died { pos, .. } => play_at(ctx, &self.death, 1.0, pos, listener)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Play a sound positioned in the world, as heard from the listener position.
+/// Sounds to the left of the listener are louder in the left ear and far away
+/// sounds are quieter. The volume is scaled by the player's volume settings.
+pub fn play_at(
+    ctx: &mut Context,
+    data: &SoundData,
+    volume: f32,
+    pos: Point2<f32>,
+    listener: Point2<f32>,
+) -> GameResult<()> {
+    play_pitched_at(ctx, data, volume, 1.0, pos, listener)
+}
+
+/// Like play_at, but sped up or slowed down by the pitch ratio.
+pub fn play_pitched_at(
+    ctx: &mut Context,
+    data: &SoundData,
+    volume: f32,
+    pitch: f32,
+    pos: Point2<f32>,
+    listener: Point2<f32>,
+) -> GameResult<()> {
+    let attenuation = attenuation((pos - listener).norm());
+    if attenuation <= 0.0 {
+        return Ok(());
+    }
+    let pan = ((pos.x - listener.x) / FULL_PAN_DISTANCE).clamp(-1.0, 1.0);
+    let mut source = audio::SpatialSource::from_data(ctx, data.clone())?;
+    // rodio sets each ear's volume from the emitter's distances to the ears,
+    // with the ear further away getting the louder share. Placing the
+    // emitter between the ears, mirrored, gives the pan without any distance
+    // falloff, which is done by attenuation instead.
+    source.set_ears([-0.5, 0.0, 0.0], [0.5, 0.0, 0.0]);
+    source.set_position([-0.5 * pan, 0.0, 0.0]);
+    source.set_volume(volume * attenuation * settings::current().sound_volume());
+    source.set_pitch(pitch);
+    source.play_detached()
+}
+
+/// Volume multiplier for a sound the given distance away.
+fn attenuation(distance: f32) -> f32 {
+    if distance <= FULL_VOLUME_DISTANCE {
+        1.0
+    } else if distance >= HEARING_DISTANCE {
+        0.0
+    } else {
+        // Falls off inversely with distance, reaching zero at the hearing
+        // distance instead of trailing off forever.
+        let inverse = FULL_VOLUME_DISTANCE / distance;
+        let edge = FULL_VOLUME_DISTANCE / HEARING_DISTANCE;
+        (inverse - edge) / (1.0 - edge)
+    }
+}
+
+/// Synthesize a sine tone as WAV data, with a short fade at both ends so it
+/// doesn't click.
+pub fn tone(frequency: f32, duration: f32, volume: f32) -> SoundData {
+    let sample_count = (duration * SAMPLE_RATE as f32) as u32;
+    let fade_count = (sample_count / 10).max(1);
+    let mut samples = Vec::with_capacity(sample_count as usize);
+    for i in 0..sample_count {
+        let t = i as f32 / SAMPLE_RATE as f32;
+        let fade = (i.min(sample_count - i) as f32 / fade_count as f32).min(1.0);
+        let value = (t * frequency * 2.0 * std::f32::consts::PI).sin() * volume * fade;
+        samples.push((value * i16::MAX as f32) as i16);
+    }
+    wav(&samples).into()
+}
+
+/// Synthesize a whoosh as WAV data: noise that swells and dies away while
+/// going from bright to dull, like something rushing past.
+pub fn whoosh(duration: f32, volume: f32) -> SoundData {
+    let sample_count = (duration * SAMPLE_RATE as f32) as u32;
+    let mut samples = Vec::with_capacity(sample_count as usize);
+    let mut filtered = 0.0;
+    for i in 0..sample_count {
+        let t = i as f32 / sample_count as f32;
+        let noise = 2.0 * rand::random::<f32>() - 1.0;
+        // A low pass filter closing over time, which also makes the noise
+        // quieter, so it's boosted back up.
+        let brightness = 0.5 * (1.0 - t) + 0.05;
+        filtered += brightness * (noise - filtered);
+        let boost = (0.5 / brightness).sqrt();
+        // Peaks a little before the middle.
+        let envelope = (t / 0.4).min((1.0 - t) / 0.6);
+        let value = (filtered * boost * envelope * volume).clamp(-1.0, 1.0);
+        samples.push((value * i16::MAX as f32) as i16);
+    }
+    wav(&samples).into()
+}
+
+/// Wrap 16 bit mono samples in a WAV header.
+fn wav(samples: &[i16]) -> Vec<u8> {
+    let data_len = samples.len() as u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // Mono
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+    bytes
+}
@@ -0,0 +1,192 @@
+use ggez::graphics::{self, Color};
+use ggez::input::keyboard::KeyCode;
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+
+use crate::balance;
+use crate::pickup::{PickupKind, PickupSpawn};
+use crate::team::Team;
+use crate::SCREEN_SIZE;
+
+const KINDS: [Kind; 3] = [Kind::Bot, Kind::Pickup, Kind::Projectile];
+const TEAMS: [Option<Team>; 3] = [None, Some(Team::Red), Some(Team::Blue)];
+const PICKUPS: [PickupKind; 4] = [
+    PickupKind::SpeedBoost,
+    PickupKind::DoubleHook,
+    PickupKind::Shield,
+    PickupKind::Health,
+];
+// Seconds until a spawned pickup comes back, None for never.
+const RESPAWN_TIMES: [Option<f32>; 4] = [None, Some(5.0), Some(10.0), Some(30.0)];
+// Degrees from pointing right, clockwise since y points down.
+const ANGLE_STEP: u32 = 15;
+// Projectile speeds as multiples of the weapon's.
+const SPEEDS: [f32; 4] = [0.5, 1.0, 2.0, 4.0];
+
+/// Something to put in the match where the cursor is.
+pub enum Spawn {
+    Bot {
+        pos: Point2<f32>,
+        team: Option<Team>,
+    },
+    Pickup(PickupSpawn),
+    /// Fired by the local player.
+    Projectile {
+        pos: Point2<f32>,
+        vel: Vector2<f32>,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Kind {
+    Bot,
+    Pickup,
+    Projectile,
+}
+
+#[derive(Clone, Copy)]
+enum Row {
+    Kind,
+    Team,
+    Pickup,
+    RespawnTime,
+    Angle,
+    Speed,
+}
+
+/// Menu for developers to spawn bots, pickups and projectiles at the cursor,
+/// to reproduce bug reports and try things out without editing levels. Up
+/// and down pick a row, left and right change it and a left click spawns.
+pub struct SpawnMenu {
+    row: usize,
+    kind: usize,
+    team: usize,
+    pickup: usize,
+    respawn_time: usize,
+    angle: u32,
+    speed: usize,
+}
+
+impl SpawnMenu {
+    pub fn new() -> SpawnMenu {
+        SpawnMenu {
+            row: 0,
+            kind: 0,
+            team: 0,
+            pickup: 0,
+            respawn_time: 0,
+            angle: 0,
+            speed: 1,
+        }
+    }
+
+    fn rows(&self) -> &'static [Row] {
+        match KINDS[self.kind] {
+            Kind::Bot => &[Row::Kind, Row::Team],
+            Kind::Pickup => &[Row::Kind, Row::Pickup, Row::RespawnTime],
+            Kind::Projectile => &[Row::Kind, Row::Angle, Row::Speed],
+        }
+    }
+
+    /// Pick or change a row, returning whether the key was for the menu.
+    pub fn key_down(&mut self, keycode: KeyCode) -> bool {
+        let rows = self.rows();
+        let step = match keycode {
+            KeyCode::Up => {
+                self.row = (self.row + rows.len() - 1) % rows.len();
+                return true;
+            }
+            KeyCode::Down => {
+                self.row = (self.row + 1) % rows.len();
+                return true;
+            }
+            KeyCode::Left => -1,
+            KeyCode::Right => 1,
+            _ => return false,
+        };
+        let cycle = |value: &mut usize, len: usize| {
+            *value = (*value as i32 + step).rem_euclid(len as i32) as usize;
+        };
+        match rows[self.row] {
+            Row::Kind => cycle(&mut self.kind, KINDS.len()),
+            Row::Team => cycle(&mut self.team, TEAMS.len()),
+            Row::Pickup => cycle(&mut self.pickup, PICKUPS.len()),
+            Row::RespawnTime => cycle(&mut self.respawn_time, RESPAWN_TIMES.len()),
+            Row::Angle => {
+                self.angle = (self.angle as i32 + step * ANGLE_STEP as i32).rem_euclid(360) as u32
+            }
+            Row::Speed => cycle(&mut self.speed, SPEEDS.len()),
+        }
+        true
+    }
+
+    /// What to spawn at the given point with the current choices.
+    pub fn spawn(&self, pos: Point2<f32>) -> Spawn {
+        match KINDS[self.kind] {
+            Kind::Bot => Spawn::Bot {
+                pos,
+                team: TEAMS[self.team],
+            },
+            Kind::Pickup => Spawn::Pickup(PickupSpawn {
+                kind: PICKUPS[self.pickup],
+                pos: (pos.x, pos.y),
+                respawn_time: RESPAWN_TIMES[self.respawn_time],
+            }),
+            Kind::Projectile => {
+                let angle = (self.angle as f32).to_radians();
+                let speed = SPEEDS[self.speed] * balance::current().weapon.speed;
+                let vel = speed * Vector2::new(angle.cos(), angle.sin());
+                Spawn::Projectile { pos, vel }
+            }
+        }
+    }
+
+    fn describe(&self, row: Row) -> String {
+        match row {
+            Row::Kind => format!("Spawn: {:?}", KINDS[self.kind]),
+            Row::Team => match TEAMS[self.team] {
+                Some(team) => format!("Team: {:?}", team),
+                None => "Team: none".to_string(),
+            },
+            Row::Pickup => format!("Pickup: {:?}", PICKUPS[self.pickup]),
+            Row::RespawnTime => match RESPAWN_TIMES[self.respawn_time] {
+                Some(time) => format!("Respawns after: {:.0} s", time),
+                None => "Respawns after: never".to_string(),
+            },
+            Row::Angle => format!("Angle: {} degrees", self.angle),
+            Row::Speed => format!("Speed: {}x weapon", SPEEDS[self.speed]),
+        }
+    }
+
+    /// Draw the menu on the right side of the screen.
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut lines = vec!["SPAWN MENU   F4 to close, click to spawn".to_string()];
+        for (i, &row) in self.rows().iter().enumerate() {
+            let marker = if i == self.row { ">" } else { " " };
+            lines.push(format!("{} {}", marker, self.describe(row)));
+        }
+        if let Kind::Projectile = KINDS[self.kind] {
+            lines.push("Fired by you".to_string());
+        }
+        let text = graphics::Text::new(lines.join("\n"));
+        let (width, height) = text.dimensions(ctx);
+        let dest = Point2::new(SCREEN_SIZE.0 - width as f32 - 14.0, 200.0);
+        let background = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(
+                dest.x - 4.0,
+                dest.y - 4.0,
+                width as f32 + 8.0,
+                height as f32 + 8.0,
+            ),
+            Color::new(1.0, 1.0, 1.0, 0.7),
+        )?;
+        graphics::draw(ctx, &background, graphics::DrawParam::new())?;
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new().dest(dest).color(graphics::BLACK),
+        )
+    }
+}
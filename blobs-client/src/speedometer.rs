@@ -0,0 +1,149 @@
+use ggez::graphics::{self, Color, DrawMode};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::settings;
+use crate::text::{self, Effect, Style};
+use crate::tuning;
+use crate::{Blob, DT, SCREEN_SIZE, TICKS_PER_SECOND, TICK_TIME};
+
+/// Units to choose from on the settings screen.
+pub const UNITS: [SpeedUnit; 3] = [
+    SpeedUnit::UnitsPerSecond,
+    SpeedUnit::BlobsPerSecond,
+    SpeedUnit::KilometersPerHour,
+];
+// The recent top speed is the fastest of this many seconds.
+const RECENT_TIME: u32 = 3;
+// The gauge is full at this many world units per second.
+const FULL_SPEED: f32 = 1500.0;
+const GAUGE_SIZE: (f32, f32) = (200.0, 10.0);
+// Bottom of the widget, above where toasts are shown.
+const BOTTOM: f32 = SCREEN_SIZE.1 - 60.0;
+const ARROW_LENGTH: f32 = 14.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpeedUnit {
+    /// World units per second.
+    UnitsPerSecond,
+    /// Blob widths per second.
+    BlobsPerSecond,
+    /// As if blobs were a meter wide.
+    KilometersPerHour,
+}
+
+impl SpeedUnit {
+    pub fn name(self) -> &'static str {
+        match self {
+            SpeedUnit::UnitsPerSecond => "units/s",
+            SpeedUnit::BlobsPerSecond => "blobs/s",
+            SpeedUnit::KilometersPerHour => "km/h",
+        }
+    }
+
+    /// The speed in world units per second in this unit.
+    pub fn convert(self, speed: f32) -> f32 {
+        let blobs = speed / (2.0 * tuning::current().blob_radius);
+        match self {
+            SpeedUnit::UnitsPerSecond => speed,
+            SpeedUnit::BlobsPerSecond => blobs,
+            SpeedUnit::KilometersPerHour => 3.6 * blobs,
+        }
+    }
+}
+
+/// Shows how fast the local blob goes, with a marker at the fastest it has
+/// gone in the last few seconds.
+pub struct Speedometer {
+    // World units per second, and where it was headed.
+    speed: f32,
+    direction: Vector2<f32>,
+    // Speeds of the last few seconds of ticks, newest last.
+    recent: VecDeque<f32>,
+}
+
+impl Speedometer {
+    pub fn new() -> Speedometer {
+        Speedometer {
+            speed: 0.0,
+            direction: Vector2::zeros(),
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Call once per tick with the local blob, after it has moved.
+    pub fn update(&mut self, blob: Option<&Blob>) {
+        // Blob velocities are in world units per DT, ticks are TICK_TIME.
+        let vel = blob.map_or(Vector2::zeros(), |blob| blob.vel * DT / TICK_TIME);
+        self.speed = vel.norm();
+        if self.speed > 0.0 {
+            self.direction = vel / self.speed;
+        }
+        self.recent.push_back(self.speed);
+        if self.recent.len() > (RECENT_TIME * TICKS_PER_SECOND) as usize {
+            self.recent.pop_front();
+        }
+    }
+
+    fn recent_max(&self) -> f32 {
+        self.recent.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// Draw the gauge at the bottom middle of the screen, if it is turned on.
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let settings = settings::current();
+        if !settings.speedometer {
+            return Ok(());
+        }
+        let unit = settings.speed_unit;
+        let fraction = |speed: f32| (speed / FULL_SPEED).min(1.0);
+        let left = (SCREEN_SIZE.0 - GAUGE_SIZE.0) / 2.0;
+        let top = BOTTOM - GAUGE_SIZE.1;
+        let area = graphics::Rect::new(left, top, GAUGE_SIZE.0, GAUGE_SIZE.1);
+        let filled =
+            graphics::Rect::new(left, top, GAUGE_SIZE.0 * fraction(self.speed), GAUGE_SIZE.1);
+        let marker_x = left + GAUGE_SIZE.0 * fraction(self.recent_max());
+        let marker = [
+            Point2::new(marker_x, top - 4.0),
+            Point2::new(marker_x, BOTTOM + 4.0),
+        ];
+        let mut builder = graphics::MeshBuilder::new();
+        builder
+            .rectangle(DrawMode::fill(), area, Color::new(0.0, 0.0, 0.0, 0.3))
+            .rectangle(DrawMode::fill(), filled, Color::from_rgb(80, 170, 250))
+            .rectangle(DrawMode::stroke(2.0), area, graphics::BLACK)
+            .line(&marker, 2.0, Color::from_rgb(220, 50, 40))?;
+
+        let label = format!(
+            "{:.0} {}  (top {:.0})",
+            unit.convert(self.speed),
+            unit.name(),
+            unit.convert(self.recent_max())
+        );
+        let style = Style::new(20.0).effect(Effect::Outline(graphics::WHITE));
+        let (width, height) = text::size(ctx, &label, style);
+        let label_pos = Point2::new((SCREEN_SIZE.0 - width) / 2.0, top - height - 6.0);
+        if settings.speed_arrow && self.speed > 0.0 {
+            let center = Point2::new(left - 2.0 * ARROW_LENGTH, BOTTOM - GAUGE_SIZE.1 / 2.0);
+            let tip = center + ARROW_LENGTH * self.direction;
+            let tail = center - ARROW_LENGTH * self.direction;
+            let side = Vector2::new(-self.direction.y, self.direction.x);
+            let back = tip - 0.5 * ARROW_LENGTH * self.direction;
+            builder
+                .line(&[tail, tip], 3.0, graphics::BLACK)?
+                .triangles(
+                    &[
+                        tip,
+                        back + 0.4 * ARROW_LENGTH * side,
+                        back - 0.4 * ARROW_LENGTH * side,
+                    ],
+                    graphics::BLACK,
+                )?;
+        }
+        let mesh = builder.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::new())?;
+        text::draw(ctx, &label, label_pos, style)
+    }
+}
@@ -0,0 +1,169 @@
+//! Images drawn in place of the generated shapes.
+//!
+//! Each sprite is optional: when its file is missing from the resource
+//! directory, the shape it replaces is drawn as before. Sprites are tinted
+//! with the color of what they stand for, so they should be mostly white.
+
+use ggez::filesystem;
+use ggez::graphics::{self, Color, Image, Vertex, WrapMode};
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+use std::cell::RefCell;
+
+const BLOB: &str = "/sprites/blob.png";
+const HOOK: &str = "/sprites/hook.png";
+const TILE: &str = "/sprites/tile.png";
+// Size in world units the hook head is drawn at.
+const HOOK_SIZE: f32 = 16.0;
+
+#[derive(Default)]
+struct Sprites {
+    blob: Option<Image>,
+    hook: Option<Image>,
+    tile: Option<Image>,
+}
+
+thread_local! {
+    static SPRITES: RefCell<Sprites> = RefCell::new(Sprites::default());
+}
+
+/// Load the sprites that are present. Call once at startup.
+pub fn init(ctx: &mut Context) {
+    let mut tile = load(ctx, TILE);
+    if let Some(tile) = &mut tile {
+        tile.set_wrap(WrapMode::Tile, WrapMode::Tile);
+    }
+    let sprites = Sprites {
+        blob: load(ctx, BLOB),
+        hook: load(ctx, HOOK),
+        tile,
+    };
+    SPRITES.with(|current| *current.borrow_mut() = sprites);
+}
+
+fn load(ctx: &mut Context, path: &str) -> Option<Image> {
+    if !filesystem::exists(ctx, path) {
+        log::debug!("No sprite {}, drawing the shape instead", path);
+        return None;
+    }
+    Image::new(ctx, path)
+        .map_err(|e| log::warn!("Could not load sprite {}: {}", path, e))
+        .ok()
+}
+
+pub fn blob_image() -> Option<Image> {
+    SPRITES.with(|sprites| sprites.borrow().blob.clone())
+}
+
+pub fn hook_image() -> Option<Image> {
+    SPRITES.with(|sprites| sprites.borrow().hook.clone())
+}
+
+/// Vertices that stretch the blob sprite over the blob's outline, so it
+/// wobbles along: the center, then each outline point. The outline points go
+/// around the center evenly at rest, starting on the right. None if there is
+/// no blob sprite.
+pub fn blob_vertices(
+    center: Point2<f32>,
+    outline: &[Point2<f32>],
+    color: Color,
+) -> Option<Vec<Vertex>> {
+    blob_image()?;
+    let color = [color.r, color.g, color.b, color.a];
+    let vertex = |p: Point2<f32>, uv: [f32; 2]| Vertex {
+        pos: [p.x, p.y],
+        uv,
+        color,
+    };
+    let mut vertices = vec![vertex(center, [0.5, 0.5])];
+    for (i, &point) in outline.iter().enumerate() {
+        let angle = i as f32 * std::f32::consts::TAU / outline.len() as f32;
+        vertices.push(vertex(
+            point,
+            [0.5 + 0.5 * angle.cos(), 0.5 + 0.5 * angle.sin()],
+        ));
+    }
+    Some(vertices)
+}
+
+/// Draw the blob sprite as an undeformed circle, for replays. Returns false
+/// without drawing if there is no blob sprite.
+pub fn draw_blob_circle(
+    ctx: &mut Context,
+    center: Point2<f32>,
+    radius: f32,
+    color: Color,
+) -> GameResult<bool> {
+    let image = match blob_image() {
+        Some(image) => image,
+        None => return Ok(false),
+    };
+    let scale = 2.0 * radius / f32::from(image.width().max(image.height()));
+    graphics::draw(
+        ctx,
+        &image,
+        graphics::DrawParam::new()
+            .dest(center)
+            .offset(Point2::new(0.5, 0.5))
+            .scale(Vector2::new(scale, scale))
+            .color(color),
+    )?;
+    Ok(true)
+}
+
+/// Draw the hook head at the end of a rope, pointing away from the blob.
+/// Without a hook sprite the rope just ends, as it always has.
+pub fn draw_hook(
+    ctx: &mut Context,
+    from: Point2<f32>,
+    hook: Point2<f32>,
+    color: Color,
+) -> GameResult<()> {
+    match (hook_image(), hook_param(from, hook, color)) {
+        (Some(image), Some(param)) => graphics::draw(ctx, &image, param),
+        _ => Ok(()),
+    }
+}
+
+/// Where and how `draw_hook` draws the hook sprite. None if there is none.
+pub fn hook_param(
+    from: Point2<f32>,
+    hook: Point2<f32>,
+    color: Color,
+) -> Option<graphics::DrawParam> {
+    let image = hook_image()?;
+    let direction = hook - from;
+    let scale = HOOK_SIZE / f32::from(image.width().max(image.height()));
+    Some(
+        graphics::DrawParam::new()
+            .dest(hook)
+            .offset(Point2::new(0.5, 0.5))
+            .rotation(direction.y.atan2(direction.x))
+            .scale(Vector2::new(scale, scale))
+            .color(color),
+    )
+}
+
+/// Fill a rectangle with the level tile, repeated at its own size. Returns
+/// false without drawing if there is no tile sprite.
+pub fn draw_tiled(ctx: &mut Context, rect: graphics::Rect, color: Color) -> GameResult<bool> {
+    let image = match SPRITES.with(|sprites| sprites.borrow().tile.clone()) {
+        Some(image) => image,
+        None => return Ok(false),
+    };
+    let src = graphics::Rect::new(
+        rect.x / f32::from(image.width()),
+        rect.y / f32::from(image.height()),
+        rect.w / f32::from(image.width()),
+        rect.h / f32::from(image.height()),
+    );
+    graphics::draw(
+        ctx,
+        &image,
+        graphics::DrawParam::new()
+            .src(src)
+            .dest(Point2::new(rect.x, rect.y))
+            .color(color),
+    )?;
+    Ok(true)
+}
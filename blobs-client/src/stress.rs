@@ -0,0 +1,241 @@
+use ggez::filesystem;
+use ggez::{Context, GameError, GameResult};
+use nalgebra::{Point2, Vector2};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::balance;
+use crate::collision::wall_point_collision;
+use crate::level::{HostileEntry, Level, Region};
+use crate::physics::{self, Bodies};
+use crate::stuck::{is_deep_in_wall, STUCK_TICKS};
+use crate::tuning;
+use crate::{enforce_hostile_entry, Blob, HookState, SCREEN_SIZE, TICKS_PER_SECOND, TICK_TIME};
+
+const REPORT_DIR: &str = "stress";
+/// Most dummies and seconds a run may ask for, so it finishes within a few
+/// minutes.
+pub const MAX_BLOBS: usize = 500;
+pub const MAX_SECONDS: f32 = 600.0;
+// Seconds a dummy holds on to its hook or lets go between hooks.
+const HOOK_TIME: (f32, f32) = (0.2, 2.0);
+// Problems are reported by the cell of this size they happened in.
+const CELL_SIZE: f32 = 50.0;
+
+/// Ways a map can trap or lose a blob.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum ProblemKind {
+    /// Stayed deep in a wall or blocking base.
+    Stuck,
+    /// Went through a wall or into a blocking base.
+    Tunneled,
+}
+
+/// Problems of one kind in one cell of the map.
+#[derive(Debug, Serialize)]
+pub struct Problem {
+    pub kind: ProblemKind,
+    /// Middle of the cell.
+    pub pos: (f32, f32),
+    pub count: u32,
+}
+
+/// Where dummy blobs got into trouble on a map.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    /// Hash of the level that was run.
+    pub level: String,
+    pub blobs: usize,
+    pub seconds: f32,
+    /// Most frequent first.
+    pub problems: Vec<Problem>,
+}
+
+impl Report {
+    /// Short summary for the toast.
+    pub fn summary(&self) -> String {
+        let count = |kind| {
+            self.problems
+                .iter()
+                .filter(|problem| problem.kind == kind)
+                .map(|problem| problem.count)
+                .sum::<u32>()
+        };
+        format!(
+            "{} blobs for {:.0} s: {} stuck, {} tunneled",
+            self.blobs,
+            self.seconds,
+            count(ProblemKind::Stuck),
+            count(ProblemKind::Tunneled)
+        )
+    }
+}
+
+/// A run going on in the background, so the game keeps responding while
+/// it takes its time.
+pub struct Run {
+    receiver: Receiver<Result<Report, String>>,
+}
+
+impl Run {
+    /// Start a run like run does, with the tuning and balance in use now.
+    pub fn start(level: &Level, rule: HostileEntry, count: usize, seconds: f32) -> Run {
+        let (sender, receiver) = mpsc::channel();
+        let source = level.source.clone();
+        let (tuning, balance) = (tuning::current(), balance::current());
+        thread::spawn(move || {
+            // Both are per thread, and the level is read again from its
+            // source so the run has its own.
+            tuning::set_current(tuning);
+            balance::set_current(balance);
+            let report = Level::parse(&source)
+                .map(|level| run(&level, rule, count, seconds))
+                .map_err(|e| e.to_string());
+            let _ = sender.send(report);
+        });
+        Run { receiver }
+    }
+
+    /// The report, once the run is over.
+    pub fn poll(&self) -> Option<Result<Report, String>> {
+        match self.receiver.try_recv() {
+            Ok(report) => Some(report),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err("the run stopped early".to_string())),
+        }
+    }
+}
+
+/// How a dummy blob is doing.
+struct Dummy {
+    // Seconds until it lets go or hooks again.
+    timer: f32,
+    // Ticks in a row spent deep in a wall.
+    stuck_ticks: u32,
+}
+
+/// Throw dummy blobs around the level with random hooks, physics only, and
+/// note everywhere one gets stuck in a wall or goes through one. Blobs that
+/// went through are put back somewhere free. Bases are walls if the mode
+/// blocks them.
+pub fn run(level: &Level, rule: HostileEntry, count: usize, seconds: f32) -> Report {
+    let blocking: Vec<Region> = match rule {
+        HostileEntry::Block => level.hostile_bases(None).map(|base| base.region).collect(),
+        _ => Vec::new(),
+    };
+    let radius = tuning::current().blob_radius;
+    let free_spot = || loop {
+        let p = Point2::new(
+            between((radius, SCREEN_SIZE.0 - radius)),
+            between((radius, SCREEN_SIZE.1 - radius)),
+        );
+        let clear = blocking
+            .iter()
+            .all(|region| region.blob_collision(p, radius).is_none());
+        if clear {
+            return p;
+        }
+    };
+
+    let mut blobs: BTreeMap<usize, Blob> = (0..count)
+        .map(|id| (id, Blob::new(free_spot(), None)))
+        .collect();
+    // By blob id.
+    let mut dummies: Vec<Dummy> = (0..count)
+        .map(|_| Dummy {
+            timer: between(HOOK_TIME),
+            stuck_ticks: 0,
+        })
+        .collect();
+    let mut problems: HashMap<(ProblemKind, (i32, i32)), u32> = HashMap::new();
+    let mut note = |kind, p: Point2<f32>| {
+        let cell = (
+            (p.x / CELL_SIZE).floor() as i32,
+            (p.y / CELL_SIZE).floor() as i32,
+        );
+        *problems.entry((kind, cell)).or_insert(0) += 1;
+    };
+
+    let mut bodies = Bodies::new();
+    let mut damage = Vec::new();
+    let ticks = (seconds * TICKS_PER_SECOND as f32) as u32;
+    for _ in 0..ticks {
+        for (&id, blob) in blobs.iter_mut() {
+            let dummy = &mut dummies[id];
+            dummy.timer -= TICK_TIME;
+            if dummy.timer > 0.0 {
+                continue;
+            }
+            dummy.timer = between(HOOK_TIME);
+            if matches!(blob.hook, HookState::None) {
+                let angle = between((0.0, std::f32::consts::TAU));
+                blob.aim_vec = Vector2::new(angle.cos(), angle.sin());
+                blob.fire_hook();
+            } else {
+                blob.hook = HookState::None;
+            }
+        }
+        physics::step(&mut blobs, &mut bodies);
+        for (&id, blob) in blobs.iter_mut() {
+            blob.update();
+            enforce_hostile_entry(level, rule, id, blob, &mut damage);
+            damage.clear();
+            let dummy = &mut dummies[id];
+            let inside_blocking = blocking.iter().any(|region| region.contains(blob.center));
+            if wall_point_collision(blob.center).is_some() || inside_blocking {
+                note(ProblemKind::Tunneled, blob.center);
+                *blob = Blob::new(free_spot(), None);
+                dummy.stuck_ticks = 0;
+            } else if is_deep_in_wall(&blocking, blob.center) {
+                dummy.stuck_ticks += 1;
+                if dummy.stuck_ticks == STUCK_TICKS {
+                    note(ProblemKind::Stuck, blob.center);
+                }
+            } else {
+                dummy.stuck_ticks = 0;
+            }
+        }
+    }
+
+    let mut problems: Vec<Problem> = problems
+        .into_iter()
+        .map(|((kind, (x, y)), count)| Problem {
+            kind,
+            pos: ((x as f32 + 0.5) * CELL_SIZE, (y as f32 + 0.5) * CELL_SIZE),
+            count,
+        })
+        .collect();
+    problems.sort_by_key(|problem| std::cmp::Reverse(problem.count));
+    Report {
+        level: level.hash.clone(),
+        blobs: count,
+        seconds,
+        problems,
+    }
+}
+
+/// Save the report as RON in the user data directory, for map checks to
+/// read. Returns the saved file's path.
+pub fn save(ctx: &Context, report: &Report) -> GameResult<PathBuf> {
+    let dir = filesystem::user_data_dir(ctx).join(REPORT_DIR);
+    fs::create_dir_all(&dir)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let prefix: String = report.level.chars().take(8).collect();
+    let path = dir.join(format!("{}-{}.ron", prefix, timestamp));
+    let source = ron::ser::to_string_pretty(report, ron::ser::PrettyConfig::default())
+        .map_err(|e| GameError::FilesystemError(e.to_string()))?;
+    fs::write(&path, source)?;
+    Ok(path)
+}
+
+fn between((low, high): (f32, f32)) -> f32 {
+    low + (high - low) * rand::random::<f32>()
+}
@@ -0,0 +1,26 @@
+use ggez::graphics::Color;
+
+pub use blobs_core::team::Team;
+
+/// How teams are shown.
+pub trait TeamColor {
+    fn color(self) -> Color;
+
+    /// Faded version of the team color, used for large areas such as bases.
+    fn area_color(self) -> Color;
+}
+
+impl TeamColor for Team {
+    fn color(self) -> Color {
+        match self {
+            Team::Red => Color::from_rgb(200, 60, 60),
+            Team::Blue => Color::from_rgb(60, 90, 200),
+        }
+    }
+
+    fn area_color(self) -> Color {
+        let mut color = self.color();
+        color.a = 0.2;
+        color
+    }
+}
@@ -0,0 +1,135 @@
+use ggez::graphics::{self, Color};
+use ggez::input::mouse::MouseButton;
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+
+use crate::TICK_TIME;
+
+// Seconds a finished mark stays on screen.
+const MARK_TIME: f32 = 6.0;
+// Seconds over which a mark fades out at the end.
+const FADE_TIME: f32 = 1.0;
+const LINE_WIDTH: f32 = 5.0;
+const ARROW_HEAD: f32 = 20.0;
+
+#[derive(Clone, Copy, Debug)]
+enum Shape {
+    Arrow(Point2<f32>, Point2<f32>),
+    Circle(Point2<f32>, f32),
+}
+
+impl Shape {
+    /// Left drags draw arrows, right drags draw circles.
+    fn dragged(button: MouseButton, from: Point2<f32>, to: Point2<f32>) -> Option<Shape> {
+        match button {
+            MouseButton::Left => Some(Shape::Arrow(from, to)),
+            MouseButton::Right => Some(Shape::Circle(from, (to - from).norm())),
+            _ => None,
+        }
+    }
+
+    fn draw(self, ctx: &mut Context, color: Color) -> GameResult<()> {
+        let mut builder = graphics::MeshBuilder::new();
+        match self {
+            Shape::Arrow(from, to) => {
+                let along = to - from;
+                if along.norm() < 1.0 {
+                    return Ok(());
+                }
+                let dir = along.normalize();
+                let side = Vector2::new(-dir.y, dir.x);
+                let base = to - ARROW_HEAD * dir;
+                builder.line(&[from, base], LINE_WIDTH, color)?;
+                builder.polygon(
+                    graphics::DrawMode::fill(),
+                    &[
+                        to,
+                        base + 0.5 * ARROW_HEAD * side,
+                        base - 0.5 * ARROW_HEAD * side,
+                    ],
+                    color,
+                )?;
+            }
+            Shape::Circle(center, radius) => {
+                if radius < 1.0 {
+                    return Ok(());
+                }
+                builder.circle(
+                    graphics::DrawMode::stroke(LINE_WIDTH),
+                    center,
+                    radius,
+                    0.5,
+                    color,
+                );
+            }
+        }
+        let mesh = builder.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::new())
+    }
+}
+
+/// Arrows and circles drawn over a replay with the mouse to point out what
+/// happens. Marks fade away after a few seconds.
+pub struct Telestrator {
+    marks: Vec<(Shape, f32)>,
+    // Button held and where the drag started.
+    drag: Option<(MouseButton, Point2<f32>)>,
+    cursor: Point2<f32>,
+}
+
+impl Telestrator {
+    pub fn new() -> Telestrator {
+        Telestrator {
+            marks: Vec::new(),
+            drag: None,
+            cursor: Point2::origin(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.marks.clear();
+        self.drag = None;
+    }
+
+    /// Call once per tick.
+    pub fn update(&mut self) {
+        for (_shape, time_left) in self.marks.iter_mut() {
+            *time_left -= TICK_TIME;
+        }
+        self.marks.retain(|&(_shape, time_left)| time_left > 0.0);
+    }
+
+    pub fn mouse_motion(&mut self, p: Point2<f32>) {
+        self.cursor = p;
+    }
+
+    pub fn mouse_button_down(&mut self, button: MouseButton, p: Point2<f32>) {
+        self.cursor = p;
+        self.drag = Some((button, p));
+    }
+
+    pub fn mouse_button_up(&mut self, button: MouseButton, p: Point2<f32>) {
+        if let Some((drag_button, from)) = self.drag {
+            if drag_button == button {
+                self.drag = None;
+                if let Some(shape) = Shape::dragged(button, from, p) {
+                    self.marks.push((shape, MARK_TIME));
+                }
+            }
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let color = Color::from_rgb(250, 200, 0);
+        for &(shape, time_left) in &self.marks {
+            let alpha = (time_left / FADE_TIME).min(1.0);
+            shape.draw(ctx, Color { a: alpha, ..color })?;
+        }
+        if let Some((button, from)) = self.drag {
+            if let Some(shape) = Shape::dragged(button, from, self.cursor) {
+                shape.draw(ctx, color)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+//! Text drawing with font fallback and outline or shadow styles.
+//!
+//! Fonts listed in `FALLBACK_FONTS` are used when they exist in the resource
+//! directory, e.g. a Noto CJK font and an emoji font. Each character is drawn
+//! with the first of them that has a glyph for it, falling back to the
+//! built-in font.
+
+use ggez::filesystem;
+use ggez::graphics::{self, Color, Font, Scale, Text, TextFragment};
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::Read;
+
+const FALLBACK_FONTS: &[&str] = &[
+    "/fonts/NotoSans-Regular.ttf",
+    "/fonts/NotoSansCJK-Regular.ttc",
+    "/fonts/NotoEmoji-Regular.ttf",
+];
+// Laid out texts are kept until there are this many, then the cache starts
+// over.
+const MAX_CACHED_TEXTS: usize = 256;
+
+/// How text stands out from the background.
+#[derive(Clone, Copy)]
+pub enum Effect {
+    Plain,
+    /// Drop shadow in the given color.
+    Shadow(Color),
+    /// Outline in the given color.
+    Outline(Color),
+}
+
+#[derive(Clone, Copy)]
+pub struct Style {
+    pub size: f32,
+    pub color: Color,
+    pub effect: Effect,
+}
+
+impl Style {
+    pub fn new(size: f32) -> Style {
+        Style {
+            size,
+            color: graphics::BLACK,
+            effect: Effect::Plain,
+        }
+    }
+
+    pub fn color(self, color: Color) -> Style {
+        Style { color, ..self }
+    }
+
+    pub fn effect(self, effect: Effect) -> Style {
+        Style { effect, ..self }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Style {
+        Style::new(graphics::DEFAULT_FONT_SCALE)
+    }
+}
+
+struct LoadedFont {
+    font: Font,
+    data: Vec<u8>,
+}
+
+impl LoadedFont {
+    fn has_glyph(&self, character: char) -> bool {
+        ttf_parser::Face::parse(&self.data, 0)
+            .is_ok_and(|face| face.glyph_index(character).is_some())
+    }
+}
+
+#[derive(Default)]
+struct Fonts {
+    fallbacks: Vec<LoadedFont>,
+    // Font used for each character seen so far, None for the built-in font.
+    glyph_fonts: HashMap<char, Option<usize>>,
+    texts: HashMap<(String, u32), Text>,
+}
+
+impl Fonts {
+    fn font_for(&mut self, character: char) -> Option<usize> {
+        let fallbacks = &self.fallbacks;
+        *self
+            .glyph_fonts
+            .entry(character)
+            .or_insert_with(|| fallbacks.iter().position(|f| f.has_glyph(character)))
+    }
+
+    /// Lay out text, splitting it into runs that share a font.
+    fn text(&mut self, string: &str, size: f32) -> Text {
+        let key = (string.to_string(), size.to_bits());
+        if let Some(text) = self.texts.get(&key) {
+            return text.clone();
+        }
+        let mut text = Text::default();
+        let mut run = String::new();
+        let mut run_font = None;
+        for character in string.chars() {
+            let font = self.font_for(character);
+            if font != run_font && !run.is_empty() {
+                text.add(self.fragment(&run, run_font, size));
+                run.clear();
+            }
+            run_font = font;
+            run.push(character);
+        }
+        if !run.is_empty() {
+            text.add(self.fragment(&run, run_font, size));
+        }
+        if self.texts.len() >= MAX_CACHED_TEXTS {
+            self.texts.clear();
+        }
+        self.texts.insert(key, text.clone());
+        text
+    }
+
+    fn fragment(&self, run: &str, font: Option<usize>, size: f32) -> TextFragment {
+        let font = font.map_or_else(Font::default, |i| self.fallbacks[i].font);
+        TextFragment::new(run)
+            .font(font)
+            .scale(Scale::uniform(size))
+    }
+}
+
+thread_local! {
+    static FONTS: RefCell<Fonts> = RefCell::new(Fonts::default());
+}
+
+/// Load the fallback fonts that are present. Call once at startup.
+pub fn init(ctx: &mut Context) {
+    let mut fallbacks = Vec::new();
+    for path in FALLBACK_FONTS {
+        let mut data = Vec::new();
+        let loaded = filesystem::open(ctx, path)
+            .and_then(|mut file| Ok(file.read_to_end(&mut data)?))
+            .and_then(|_| Font::new_glyph_font_bytes(ctx, &data));
+        match loaded {
+            Ok(font) => fallbacks.push(LoadedFont { font, data }),
+            Err(e) => log::debug!("Font {} not used: {}", path, e),
+        }
+    }
+    FONTS.with(|fonts| {
+        *fonts.borrow_mut() = Fonts {
+            fallbacks,
+            ..Fonts::default()
+        }
+    });
+}
+
+/// Lay out text in the given size, ready to be measured or drawn.
+pub fn layout(string: &str, size: f32) -> Text {
+    FONTS.with(|fonts| fonts.borrow_mut().text(string, size))
+}
+
+/// Size of text when drawn with the given style.
+pub fn size(ctx: &mut Context, string: &str, style: Style) -> (f32, f32) {
+    let text = layout(string, style.size);
+    (text.width(ctx) as f32, text.height(ctx) as f32)
+}
+
+pub fn draw(ctx: &mut Context, string: &str, dest: Point2<f32>, style: Style) -> GameResult<()> {
+    let text = layout(string, style.size);
+    let offsets: &[(f32, f32)] = match style.effect {
+        Effect::Plain => &[],
+        Effect::Shadow(_) => &[(2.0, 2.0)],
+        Effect::Outline(_) => &[
+            (-1.5, -1.5),
+            (0.0, -1.5),
+            (1.5, -1.5),
+            (-1.5, 0.0),
+            (1.5, 0.0),
+            (-1.5, 1.5),
+            (0.0, 1.5),
+            (1.5, 1.5),
+        ],
+    };
+    if let Effect::Shadow(color) | Effect::Outline(color) = style.effect {
+        for &(dx, dy) in offsets {
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::new()
+                    .dest(Point2::new(dest.x + dx, dest.y + dy))
+                    .color(color),
+            )?;
+        }
+    }
+    graphics::draw(
+        ctx,
+        &text,
+        graphics::DrawParam::new().dest(dest).color(style.color),
+    )
+}
@@ -0,0 +1,66 @@
+use ggez::conf::NumSamples;
+use ggez::filesystem;
+use ggez::graphics::{self, Canvas, Image, ImageFormat};
+use ggez::{Context, GameResult};
+
+use crate::level::{self, Level};
+use crate::mode;
+use crate::objective::Objective;
+use crate::pickup::{self, Pickups};
+
+// Folder in the writable user directory where thumbnails are kept, named by
+// level hash so edited levels get new ones.
+const THUMBNAIL_DIR: &str = "/thumbnails";
+pub const THUMBNAIL_SIZE: u16 = 250;
+
+fn path(level: &Level) -> String {
+    format!("{}/{}.png", THUMBNAIL_DIR, level.hash)
+}
+
+/// Load the thumbnail of a level, rendering and saving it first if there is
+/// none.
+pub fn load_or_create(ctx: &mut Context, level: &Level) -> GameResult<Image> {
+    let path = path(level);
+    if filesystem::exists(ctx, &path) {
+        return Image::new(ctx, &path);
+    }
+    create(ctx, level)
+}
+
+/// Render an overview of the whole level and save it next to the other
+/// thumbnails.
+pub fn create(ctx: &mut Context, level: &Level) -> GameResult<Image> {
+    // The screen coordinates cover the whole level, so drawing to a small
+    // canvas gives a scaled down overview.
+    let canvas = Canvas::new(ctx, THUMBNAIL_SIZE, THUMBNAIL_SIZE, NumSamples::One)?;
+    graphics::set_canvas(ctx, Some(&canvas));
+    graphics::clear(ctx, graphics::WHITE);
+    let drawn = draw_overview(ctx, level);
+    graphics::set_canvas(ctx, None);
+    drawn?;
+
+    // Canvas rows come out bottom up.
+    let row_len = THUMBNAIL_SIZE as usize * 4;
+    let pixels: Vec<u8> = canvas
+        .image()
+        .to_rgba8(ctx)?
+        .chunks(row_len)
+        .rev()
+        .flatten()
+        .cloned()
+        .collect();
+    let image = Image::from_rgba8(ctx, THUMBNAIL_SIZE, THUMBNAIL_SIZE, &pixels)?;
+    filesystem::create_dir(ctx, THUMBNAIL_DIR)?;
+    image.encode(ctx, ImageFormat::Png, path(level))?;
+    Ok(image)
+}
+
+fn draw_overview(ctx: &mut Context, level: &Level) -> GameResult<()> {
+    level::draw(ctx, level)?;
+    mode::create(&level.mode, level).draw(ctx)?;
+    pickup::draw(ctx, &Pickups::new(&level.pickups))?;
+    for spawn in &level.objectives {
+        Objective::new(spawn).draw(ctx)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use crate::TICK_TIME;
+
+/// Time scales the debug keys choose between.
+pub const TIME_SCALES: [f32; 4] = [0.25, 0.5, 1.0, 2.0];
+// Most ticks banked at once. Time beyond that, from a long frame or a
+// machine too slow for the time scale, is dropped so catching up can't make
+// the next frame even longer. It covers 2x speed at the 30 fps battery
+// saver cap.
+const MAX_CATCH_UP_TICKS: u32 = 5;
+
+thread_local! {
+    static SCALE: Cell<f32> = const { Cell::new(1.0) };
+}
+
+/// How fast the simulation runs compared to real time.
+pub fn scale() -> f32 {
+    SCALE.with(|scale| scale.get())
+}
+
+pub fn set_scale(scale: f32) {
+    SCALE.with(|current| current.set(scale));
+}
+
+/// Fixed-step accumulator. Real time between frames, times the time scale,
+/// is banked and spent in whole ticks, so slow motion runs fewer ticks per
+/// frame rather than drawing fewer frames.
+pub struct Accumulator {
+    banked: f32,
+}
+
+impl Accumulator {
+    pub fn new() -> Accumulator {
+        Accumulator { banked: 0.0 }
+    }
+
+    /// Bank the real time since the last frame. Call once per frame.
+    pub fn add(&mut self, elapsed: Duration) {
+        self.banked = (self.banked + elapsed.as_secs_f32() * scale())
+            .min(MAX_CATCH_UP_TICKS as f32 * TICK_TIME);
+    }
+
+    /// Spend a tick if enough time is banked, returning whether one was.
+    pub fn tick(&mut self) -> bool {
+        if self.banked < TICK_TIME {
+            return false;
+        }
+        self.banked -= TICK_TIME;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticks(timestep: &mut Accumulator) -> u32 {
+        let mut ticks = 0;
+        while timestep.tick() {
+            ticks += 1;
+        }
+        ticks
+    }
+
+    #[test]
+    fn long_frames_only_catch_up_a_few_ticks() {
+        let mut timestep = Accumulator::new();
+        timestep.add(Duration::from_secs(3));
+        assert_eq!(ticks(&mut timestep), MAX_CATCH_UP_TICKS);
+        // The dropped time doesn't come back later.
+        timestep.add(Duration::from_secs_f32(TICK_TIME * 1.5));
+        assert_eq!(ticks(&mut timestep), 1);
+    }
+}
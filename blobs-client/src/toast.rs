@@ -0,0 +1,47 @@
+use ggez::graphics;
+use ggez::{Context, GameResult};
+use nalgebra::Point2;
+
+use crate::text::{self, Effect, Style};
+use crate::{SCREEN_SIZE, TICK_TIME};
+
+// Seconds a message stays on screen.
+const TOAST_TIME: f32 = 3.0;
+
+/// Short message shown at the bottom of the screen, e.g. to confirm that
+/// something was saved.
+pub struct Toast {
+    message: Option<(String, f32)>,
+}
+
+impl Toast {
+    pub fn new() -> Toast {
+        Toast { message: None }
+    }
+
+    pub fn show(&mut self, message: String) {
+        self.message = Some((message, TOAST_TIME));
+    }
+
+    /// Call once per tick.
+    pub fn update(&mut self) {
+        if let Some((_message, time_left)) = &mut self.message {
+            *time_left -= TICK_TIME;
+            if *time_left <= 0.0 {
+                self.message = None;
+            }
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        if let Some((message, _time_left)) = &self.message {
+            let style = Style::default()
+                .color(graphics::WHITE)
+                .effect(Effect::Outline(graphics::BLACK));
+            let (width, height) = text::size(ctx, message, style);
+            let dest = Point2::new((SCREEN_SIZE.0 - width) / 2.0, SCREEN_SIZE.1 - height - 20.0);
+            text::draw(ctx, message, dest, style)?;
+        }
+        Ok(())
+    }
+}
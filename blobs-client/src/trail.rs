@@ -0,0 +1,36 @@
+use blobs_core::trail::Trail;
+use ggez::graphics::Color;
+use nalgebra::Vector2;
+
+use crate::batch::Batch;
+use crate::tuning;
+
+// Blobs start leaving a trail at the lower speed, and it is at its
+// strongest from the higher one.
+const SPEEDS: (f32, f32) = (60.0, 150.0);
+const MAX_ALPHA: f32 = 0.5;
+
+/// Add the trail to a batch, tapering from most of the blob's width to
+/// nothing and fading out towards its end. Slow blobs leave no trail.
+pub fn add(batch: &mut Batch, trail: &Trail, vel: Vector2<f32>, color: Color) {
+    let strength = ((vel.norm() - SPEEDS.0) / (SPEEDS.1 - SPEEDS.0)).clamp(0.0, 1.0);
+    if strength <= 0.0 {
+        return;
+    }
+    let radius = tuning::current().blob_radius;
+    let trail = trail.points();
+    let last = (trail.len().max(2) - 1) as f32;
+    let points: Vec<_> = trail
+        .iter()
+        .enumerate()
+        .map(|(i, &point)| {
+            let left = 1.0 - i as f32 / last;
+            let color = Color {
+                a: color.a * MAX_ALPHA * strength * left,
+                ..color
+            };
+            (point, 1.6 * radius * left, color)
+        })
+        .collect();
+    batch.ribbon(&points);
+}
@@ -0,0 +1,92 @@
+use ggez::{filesystem, Context};
+use std::io::Read;
+
+pub use blobs_core::tuning::*;
+
+use crate::TICK_TIME;
+
+// In the resource directory, next to the balance file.
+const PATH: &str = "/tuning.ron";
+// Seconds between checks for changes to the file.
+const POLL_TIME: f32 = 1.0;
+
+/// Read the tuning file and make it current. On errors the current tuning
+/// is kept and the errors are returned, one per line. Without a tuning file
+/// the defaults are used.
+pub fn load(ctx: &mut Context) -> Result<(), String> {
+    match read(ctx)? {
+        Some(source) => apply(&source),
+        None => {
+            log::info!("No tuning file, using the default tuning");
+            Ok(())
+        }
+    }
+}
+
+fn read(ctx: &mut Context) -> Result<Option<String>, String> {
+    if !filesystem::exists(ctx, PATH) {
+        return Ok(None);
+    }
+    let mut source = String::new();
+    filesystem::open(ctx, PATH)
+        .and_then(|mut file| Ok(file.read_to_string(&mut source)?))
+        .map_err(|e| format!("Could not read {}: {}", PATH, e))?;
+    Ok(Some(source))
+}
+
+fn apply(source: &str) -> Result<(), String> {
+    let tuning: Tuning =
+        ron::de::from_str(source).map_err(|e| format!("Invalid {}: {}", PATH, e))?;
+    let errors = tuning.validate();
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    set_current(tuning);
+    Ok(())
+}
+
+/// Applies changes to the tuning file while the game runs, so the feel can
+/// be tried out without restarting. The file is read about once a second.
+pub struct Watcher {
+    // Seconds until the file is read again.
+    timer: f32,
+    // What the file held when it was last read.
+    source: Option<String>,
+}
+
+impl Watcher {
+    pub fn new(ctx: &mut Context) -> Watcher {
+        Watcher {
+            timer: POLL_TIME,
+            source: read(ctx).ok().flatten(),
+        }
+    }
+
+    /// Call once per tick. Returns how applying the file went, if it changed.
+    pub fn update(&mut self, ctx: &mut Context) -> Option<Result<(), String>> {
+        self.timer -= TICK_TIME;
+        if self.timer > 0.0 {
+            return None;
+        }
+        self.timer = POLL_TIME;
+        // Editors can leave the file unreadable for a moment while saving
+        // it, so it's read again next time.
+        let source = match read(ctx) {
+            Ok(source) => source,
+            Err(_) => return None,
+        };
+        if source == self.source {
+            return None;
+        }
+        self.source = source;
+        let result = match &self.source {
+            Some(source) => apply(source),
+            // The file was removed, go back to the defaults.
+            None => {
+                set_current(Tuning::default());
+                Ok(())
+            }
+        };
+        Some(result)
+    }
+}
@@ -0,0 +1,46 @@
+use ggez::graphics;
+use ggez::{Context, GameResult};
+use nalgebra::{Point2, Vector2};
+
+use crate::settings;
+
+// The automatic scale stays within these, so tiny or huge windows still get
+// a usable HUD.
+const MIN_AUTO_SCALE: f32 = 0.5;
+const MAX_AUTO_SCALE: f32 = 2.0;
+
+/// How much larger than designed to draw the HUD and other overlays. The
+/// world always fills the window, but the overlays keep the same size in
+/// logical pixels, which already include the monitor's scale factor. The
+/// player's UI scale setting applies on top.
+pub fn scale(ctx: &Context) -> f32 {
+    let (width, _) = graphics::drawable_size(ctx);
+    let screen = graphics::screen_coordinates(ctx);
+    let auto = if width > 0.0 {
+        (screen.w / width).clamp(MIN_AUTO_SCALE, MAX_AUTO_SCALE)
+    } else {
+        1.0
+    };
+    auto * settings::current().ui_scale
+}
+
+/// Draw an overlay in screen coordinates, whatever transform the world is
+/// drawn with, scaled by the UI scale. The anchor, such as the corner the
+/// overlay is laid out from, stays in place.
+pub fn draw_scaled(
+    ctx: &mut Context,
+    anchor: Point2<f32>,
+    draw: impl FnOnce(&mut Context) -> GameResult<()>,
+) -> GameResult<()> {
+    let scale = scale(ctx);
+    let transform = graphics::DrawParam::new()
+        .dest(anchor - scale * anchor.coords)
+        .scale(Vector2::new(scale, scale))
+        .to_matrix();
+    graphics::push_transform(ctx, Some(transform));
+    graphics::apply_transformations(ctx)?;
+    let result = draw(ctx);
+    graphics::pop_transform(ctx);
+    graphics::apply_transformations(ctx)?;
+    result
+}
@@ -0,0 +1,188 @@
+use serde::Deserialize;
+use std::env;
+use std::error::Error;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use crate::logging;
+use crate::settings;
+use crate::PROTOCOL_VERSION;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/freidrichen/blobs/releases/latest";
+// Overrides the update_check setting: 0 skips the check, e.g. when playing
+// offline, and 1 checks even if the setting is off.
+const CHECK_ENV_VAR: &str = "BLOBS_UPDATE_CHECK";
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+impl Release {
+    /// Releases state their network protocol in the notes, on a line like
+    /// "Protocol: 2".
+    fn protocol_version(&self) -> Option<u32> {
+        self.body.as_ref()?.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            if key.trim().eq_ignore_ascii_case("protocol") {
+                value.trim().parse().ok()
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Parse a version like "v1.2.3" or "1.2.3-beta.1" into something that
+/// compares the way versions do. Pre-releases come before their release.
+fn parse_version(version: &str) -> Option<(u32, u32, u32, bool)> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    // Build metadata doesn't count.
+    let version = version.split('+').next()?;
+    let (version, release) = match version.split_once('-') {
+        Some((version, _)) => (version, false),
+        None => (version, true),
+    };
+    let numbers: Vec<u32> = version
+        .split('.')
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    match numbers[..] {
+        [major, minor] => Some((major, minor, 0, release)),
+        [major, minor, patch] => Some((major, minor, patch, release)),
+        _ => None,
+    }
+}
+
+fn fetch_latest() -> Result<Release, Box<dyn Error>> {
+    log::info!(target: logging::NET, "Checking {} for updates", RELEASES_URL);
+    let release: Release = ureq::get(RELEASES_URL)
+        .set("User-Agent", concat!("blobs/", env!("CARGO_PKG_VERSION")))
+        .call()?
+        .into_json()?;
+    log::debug!(target: logging::NET, "Latest release is {}", release.tag_name);
+    Ok(release)
+}
+
+/// Describe a newer release than the current version to the player, or None
+/// if the current version is up to date.
+fn notice(release: &Release, current: &str) -> Option<String> {
+    let latest = parse_version(&release.tag_name)?;
+    if latest <= parse_version(current)? {
+        return None;
+    }
+    let mut notice = format!("Blobs {} is available", release.tag_name);
+    if release
+        .protocol_version()
+        .is_some_and(|protocol| protocol != PROTOCOL_VERSION)
+    {
+        notice.push_str(". It plays over a new network protocol, so you can't join servers running it until you update");
+    }
+    Some(notice)
+}
+
+/// Whether to check, from the environment variable if it is set to 0 or 1,
+/// and from the setting otherwise.
+fn is_enabled(env_value: Option<&str>, setting: bool) -> bool {
+    match env_value {
+        Some("0") => false,
+        Some("1") => true,
+        _ => setting,
+    }
+}
+
+/// Look for a newer release in the background, if the player wants to. The
+/// receiver gets a notice for the player if there is one. Failures are only
+/// logged, since the game works fine without the check.
+pub fn check_in_background() -> Option<Receiver<String>> {
+    let env_value = env::var(CHECK_ENV_VAR).ok();
+    if !is_enabled(env_value.as_deref(), settings::current().update_check) {
+        return None;
+    }
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || match fetch_latest() {
+        Ok(release) => {
+            if let Some(notice) = notice(&release, env!("CARGO_PKG_VERSION")) {
+                let _ = sender.send(notice);
+            }
+        }
+        Err(e) => log::info!(target: logging::NET, "Update check failed: {}", e),
+    });
+    Some(receiver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag_name: &str, body: Option<&str>) -> Release {
+        Release {
+            tag_name: tag_name.to_string(),
+            body: body.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_versions() {
+        assert_eq!(parse_version("v1.2.3"), Some((1, 2, 3, true)));
+        assert_eq!(parse_version("1.2.3"), Some((1, 2, 3, true)));
+        assert_eq!(parse_version("v0.10"), Some((0, 10, 0, true)));
+        assert_eq!(parse_version("1.2.3-beta.1"), Some((1, 2, 3, false)));
+        assert_eq!(parse_version("1.2.3+build.5"), Some((1, 2, 3, true)));
+        assert_eq!(parse_version("1.2.3+build-5"), Some((1, 2, 3, true)));
+        assert_eq!(parse_version("1"), None);
+        assert_eq!(parse_version("1.x.3"), None);
+        assert_eq!(parse_version("1.2.x"), None);
+        assert_eq!(parse_version("1.2.3.4"), None);
+        assert_eq!(parse_version("nightly"), None);
+        assert!(parse_version("1.2.3-rc.1") < parse_version("1.2.3"));
+        assert!(parse_version("1.2.3") < parse_version("1.2.4-rc.1"));
+        assert!(parse_version("1.2.10") > parse_version("1.2.9"));
+    }
+
+    #[test]
+    fn notices_newer_releases_only() {
+        assert!(notice(&release("v0.2.0", None), "0.1.0").is_some());
+        assert!(notice(&release("0.1.1", None), "0.1.0").is_some());
+        assert!(notice(&release("v0.1.0", None), "0.1.0").is_none());
+        assert!(notice(&release("v0.1.0", None), "0.1.0-rc.1").is_some());
+        assert!(notice(&release("v0.2.0-rc.1", None), "0.2.0").is_none());
+        assert!(notice(&release("v0.0.9", None), "0.1.0").is_none());
+        assert!(notice(&release("latest", None), "0.1.0").is_none());
+    }
+
+    #[test]
+    fn reads_the_protocol_version() {
+        let notes = |body| release("v9.0.0", Some(body)).protocol_version();
+        assert_eq!(notes("Fixes\n\nProtocol: 3\n"), Some(3));
+        assert_eq!(notes("protocol:2"), Some(2));
+        assert_eq!(notes("Protocol: soon"), None);
+        assert_eq!(notes("Just fixes"), None);
+        assert_eq!(release("v9.0.0", None).protocol_version(), None);
+
+        let other = format!("Protocol: {}", PROTOCOL_VERSION + 1);
+        let same = format!("Protocol: {}", PROTOCOL_VERSION);
+        let warns = |body: Option<&str>| {
+            notice(&release("v9.0.0", body), "0.1.0")
+                .unwrap()
+                .contains("network protocol")
+        };
+        assert!(warns(Some(&other)));
+        assert!(!warns(Some(&same)));
+        // Without a protocol version nobody is told they can't join.
+        assert!(!warns(None));
+    }
+
+    #[test]
+    fn environment_overrides_the_setting() {
+        assert!(!is_enabled(Some("0"), true));
+        assert!(is_enabled(Some("1"), false));
+        assert!(is_enabled(None, true));
+        assert!(!is_enabled(None, false));
+        assert!(is_enabled(Some("yes"), true));
+        assert!(!is_enabled(Some(""), false));
+    }
+}
@@ -0,0 +1,162 @@
+use ggez::filesystem;
+use ggez::Context;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::level::{self, Level};
+use crate::logging;
+
+// Base URL of the map repository, e.g. "https://maps.example.com". Maps are
+// stored at "<base>/maps/<hash>.ron".
+const REPO_ENV_VAR: &str = "BLOBS_MAP_REPO";
+// Folder in the user data directory where downloaded maps are cached.
+pub const CACHE_DIR: &str = "maps";
+
+/// Outcome of a map transfer.
+#[derive(Debug)]
+pub enum Transfer {
+    Uploaded {
+        hash: String,
+    },
+    /// The map is in the cache and can be loaded from `level_path(hash)`.
+    Downloaded {
+        hash: String,
+    },
+    Failed(String),
+}
+
+/// Whether the text could be a map hash as level::hash makes them: 64
+/// lowercase hex digits. Hashes from the server or a share are checked
+/// before they become part of a path or URL.
+fn is_map_hash(hash: &str) -> bool {
+    hash.len() == 64
+        && hash
+            .bytes()
+            .all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte))
+}
+
+/// Path of a cached map, for `Level::load`.
+pub fn level_path(hash: &str) -> String {
+    format!("/{}/{}.ron", CACHE_DIR, hash)
+}
+
+/// Uploads maps to and downloads maps from a simple HTTP map repository, in
+/// the background. Downloaded maps are cached by hash so each version is
+/// only fetched once.
+pub struct Workshop {
+    repo: Option<String>,
+    cache_dir: PathBuf,
+    sender: Sender<Transfer>,
+    receiver: Receiver<Transfer>,
+}
+
+impl Workshop {
+    pub fn new(ctx: &Context) -> Workshop {
+        let (sender, receiver) = mpsc::channel();
+        Workshop {
+            repo: env::var(REPO_ENV_VAR).ok(),
+            cache_dir: filesystem::user_data_dir(ctx).join(CACHE_DIR),
+            sender,
+            receiver,
+        }
+    }
+
+    fn map_url(&self, hash: &str) -> Result<String, String> {
+        match &self.repo {
+            Some(repo) => Ok(format!("{}/maps/{}.ron", repo.trim_end_matches('/'), hash)),
+            None => Err(format!("No map repository set, see {}", REPO_ENV_VAR)),
+        }
+    }
+
+    fn report(&self, transfer: Transfer) {
+        // The receiver lives as long as self, so sending can't fail.
+        let _ = self.sender.send(transfer);
+    }
+
+    fn run<F>(&self, transfer: F)
+    where
+        F: FnOnce() -> Result<Transfer, Box<dyn Error>> + Send + 'static,
+    {
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = transfer().unwrap_or_else(|e| Transfer::Failed(e.to_string()));
+            log::info!(target: logging::NET, "Workshop transfer finished: {:?}", result);
+            let _ = sender.send(result);
+        });
+    }
+
+    pub fn upload(&self, level: &Level) {
+        let url = match self.map_url(&level.hash) {
+            Ok(url) => url,
+            Err(e) => return self.report(Transfer::Failed(e)),
+        };
+        let (hash, source) = (level.hash.clone(), level.source.clone());
+        log::info!(target: logging::NET, "Uploading map {} to {}", hash, url);
+        self.run(move || {
+            ureq::put(&url).send_string(&source)?;
+            Ok(Transfer::Uploaded { hash })
+        });
+    }
+
+    pub fn download(&self, hash: &str) {
+        if !is_map_hash(hash) {
+            return self.report(Transfer::Failed(format!("{} isn't a map hash", hash)));
+        }
+        let hash = hash.to_string();
+        let path = self.cache_dir.join(format!("{}.ron", hash));
+        if path.exists() {
+            return self.report(Transfer::Downloaded { hash });
+        }
+        let url = match self.map_url(&hash) {
+            Ok(url) => url,
+            Err(e) => return self.report(Transfer::Failed(e)),
+        };
+        let cache_dir = self.cache_dir.clone();
+        log::info!(target: logging::NET, "Downloading map {} from {}", hash, url);
+        self.run(move || {
+            let source = ureq::get(&url).call()?.into_string()?;
+            if level::hash(&source) != hash {
+                return Err("Downloaded map doesn't match its hash".into());
+            }
+            ron::de::from_str::<Level>(&source)?;
+            fs::create_dir_all(&cache_dir)?;
+            fs::write(path, source)?;
+            Ok(Transfer::Downloaded { hash })
+        });
+    }
+
+    /// A finished transfer, if there is one.
+    pub fn poll(&self) -> Option<Transfer> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_map_hashes_are_downloaded() {
+        let hash = level::hash("()");
+        assert!(is_map_hash(&hash));
+        assert!(!is_map_hash(&hash.to_uppercase()));
+        assert!(!is_map_hash(&hash[1..]));
+        assert!(!is_map_hash(&format!("{}0", hash)));
+        assert!(!is_map_hash(&format!("../{}", &hash[3..])));
+        assert!(!is_map_hash(""));
+
+        let (sender, receiver) = mpsc::channel();
+        let workshop = Workshop {
+            repo: None,
+            cache_dir: PathBuf::from("maps"),
+            sender,
+            receiver,
+        };
+        workshop.download("../../settings");
+        assert!(matches!(workshop.poll(), Some(Transfer::Failed(_))));
+    }
+}
@@ -0,0 +1,96 @@
+//! Time a tick of a synthetic match: an empty arena full of blobs swinging
+//! on their ropes, hooks in flight and projectiles everywhere.
+//!
+//! Run with `cargo bench -p blobs-core`. Add `--features simd` to compare
+//! the SSE loops, or `--features rapier` to step world_step with rapier2d.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use nalgebra::{Point2, Vector2};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use blobs_core::attachment::{Anchor, Attachment};
+use blobs_core::entity::Entity;
+use blobs_core::level::{HostileEntry, Level};
+use blobs_core::physics::{self, Bodies};
+use blobs_core::projectile::Projectile;
+use blobs_core::world::{Rules, World};
+use blobs_core::{Blob, HookState, SCREEN_SIZE};
+
+const BLOB_COUNTS: [usize; 4] = [100, 250, 500, 1000];
+// Same world every run, so the numbers can be compared.
+const SEED: u64 = 328;
+
+const RULES: Rules = Rules {
+    hostile_entry: HostileEntry::Allow,
+    allows_damage: true,
+};
+
+/// A world with the given number of blobs spread over the arena. Most are
+/// hanging from a rope, some have their hook in flight, and every blob has
+/// just fired a projectile.
+fn synthetic_world(blobs: usize) -> World {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut world = World::new(Level::parse("()").expect("empty level"));
+    for id in 0..blobs {
+        let center = Point2::new(
+            rng.gen_range(50.0, SCREEN_SIZE.0 - 50.0),
+            rng.gen_range(50.0, SCREEN_SIZE.1 - 50.0),
+        );
+        let mut blob = Blob::new(center, None);
+        let angle = rng.gen_range(0.0, std::f32::consts::TAU);
+        blob.aim_vec = Vector2::new(angle.cos(), angle.sin());
+        blob.vel = rng.gen_range(0.0, 30.0) * blob.aim_vec;
+        match id % 4 {
+            0 => blob.fire_hook(),
+            1 | 2 => {
+                let hook_point = Point2::new(rng.gen_range(0.0, SCREEN_SIZE.0), 0.0);
+                blob.hook = HookState::Attached(Attachment::rope(Anchor::Wall, hook_point));
+            }
+            _ => {}
+        }
+        world
+            .entities
+            .push(Entity::Projectile(Projectile::fire(id, &blob)));
+        world.blobs.insert(id, blob);
+    }
+    world
+}
+
+/// The integrator and the wall bounces alone.
+fn physics_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("physics_step");
+    for &blobs in &BLOB_COUNTS {
+        group.throughput(Throughput::Elements(blobs as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(blobs), &blobs, |b, &blobs| {
+            let mut world = synthetic_world(blobs);
+            let mut bodies = Bodies::new();
+            b.iter(|| physics::step(&mut world.blobs, &mut bodies));
+        });
+    }
+    group.finish();
+}
+
+/// A whole tick: physics, hooks, projectiles, pickups and damage. Each
+/// iteration starts from a fresh world, since projectiles run out and blobs
+/// die.
+fn world_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("world_step");
+    for &blobs in &BLOB_COUNTS {
+        group.throughput(Throughput::Elements(blobs as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(blobs), &blobs, |b, &blobs| {
+            b.iter_batched(
+                || synthetic_world(blobs),
+                |mut world| {
+                    world.step(RULES);
+                    world
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, physics_step, world_step);
+criterion_main!(benches);
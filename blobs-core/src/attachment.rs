@@ -0,0 +1,55 @@
+//! Springy links from a blob to something else: the rope to where its hook
+//! caught, and tow cables to other blobs or the escort payload. Game modes
+//! move the far end along with what it holds on to.
+
+use nalgebra::{Point2, Vector2};
+use serde::{Deserialize, Serialize};
+
+use crate::tuning;
+
+/// What the far end of an attachment holds on to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Anchor {
+    /// A wall the hook caught on. The end stays put.
+    Wall,
+    /// Another blob, by id.
+    Blob(usize),
+    /// The escort payload.
+    Payload,
+}
+
+/// A link from a blob to a point, pulling the blob when stretched past the
+/// rope's rest length. The pull levels off at the spring constant as the
+/// link gets longer. Both come from the tuning of the moment, so tuning
+/// changes apply to links that are already attached.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attachment {
+    pub anchor: Anchor,
+    /// Where the far end is now.
+    pub point: Point2<f32>,
+}
+
+impl Attachment {
+    /// The hook's rope.
+    pub fn rope(anchor: Anchor, point: Point2<f32>) -> Attachment {
+        Attachment { anchor, point }
+    }
+
+    /// Acceleration of a blob at center from the link.
+    pub fn acceleration(&self, center: Point2<f32>) -> Vector2<f32> {
+        let tuning = tuning::current();
+        let spring_vec = self.point - center;
+        let length = spring_vec.norm();
+        if length < tuning.spring_eq_len {
+            return Vector2::zeros();
+        }
+        (length - tuning.spring_eq_len) / length / length * tuning.spring_const * spring_vec
+    }
+
+    /// Acceleration of what the far end holds on to, if it weighs as much as
+    /// a blob, from the blob at center. Scale it by the mass ratio for
+    /// anything heavier or lighter.
+    pub fn reaction(&self, center: Point2<f32>) -> Vector2<f32> {
+        -self.acceleration(center)
+    }
+}
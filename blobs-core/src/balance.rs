@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+
+/// The projectile weapon.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Weapon {
+    pub speed: f32,
+    pub radius: f32,
+    /// Seconds until a projectile that hit nothing disappears.
+    pub lifetime: f32,
+    /// Velocity change of a blob hit by a projectile.
+    pub knockback: f32,
+    pub damage: f32,
+}
+
+impl Default for Weapon {
+    fn default() -> Weapon {
+        Weapon {
+            speed: 600.0,
+            radius: 5.0,
+            lifetime: 10.0,
+            knockback: 30.0,
+            damage: 20.0,
+        }
+    }
+}
+
+/// Stats of the blobs. There is only one kind of blob so far.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlobClass {
+    /// Wall hits faster than this hurt the blob.
+    pub impact_damage_speed: f32,
+    pub impact_damage_per_speed: f32,
+    pub hostile_base_damage_per_second: f32,
+    /// Seconds from death to respawn.
+    pub respawn_delay: f32,
+    /// Seconds a respawned blob can't be hurt.
+    pub spawn_invulnerability: f32,
+    /// Seconds a blob can hang on to a wall.
+    pub perch_stamina: f32,
+    /// Seconds of stamina regained per second off the walls.
+    pub stamina_regen: f32,
+    /// Speed a blob kicks off a wall with when it lets go.
+    pub perch_kick_speed: f32,
+}
+
+impl Default for BlobClass {
+    fn default() -> BlobClass {
+        BlobClass {
+            impact_damage_speed: 150.0,
+            impact_damage_per_speed: 0.2,
+            hostile_base_damage_per_second: 25.0,
+            respawn_delay: 3.0,
+            spawn_invulnerability: 2.0,
+            perch_stamina: 3.0,
+            stamina_regen: 1.0,
+            perch_kick_speed: 80.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pickups {
+    /// Seconds a picked up effect lasts.
+    pub effect_time: f32,
+    pub health_amount: f32,
+    /// Rope pull multiplier while the speed boost is active.
+    pub speed_boost_factor: f32,
+}
+
+impl Default for Pickups {
+    fn default() -> Pickups {
+        Pickups {
+            effect_time: 10.0,
+            health_amount: 50.0,
+            speed_boost_factor: 1.5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Escort {
+    /// Blobs within this distance of the payload push or contest it.
+    pub push_radius: f32,
+    pub push_speed: f32,
+    pub push_acc: f32,
+    pub payload_friction: f32,
+    /// Blob mass divided by payload mass, scales the pull from towing blobs.
+    pub tow_mass_ratio: f32,
+    /// Seconds of overtime the attackers get after leaving the payload.
+    pub overtime_grace: f32,
+}
+
+impl Default for Escort {
+    fn default() -> Escort {
+        Escort {
+            push_radius: 150.0,
+            push_speed: 20.0,
+            push_acc: 5.0,
+            payload_friction: 0.5,
+            tow_mass_ratio: 0.25,
+            overtime_grace: 3.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Duel {
+    /// Seconds the blobs can't be hurt at the start of each round.
+    pub round_start_invulnerability: f32,
+}
+
+impl Default for Duel {
+    fn default() -> Duel {
+        Duel {
+            round_start_invulnerability: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookDuel {
+    /// Slowest a pulled blob can enter a zone and still count as slammed.
+    pub slam_speed: f32,
+    /// Seconds a slam still counts after the hook lets go of the blob.
+    pub credit_time: f32,
+    /// Pull on a hooked blob, relative to the pull on the blob hooking it.
+    pub tow_mass_ratio: f32,
+}
+
+impl Default for HookDuel {
+    fn default() -> HookDuel {
+        HookDuel {
+            slam_speed: 60.0,
+            credit_time: 1.5,
+            tow_mass_ratio: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HideAndSeek {
+    /// Furthest a seeker's hook flies before it comes back.
+    pub seeker_hook_range: f32,
+    /// Hiders slower than this stick to walls they touch.
+    pub latch_speed: f32,
+    /// Seconds for a latched hider to fade into the wall completely.
+    pub camouflage_time: f32,
+}
+
+impl Default for HideAndSeek {
+    fn default() -> HideAndSeek {
+        HideAndSeek {
+            seeker_hook_range: 300.0,
+            latch_speed: 30.0,
+            camouflage_time: 2.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Suicide {
+    /// Seconds added to the respawn delay of blobs that kill themselves.
+    pub respawn_penalty: f32,
+    /// Frags taken off blobs that kill themselves in deathmatch.
+    pub frag_penalty: u32,
+}
+
+impl Default for Suicide {
+    fn default() -> Suicide {
+        Suicide {
+            respawn_penalty: 3.0,
+            frag_penalty: 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Afk {
+    /// Seconds without input before a player is moved to the spectators.
+    pub idle_time: f32,
+    /// Seconds before that to warn them.
+    pub warning_time: f32,
+}
+
+impl Default for Afk {
+    fn default() -> Afk {
+        Afk {
+            idle_time: 60.0,
+            warning_time: 10.0,
+        }
+    }
+}
+
+/// Numbers to tweak the game balance with, read from a data file so they can
+/// be changed without recompiling. Missing fields keep their defaults.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Balance {
+    pub weapon: Weapon,
+    pub blob: BlobClass,
+    pub pickups: Pickups,
+    pub escort: Escort,
+    pub duel: Duel,
+    pub hook_duel: HookDuel,
+    pub hide_and_seek: HideAndSeek,
+    pub suicide: Suicide,
+    pub afk: Afk,
+}
+
+impl Balance {
+    /// Describe every value that would break the game.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut positive = |name: &str, value: f32| {
+            if value.is_nan() || value <= 0.0 {
+                errors.push(format!("{} must be positive, not {}", name, value));
+            }
+        };
+        positive("weapon.speed", self.weapon.speed);
+        positive("weapon.radius", self.weapon.radius);
+        positive("weapon.lifetime", self.weapon.lifetime);
+        positive("blob.impact_damage_speed", self.blob.impact_damage_speed);
+        positive("blob.perch_stamina", self.blob.perch_stamina);
+        positive("pickups.effect_time", self.pickups.effect_time);
+        positive(
+            "pickups.speed_boost_factor",
+            self.pickups.speed_boost_factor,
+        );
+        positive("escort.push_radius", self.escort.push_radius);
+        positive("escort.push_speed", self.escort.push_speed);
+        positive("hook_duel.slam_speed", self.hook_duel.slam_speed);
+        positive("afk.idle_time", self.afk.idle_time);
+        positive(
+            "hide_and_seek.seeker_hook_range",
+            self.hide_and_seek.seeker_hook_range,
+        );
+        positive(
+            "hide_and_seek.camouflage_time",
+            self.hide_and_seek.camouflage_time,
+        );
+        let mut not_negative = |name: &str, value: f32| {
+            if value.is_nan() || value < 0.0 {
+                errors.push(format!("{} can't be negative, not {}", name, value));
+            }
+        };
+        not_negative("weapon.knockback", self.weapon.knockback);
+        not_negative("weapon.damage", self.weapon.damage);
+        not_negative(
+            "blob.impact_damage_per_speed",
+            self.blob.impact_damage_per_speed,
+        );
+        not_negative(
+            "blob.hostile_base_damage_per_second",
+            self.blob.hostile_base_damage_per_second,
+        );
+        not_negative("blob.respawn_delay", self.blob.respawn_delay);
+        not_negative(
+            "blob.spawn_invulnerability",
+            self.blob.spawn_invulnerability,
+        );
+        not_negative("blob.stamina_regen", self.blob.stamina_regen);
+        not_negative("blob.perch_kick_speed", self.blob.perch_kick_speed);
+        not_negative("pickups.health_amount", self.pickups.health_amount);
+        not_negative("escort.push_acc", self.escort.push_acc);
+        not_negative("escort.payload_friction", self.escort.payload_friction);
+        not_negative("escort.tow_mass_ratio", self.escort.tow_mass_ratio);
+        not_negative("escort.overtime_grace", self.escort.overtime_grace);
+        not_negative(
+            "duel.round_start_invulnerability",
+            self.duel.round_start_invulnerability,
+        );
+        not_negative("hook_duel.credit_time", self.hook_duel.credit_time);
+        not_negative("hook_duel.tow_mass_ratio", self.hook_duel.tow_mass_ratio);
+        not_negative("hide_and_seek.latch_speed", self.hide_and_seek.latch_speed);
+        not_negative("suicide.respawn_penalty", self.suicide.respawn_penalty);
+        not_negative("afk.warning_time", self.afk.warning_time);
+        errors
+    }
+}
+
+thread_local! {
+    static CURRENT: Cell<Balance> = Cell::new(Balance::default());
+}
+
+pub fn current() -> Balance {
+    CURRENT.with(|current| current.get())
+}
+
+/// Make the balance current until the file is read again, for changing single
+/// values from the console.
+pub fn set_current(balance: Balance) {
+    CURRENT.with(|current| current.set(balance));
+}
@@ -0,0 +1,90 @@
+use nalgebra::{Point2, Vector2};
+use rand::Rng;
+
+use crate::tuning;
+use crate::{Blob, HookState, SCREEN_SIZE, TICK_TIME};
+
+// Seconds a bot hangs on to its hook, and lets go between swings.
+const HOLD_TIME: (f32, f32) = (1.0, 3.0);
+const FALL_TIME: (f32, f32) = (0.1, 0.6);
+// Bots this many blob radii from the floor hook again right away, so they
+// don't lie there.
+const FLOOR_MARGIN: f32 = 3.0;
+
+/// What a player does with their blob, besides aiming.
+#[derive(Clone, Copy, Debug)]
+pub enum Input {
+    /// Shoot the hook this way.
+    Hook(Vector2<f32>),
+    Release,
+}
+
+impl Input {
+    pub fn apply(self, blob: &mut Blob) {
+        match self {
+            Input::Hook(direction) => {
+                blob.aim_vec = direction;
+                blob.fire_hook();
+            }
+            Input::Release => blob.hook = HookState::None,
+        }
+    }
+}
+
+/// Plays a blob by swinging from wall to wall: it hooks somewhere above and
+/// ahead, hangs on for a while, lets go and hooks again. It doesn't fight or
+/// play the game modes, it only keeps moving.
+pub struct Bot {
+    // Seconds until the bot lets go or hooks again.
+    timer: f32,
+}
+
+impl Bot {
+    pub fn new(rng: &mut impl Rng) -> Bot {
+        Bot {
+            timer: between(FALL_TIME, rng),
+        }
+    }
+
+    /// Aim and hook for the blob, once per tick. Bots decide at random with
+    /// the given generator, so a seeded one makes them play the same again.
+    pub fn update(&mut self, blob: &mut Blob, rng: &mut impl Rng) {
+        // The hold time starts when the hook catches.
+        if !matches!(blob.hook, HookState::Traveling(..)) {
+            self.timer -= TICK_TIME;
+        }
+        let near_floor =
+            blob.center.y > SCREEN_SIZE.1 - FLOOR_MARGIN * tuning::current().blob_radius;
+        match blob.hook {
+            HookState::None if self.timer <= 0.0 || near_floor => {
+                Input::Hook((target(blob, rng) - blob.center).normalize()).apply(blob);
+                self.timer = between(HOLD_TIME, rng);
+            }
+            HookState::Attached(_) if self.timer <= 0.0 => {
+                Input::Release.apply(blob);
+                self.timer = between(FALL_TIME, rng);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A point on the ceiling ahead of the blob, so it keeps swinging the way it
+/// is going. Standing still, either way will do.
+fn target(blob: &Blob, rng: &mut impl Rng) -> Point2<f32> {
+    let direction = if blob.vel.x.abs() > 1.0 {
+        blob.vel.x.signum()
+    } else if rng.gen() {
+        1.0
+    } else {
+        -1.0
+    };
+    let ahead = between((0.1, 0.4), rng) * SCREEN_SIZE.0 * direction;
+    let x = (blob.center.x + ahead).clamp(0.0, SCREEN_SIZE.0);
+    // Off the top of the screen, so the hook always reaches the ceiling.
+    Point2::new(x, 0.0) - Vector2::y()
+}
+
+fn between((low, high): (f32, f32), rng: &mut impl Rng) -> f32 {
+    low + (high - low) * rng.gen::<f32>()
+}
@@ -0,0 +1,293 @@
+//! Where blobs, hooks and projectiles touch the walls around the level.
+
+use nalgebra::{Point2, Vector2};
+
+use crate::tuning;
+use crate::SCREEN_SIZE;
+
+/// Look for collision between blob and walls.
+/// Returns the point of collision and the normal vector,
+/// or None if no collision has occurred.
+pub fn wall_blob_collision(blob_center: Point2<f32>) -> Option<(Point2<f32>, Vector2<f32>)> {
+    wall_blob_collisions(blob_center).next()
+}
+
+/// Every wall the blob overlaps, which is two in a corner. Side walls come
+/// before the ceiling and floor.
+pub fn wall_blob_collisions(
+    blob_center: Point2<f32>,
+) -> impl Iterator<Item = (Point2<f32>, Vector2<f32>)> {
+    let x = blob_center.coords.x;
+    let y = blob_center.coords.y;
+    let radius = tuning::current().blob_radius;
+    let walls = [
+        (x < radius, Point2::new(0.0, y), Vector2::x()),
+        (
+            x > SCREEN_SIZE.0 - radius,
+            Point2::new(SCREEN_SIZE.0, y),
+            -Vector2::x(),
+        ),
+        (y < radius, Point2::new(x, 0.0), Vector2::y()),
+        (
+            y > SCREEN_SIZE.1 - radius,
+            Point2::new(x, SCREEN_SIZE.1),
+            -Vector2::y(),
+        ),
+    ];
+    IntoIterator::into_iter(walls)
+        .filter(|&(hit, _, _)| hit)
+        .map(|(_, point, normal)| (point, normal))
+}
+
+/// Look for collision between point p and walls.
+/// Returns the point of collision if any, otherwise returns None. The point
+/// is on the wall, even when p is past a corner.
+pub fn wall_point_collision(p: Point2<f32>) -> Option<Point2<f32>> {
+    let x = p.coords.x;
+    let y = p.coords.y;
+    let on_wall = |x: f32, y: f32| {
+        Some(Point2::new(
+            x.clamp(0.0, SCREEN_SIZE.0),
+            y.clamp(0.0, SCREEN_SIZE.1),
+        ))
+    };
+    if x < 0.0 {
+        on_wall(0.0, y)
+    } else if x > SCREEN_SIZE.0 {
+        on_wall(SCREEN_SIZE.0, y)
+    } else if y < 0.0 {
+        on_wall(x, 0.0)
+    } else if y > SCREEN_SIZE.1 {
+        on_wall(x, SCREEN_SIZE.1)
+    } else {
+        None
+    }
+}
+
+/// Look for collision between walls and something that moved from a to b
+/// this step, like a hook. Returns where it first crossed a wall, so fast
+/// hooks catch where they hit instead of where they ended up. Something
+/// that was already outside at a catches on the wall nearest a.
+pub fn wall_segment_collision(a: Point2<f32>, b: Point2<f32>) -> Option<Point2<f32>> {
+    // The level is convex, so a path between two points inside stays inside.
+    wall_point_collision(b)?;
+    if let Some(point) = wall_point_collision(a) {
+        return Some(point);
+    }
+    // How far along the path it crosses each wall it ends up past. The
+    // nearest crossing is the wall it hit.
+    let ab = b - a;
+    let crossings = [
+        (b.x < 0.0, -a.x / ab.x),
+        (b.x > SCREEN_SIZE.0, (SCREEN_SIZE.0 - a.x) / ab.x),
+        (b.y < 0.0, -a.y / ab.y),
+        (b.y > SCREEN_SIZE.1, (SCREEN_SIZE.1 - a.y) / ab.y),
+    ];
+    let (wall, t) = (0..crossings.len())
+        .filter(|&wall| crossings[wall].0)
+        .map(|wall| (wall, crossings[wall].1))
+        .min_by(|(_, s), (_, t)| s.total_cmp(t))?;
+    let p = a + t * ab;
+    let x = p.x.clamp(0.0, SCREEN_SIZE.0);
+    let y = p.y.clamp(0.0, SCREEN_SIZE.1);
+    // Right on the wall, which rounding may have missed.
+    Some(match wall {
+        0 => Point2::new(0.0, y),
+        1 => Point2::new(SCREEN_SIZE.0, y),
+        2 => Point2::new(x, 0.0),
+        _ => Point2::new(x, SCREEN_SIZE.1),
+    })
+}
+
+/// Mirror velocity in the plane defined by normal vector.
+pub fn reflect(vel: Vector2<f32>, normal: Vector2<f32>) -> Vector2<f32> {
+    vel - 2.0 * vel.dot(&normal) * normal
+}
+
+/// Shortest distance between point p and the line segment from a to b.
+pub fn segment_point_distance(a: Point2<f32>, b: Point2<f32>, p: Point2<f32>) -> f32 {
+    let ab = b - a;
+    let t = if ab.norm_squared() > 0.0 {
+        ((p - a).dot(&ab) / ab.norm_squared()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (a + t * ab - p).norm()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EPSILON: f32 = 1e-3;
+
+    fn radius() -> f32 {
+        tuning::current().blob_radius
+    }
+
+    /// Whether p is on one of the four walls.
+    fn on_wall(p: Point2<f32>) -> bool {
+        let inside_x = (0.0..=SCREEN_SIZE.0).contains(&p.x);
+        let inside_y = (0.0..=SCREEN_SIZE.1).contains(&p.y);
+        (inside_y && (p.x == 0.0 || p.x == SCREEN_SIZE.0))
+            || (inside_x && (p.y == 0.0 || p.y == SCREEN_SIZE.1))
+    }
+
+    #[test]
+    fn blob_clear_of_the_walls_touches_nothing() {
+        let middle = Point2::new(SCREEN_SIZE.0 / 2.0, SCREEN_SIZE.1 / 2.0);
+        assert!(wall_blob_collision(middle).is_none());
+        let just_clear = Point2::new(radius(), SCREEN_SIZE.1 / 2.0);
+        assert!(wall_blob_collision(just_clear).is_none());
+    }
+
+    #[test]
+    fn penetration_depth_is_how_far_the_blob_overlaps() {
+        let depth = 7.0;
+        let cases = [
+            Point2::new(radius() - depth, 500.0),
+            Point2::new(SCREEN_SIZE.0 - radius() + depth, 500.0),
+            Point2::new(500.0, radius() - depth),
+            Point2::new(500.0, SCREEN_SIZE.1 - radius() + depth),
+        ];
+        for &center in &cases {
+            let (point, normal) = wall_blob_collision(center).unwrap();
+            let penetration = radius() - (center - point).dot(&normal);
+            assert!(
+                (penetration - depth).abs() < EPSILON,
+                "{:?} overlaps by {} instead of {}",
+                center,
+                penetration,
+                depth
+            );
+            assert!(on_wall(point), "{:?} isn't on a wall", point);
+            assert!((normal.norm() - 1.0).abs() < EPSILON);
+        }
+    }
+
+    #[test]
+    fn normals_point_into_the_level() {
+        let (_, left) = wall_blob_collision(Point2::new(1.0, 500.0)).unwrap();
+        assert_eq!(left, Vector2::x());
+        let (_, floor) = wall_blob_collision(Point2::new(500.0, SCREEN_SIZE.1 - 1.0)).unwrap();
+        assert_eq!(floor, -Vector2::y());
+    }
+
+    #[test]
+    fn blob_in_a_corner_touches_both_walls() {
+        let corner = Point2::new(SCREEN_SIZE.0 - 1.0, SCREEN_SIZE.1 - 1.0);
+        let normals: Vec<_> = wall_blob_collisions(corner)
+            .map(|(_, normal)| normal)
+            .collect();
+        assert_eq!(normals, vec![-Vector2::x(), -Vector2::y()]);
+        assert_eq!(wall_blob_collision(corner).unwrap().1, -Vector2::x());
+    }
+
+    #[test]
+    fn hook_past_a_corner_catches_on_the_corner() {
+        let point = wall_point_collision(Point2::new(-5.0, -8.0)).unwrap();
+        assert_eq!(point, Point2::new(0.0, 0.0));
+        let point = wall_point_collision(Point2::new(SCREEN_SIZE.0 + 3.0, SCREEN_SIZE.1 + 1.0));
+        assert_eq!(point, Some(Point2::new(SCREEN_SIZE.0, SCREEN_SIZE.1)));
+    }
+
+    #[test]
+    fn fast_hook_catches_where_it_hit_the_wall() {
+        // Past the ceiling, a tick's move from the left wall.
+        let a = Point2::new(10.0, 20.0);
+        let b = Point2::new(-30.0, -60.0);
+        assert_eq!(wall_segment_collision(a, b), Some(Point2::new(0.0, 0.0)));
+        let b = Point2::new(50.0, -60.0);
+        assert_eq!(wall_segment_collision(a, b), Some(Point2::new(20.0, 0.0)));
+        assert_eq!(wall_point_collision(b), Some(Point2::new(50.0, 0.0)));
+    }
+
+    #[test]
+    fn projectile_path_distance() {
+        let a = Point2::new(0.0, 0.0);
+        let b = Point2::new(10.0, 0.0);
+        assert_eq!(segment_point_distance(a, b, Point2::new(5.0, 3.0)), 3.0);
+        // Past the end, the end is closest.
+        assert_eq!(segment_point_distance(a, b, Point2::new(13.0, 4.0)), 5.0);
+        // A projectile that didn't move.
+        assert_eq!(segment_point_distance(a, a, Point2::new(3.0, 4.0)), 5.0);
+    }
+
+    fn normals() -> impl Strategy<Value = Vector2<f32>> {
+        prop_oneof![
+            Just(Vector2::x()),
+            Just(-Vector2::x()),
+            Just(Vector2::y()),
+            Just(-Vector2::y()),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn reflection_keeps_the_speed(
+            x in -1000.0f32..1000.0,
+            y in -1000.0f32..1000.0,
+            normal in normals(),
+        ) {
+            let vel = Vector2::new(x, y);
+            let reflected = reflect(vel, normal);
+            prop_assert!((reflected.norm() - vel.norm()).abs() <= EPSILON * vel.norm().max(1.0));
+            // Only the part along the normal turns around.
+            prop_assert!((reflected.dot(&normal) + vel.dot(&normal)).abs() < EPSILON);
+        }
+
+        #[test]
+        fn hooks_catch_on_a_wall(
+            x in -200.0f32..SCREEN_SIZE.0 + 200.0,
+            y in -200.0f32..SCREEN_SIZE.1 + 200.0,
+        ) {
+            let p = Point2::new(x, y);
+            let inside = (0.0..=SCREEN_SIZE.0).contains(&x) && (0.0..=SCREEN_SIZE.1).contains(&y);
+            match wall_point_collision(p) {
+                Some(point) => {
+                    prop_assert!(!inside);
+                    prop_assert!(on_wall(point), "{:?} caught at {:?}", p, point);
+                }
+                None => prop_assert!(inside),
+            }
+        }
+
+        #[test]
+        fn hooks_catch_on_their_path(
+            ax in 0.0f32..SCREEN_SIZE.0,
+            ay in 0.0f32..SCREEN_SIZE.1,
+            bx in -200.0f32..SCREEN_SIZE.0 + 200.0,
+            by in -200.0f32..SCREEN_SIZE.1 + 200.0,
+        ) {
+            let (a, b) = (Point2::new(ax, ay), Point2::new(bx, by));
+            match wall_segment_collision(a, b) {
+                Some(point) => {
+                    prop_assert!(wall_point_collision(b).is_some());
+                    prop_assert!(on_wall(point), "{:?} to {:?} caught at {:?}", a, b, point);
+                    prop_assert!(segment_point_distance(a, b, point) < EPSILON);
+                }
+                None => prop_assert!(wall_point_collision(b).is_none()),
+            }
+        }
+
+        #[test]
+        fn closest_point_is_on_the_segment(
+            ax in -100.0f32..100.0,
+            ay in -100.0f32..100.0,
+            bx in -100.0f32..100.0,
+            by in -100.0f32..100.0,
+            t in 0.0f32..=1.0,
+            px in -100.0f32..100.0,
+            py in -100.0f32..100.0,
+        ) {
+            let (a, b, p) = (Point2::new(ax, ay), Point2::new(bx, by), Point2::new(px, py));
+            let distance = segment_point_distance(a, b, p);
+            // No point of the segment is closer, and the ends are no closer.
+            let on_segment = a + t * (b - a);
+            prop_assert!(distance <= (on_segment - p).norm() + EPSILON);
+            prop_assert!(distance <= (a - p).norm() + EPSILON);
+            prop_assert!(distance <= (b - p).norm() + EPSILON);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::tuning;
+use crate::{DT, SCREEN_SIZE, TICK_TIME};
+
+// Number of points around the outline.
+const POINTS: usize = 24;
+// Spring constants per unit of mass, to the center and to the neighbors.
+const RADIAL_STIFFNESS: f32 = 400.0;
+const NEIGHBOR_STIFFNESS: f32 = 200.0;
+const DAMPING: f32 = 8.0;
+// How much of the blob's acceleration the outline lags behind. All of it
+// would make bounces tear the outline apart.
+const INERTIA: f32 = 0.3;
+// The outline never gets further from or closer to the center than these
+// times the blob radius.
+const MAX_STRETCH: f32 = 1.4;
+const MIN_STRETCH: f32 = 0.5;
+
+/// The blob's outline as a ring of points, held by springs to the center and
+/// to their neighbors. The outline lags behind when the blob accelerates and
+/// flattens against walls, so the blob wobbles. It is for looks only:
+/// collisions always use the circle.
+#[derive(Clone, Debug)]
+pub struct SoftBody {
+    // Positions relative to the blob center, and their velocities in world
+    // units per second relative to the center.
+    offsets: [Vector2<f32>; POINTS],
+    rates: [Vector2<f32>; POINTS],
+    // Blob velocity in world units per second at the last update.
+    last_vel: Option<Vector2<f32>>,
+}
+
+impl Default for SoftBody {
+    fn default() -> SoftBody {
+        SoftBody::new()
+    }
+}
+
+impl SoftBody {
+    pub fn new() -> SoftBody {
+        let blob_radius = tuning::current().blob_radius;
+        let mut offsets = [Vector2::zeros(); POINTS];
+        for (i, offset) in offsets.iter_mut().enumerate() {
+            *offset = blob_radius * direction(i);
+        }
+        SoftBody {
+            offsets,
+            rates: [Vector2::zeros(); POINTS],
+            last_vel: None,
+        }
+    }
+
+    /// Call once per tick, after the blob has moved.
+    pub fn update(&mut self, center: Point2<f32>, vel: Vector2<f32>) {
+        // Blob velocities are in world units per DT, ticks are TICK_TIME.
+        let vel = vel * DT / TICK_TIME;
+        let acc = match self.last_vel {
+            Some(last_vel) => (vel - last_vel) / TICK_TIME,
+            None => Vector2::zeros(),
+        };
+        self.last_vel = Some(vel);
+
+        let blob_radius = tuning::current().blob_radius;
+        let rest_chord = 2.0 * blob_radius * (std::f32::consts::PI / POINTS as f32).sin();
+        let mut forces = [Vector2::zeros(); POINTS];
+        for (i, force) in forces.iter_mut().enumerate() {
+            let offset = self.offsets[i];
+            let radius = offset.norm();
+            if radius > 0.0 {
+                *force -= RADIAL_STIFFNESS * (radius - blob_radius) * offset / radius;
+            }
+            for j in [(i + 1) % POINTS, (i + POINTS - 1) % POINTS] {
+                let chord = self.offsets[j] - offset;
+                let length = chord.norm();
+                if length > 0.0 {
+                    *force += NEIGHBOR_STIFFNESS * (length - rest_chord) * chord / length;
+                }
+            }
+            *force -= DAMPING * self.rates[i] + INERTIA * acc;
+        }
+
+        for (i, force) in forces.iter().enumerate() {
+            self.rates[i] += force * TICK_TIME;
+            let mut offset = self.offsets[i] + self.rates[i] * TICK_TIME;
+            // Flatten against the walls.
+            let point = center + offset;
+            let inside = Point2::new(
+                point.x.clamp(0.0, SCREEN_SIZE.0),
+                point.y.clamp(0.0, SCREEN_SIZE.1),
+            );
+            if inside != point {
+                offset = inside - center;
+                self.rates[i] = Vector2::zeros();
+            }
+            let radius = offset.norm();
+            if radius > MAX_STRETCH * blob_radius {
+                offset *= MAX_STRETCH * blob_radius / radius;
+            } else if radius < MIN_STRETCH * blob_radius && radius > 0.0 {
+                offset *= MIN_STRETCH * blob_radius / radius;
+            }
+            self.offsets[i] = offset;
+        }
+    }
+
+    /// The outline around the given center, for drawing.
+    pub fn outline(&self, center: Point2<f32>) -> Vec<Point2<f32>> {
+        self.offsets.iter().map(|offset| center + offset).collect()
+    }
+}
+
+/// Direction from the center to the point at index i at rest.
+fn direction(i: usize) -> Vector2<f32> {
+    let angle = i as f32 * std::f32::consts::TAU / POINTS as f32;
+    Vector2::new(angle.cos(), angle.sin())
+}
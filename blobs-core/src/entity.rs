@@ -0,0 +1,26 @@
+use std::collections::BTreeMap;
+
+use crate::grid::Grid;
+use crate::health::Damage;
+use crate::projectile::Projectile;
+use crate::Blob;
+
+/// Short-lived things in the world besides the blobs themselves.
+pub enum Entity {
+    Projectile(Projectile),
+}
+
+impl Entity {
+    /// Advance the entity one step, with the grid holding where the blobs
+    /// are now. Returns false once the entity is done and should be removed.
+    pub fn update(
+        &mut self,
+        blobs: &mut BTreeMap<usize, Blob>,
+        grid: &Grid,
+        damage: &mut Vec<Damage>,
+    ) -> bool {
+        match self {
+            Entity::Projectile(projectile) => projectile.update(blobs, grid, damage),
+        }
+    }
+}
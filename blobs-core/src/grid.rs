@@ -0,0 +1,235 @@
+//! Uniform grid over the level, rebuilt each tick, for finding the blobs
+//! near a point or a path without checking every blob.
+
+use nalgebra::Point2;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use crate::tuning;
+use crate::{Blob, SCREEN_SIZE};
+
+/// Blob ids and centers sorted by the cell the center is in. Cells are a
+/// blob wide, so most queries look at a handful of cells. Blobs outside the
+/// level go in the nearest edge cell, so they are still found.
+#[derive(Default)]
+pub struct Grid {
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    // The blobs of each cell follow each other in id order, cells row by
+    // row, so the cells of a row that a query looks at are one slice.
+    entries: Vec<(usize, Point2<f32>)>,
+    // Where each cell's blobs start in entries, with one more at the end.
+    starts: Vec<usize>,
+    // Cell of each blob, in the order of the blob map.
+    blob_cells: Vec<usize>,
+}
+
+impl Grid {
+    pub fn new() -> Grid {
+        Grid::default()
+    }
+
+    /// Put every blob in the cell its center is in.
+    pub fn rebuild(&mut self, blobs: &BTreeMap<usize, Blob>) {
+        let cell_size = 2.0 * tuning::current().blob_radius;
+        if cell_size != self.cell_size {
+            self.cell_size = cell_size;
+            self.columns = (SCREEN_SIZE.0 / cell_size).ceil() as usize;
+            self.rows = (SCREEN_SIZE.1 / cell_size).ceil() as usize;
+        }
+        // Count the blobs in each cell, then make room for them.
+        self.starts.clear();
+        self.starts.resize(self.columns * self.rows + 1, 0);
+        self.blob_cells.clear();
+        for blob in blobs.values() {
+            let (column, row) = self.cell(blob.center);
+            let cell = row * self.columns + column;
+            self.blob_cells.push(cell);
+            self.starts[cell + 1] += 1;
+        }
+        for i in 1..self.starts.len() {
+            self.starts[i] += self.starts[i - 1];
+        }
+        // Fill each cell from its start, using the start of the next cell as
+        // the place to put the next blob, then shift them back.
+        self.entries.clear();
+        self.entries.resize(blobs.len(), (0, Point2::origin()));
+        for ((&id, blob), &cell) in blobs.iter().zip(&self.blob_cells) {
+            self.entries[self.starts[cell]] = (id, blob.center);
+            self.starts[cell] += 1;
+        }
+        for i in (1..self.starts.len()).rev() {
+            self.starts[i] = self.starts[i - 1];
+        }
+        self.starts[0] = 0;
+    }
+
+    /// Ids and centers of the blobs whose center may be within radius of p.
+    /// Blobs further away may be included too, so the caller does the exact
+    /// check.
+    pub fn near(
+        &self,
+        p: Point2<f32>,
+        radius: f32,
+    ) -> impl Iterator<Item = (usize, Point2<f32>)> + '_ {
+        self.near_segment(p, p, radius)
+    }
+
+    /// Ids and centers of the blobs whose center may be within radius of the
+    /// line segment from a to b, for things that moved from a to b this step.
+    pub fn near_segment(
+        &self,
+        a: Point2<f32>,
+        b: Point2<f32>,
+        radius: f32,
+    ) -> impl Iterator<Item = (usize, Point2<f32>)> + '_ {
+        let (columns, rows) = self.area(a, b, radius);
+        rows.flat_map(move |row| {
+            let cells = row * self.columns;
+            let start = self.starts[cells + columns.start];
+            let end = self.starts[cells + columns.end];
+            self.entries[start..end].iter().copied()
+        })
+    }
+
+    /// The lowest id of the blobs near p that hits says are there, like
+    /// near with the exact check given.
+    pub fn lowest_near(
+        &self,
+        p: Point2<f32>,
+        radius: f32,
+        hits: impl FnMut(usize, Point2<f32>) -> bool,
+    ) -> Option<usize> {
+        self.lowest_near_segment(p, p, radius, hits)
+    }
+
+    /// The lowest id of the blobs near the line segment from a to b that
+    /// hits says are there. Blobs in a cell are in id order, so a cell is
+    /// only looked at up to its first hit, which in a crowd is soon.
+    pub fn lowest_near_segment(
+        &self,
+        a: Point2<f32>,
+        b: Point2<f32>,
+        radius: f32,
+        mut hits: impl FnMut(usize, Point2<f32>) -> bool,
+    ) -> Option<usize> {
+        let (columns, rows) = self.area(a, b, radius);
+        let mut lowest: Option<usize> = None;
+        for row in rows {
+            for cell in row * self.columns + columns.start..row * self.columns + columns.end {
+                for &(id, center) in &self.entries[self.starts[cell]..self.starts[cell + 1]] {
+                    if lowest.is_some_and(|lowest| id > lowest) {
+                        break;
+                    }
+                    if hits(id, center) {
+                        lowest = Some(id);
+                        break;
+                    }
+                }
+            }
+        }
+        lowest
+    }
+
+    /// Columns and rows of the cells that may hold blobs within radius of
+    /// the line segment from a to b.
+    fn area(&self, a: Point2<f32>, b: Point2<f32>, radius: f32) -> (Range<usize>, Range<usize>) {
+        let low = Point2::new(a.x.min(b.x) - radius, a.y.min(b.y) - radius);
+        let high = Point2::new(a.x.max(b.x) + radius, a.y.max(b.y) + radius);
+        let (first_column, first_row) = self.cell(low);
+        let (last_column, last_row) = self.cell(high);
+        // No cells at all before the first rebuild.
+        if self.starts.is_empty() {
+            return (0..0, 0..0);
+        }
+        (first_column..last_column + 1, first_row..last_row + 1)
+    }
+
+    /// Column and row of the cell p is in, clamped to the grid.
+    fn cell(&self, p: Point2<f32>) -> (usize, usize) {
+        let index = |x: f32, count: usize| {
+            // NaN ends up in the first cell.
+            ((x / self.cell_size).floor().max(0.0) as usize).min(count.saturating_sub(1))
+        };
+        (index(p.x, self.columns), index(p.y, self.rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collision::segment_point_distance;
+    use proptest::prelude::*;
+
+    fn positions() -> impl Strategy<Value = Vec<(f32, f32)>> {
+        // Some blobs a bit outside the level, as after tunneling.
+        prop::collection::vec((-100.0f32..1100.0, -100.0f32..1100.0), 0..60)
+    }
+
+    proptest! {
+        #[test]
+        fn finds_every_blob_on_the_path(
+            positions in positions(),
+            a in (-100.0f32..1100.0, -100.0f32..1100.0),
+            b in (-100.0f32..1100.0, -100.0f32..1100.0),
+            radius in 0.0f32..200.0,
+        ) {
+            let blobs: BTreeMap<usize, Blob> = positions
+                .iter()
+                .map(|&(x, y)| Blob::new(Point2::new(x, y), None))
+                .enumerate()
+                .collect();
+            let mut grid = Grid::new();
+            grid.rebuild(&blobs);
+            let (a, b) = (Point2::new(a.0, a.1), Point2::new(b.0, b.1));
+            let near: Vec<usize> = grid.near_segment(a, b, radius).map(|(id, _)| id).collect();
+            for (id, blob) in &blobs {
+                if segment_point_distance(a, b, blob.center) < radius {
+                    prop_assert!(near.contains(id), "missed blob {} at {:?}", id, blob.center);
+                }
+            }
+            // Each blob is in one cell.
+            let mut unique = near.clone();
+            unique.sort_unstable();
+            unique.dedup();
+            prop_assert_eq!(unique.len(), near.len());
+        }
+
+        #[test]
+        fn lowest_is_the_first_hit_by_id(
+            positions in positions(),
+            a in (-100.0f32..1100.0, -100.0f32..1100.0),
+            b in (-100.0f32..1100.0, -100.0f32..1100.0),
+            radius in 0.0f32..200.0,
+        ) {
+            let blobs: BTreeMap<usize, Blob> = positions
+                .iter()
+                .map(|&(x, y)| Blob::new(Point2::new(x, y), None))
+                .enumerate()
+                .collect();
+            let mut grid = Grid::new();
+            grid.rebuild(&blobs);
+            let (a, b) = (Point2::new(a.0, a.1), Point2::new(b.0, b.1));
+            // Some blobs that are in the way don't count, like a shooter's own.
+            let hits = |id: usize, center| {
+                !id.is_multiple_of(3) && segment_point_distance(a, b, center) < radius
+            };
+            let lowest = blobs
+                .iter()
+                .find(|&(&id, blob)| hits(id, blob.center))
+                .map(|(&id, _)| id);
+            prop_assert_eq!(grid.lowest_near_segment(a, b, radius, hits), lowest);
+        }
+    }
+
+    #[test]
+    fn empty_before_the_first_rebuild() {
+        let grid = Grid::new();
+        assert_eq!(grid.near(Point2::new(500.0, 500.0), 100.0).count(), 0);
+        assert_eq!(
+            grid.lowest_near(Point2::new(500.0, 500.0), 100.0, |_, _| true),
+            None
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! The simulation as a reinforcement learning environment: an agent blob
+//! swings through a level among bots, choosing an action every tick and
+//! getting a reward for covering ground without getting hurt.
+//!
+//! A trained agent is a [`Policy`], which can play any blob in a world the
+//! way bots do, by observing it with [`Observation::of`] and applying the
+//! action it picks.
+
+use nalgebra::Vector2;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::bot::{Bot, Input};
+use crate::health::MAX_HEALTH;
+use crate::level::{HostileEntry, Level};
+use crate::world::{Rules, World};
+use crate::{Blob, HookState, SCREEN_SIZE};
+
+// Id of the agent's blob. The bots come after it.
+const AGENT: usize = 0;
+
+const RULES: Rules = Rules {
+    hostile_entry: HostileEntry::Allow,
+    allows_damage: true,
+};
+
+/// What the agent does in a tick.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Action {
+    /// Angle to aim at in radians, from the x axis towards the y axis,
+    /// which points down.
+    pub aim: f32,
+    /// Shoot the hook the way the blob aims.
+    pub fire: bool,
+    /// Let go of the hook. Firing in the same tick shoots it again.
+    pub release: bool,
+}
+
+impl Action {
+    pub fn apply(self, blob: &mut Blob) {
+        let aim = Vector2::new(self.aim.cos(), self.aim.sin());
+        blob.aim_vec = aim;
+        if self.release {
+            Input::Release.apply(blob);
+        }
+        if self.fire {
+            Input::Hook(aim).apply(blob);
+        }
+    }
+}
+
+/// How a blob's hook is doing, in an observation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HookObservation {
+    None,
+    Traveling { point: (f32, f32) },
+    Attached { point: (f32, f32) },
+}
+
+/// A blob as the agent sees it. Lengths are in level widths, velocities in
+/// level widths per DT.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlobObservation {
+    pub center: (f32, f32),
+    pub vel: (f32, f32),
+    pub hook: HookObservation,
+}
+
+/// What the agent sees: its own blob and the others, in id order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Observation {
+    pub agent: BlobObservation,
+    pub others: Vec<BlobObservation>,
+}
+
+impl Observation {
+    /// The world as seen by the blob with the given id, which must be in it.
+    pub fn of(world: &World, id: usize) -> Observation {
+        Observation {
+            agent: BlobObservation::of(&world.blobs[&id]),
+            others: world
+                .blobs
+                .iter()
+                .filter(|&(&other, _)| other != id)
+                .map(|(_, blob)| BlobObservation::of(blob))
+                .collect(),
+        }
+    }
+
+    /// The observation as one list of numbers for a network: for the agent
+    /// and then each other blob, its center, velocity, which of none,
+    /// traveling or attached its hook is, one-hot, and where the hook is.
+    /// The length only changes with the number of blobs.
+    pub fn features(&self) -> Vec<f32> {
+        std::iter::once(&self.agent)
+            .chain(&self.others)
+            .flat_map(|blob| {
+                let (kind, (x, y)) = match blob.hook {
+                    HookObservation::None => ([1.0, 0.0, 0.0], blob.center),
+                    HookObservation::Traveling { point } => ([0.0, 1.0, 0.0], point),
+                    HookObservation::Attached { point } => ([0.0, 0.0, 1.0], point),
+                };
+                [
+                    blob.center.0,
+                    blob.center.1,
+                    blob.vel.0,
+                    blob.vel.1,
+                    kind[0],
+                    kind[1],
+                    kind[2],
+                    x,
+                    y,
+                ]
+            })
+            .collect()
+    }
+}
+
+impl BlobObservation {
+    fn of(blob: &Blob) -> BlobObservation {
+        let scaled = |v: Vector2<f32>| (v.x / SCREEN_SIZE.0, v.y / SCREEN_SIZE.0);
+        let hook = match blob.hook {
+            HookState::None => HookObservation::None,
+            HookState::Traveling(point, _) => HookObservation::Traveling {
+                point: scaled(point.coords),
+            },
+            HookState::Attached(attachment) => HookObservation::Attached {
+                point: scaled(attachment.point.coords),
+            },
+        };
+        BlobObservation {
+            center: scaled(blob.center.coords),
+            vel: scaled(blob.vel),
+            hook,
+        }
+    }
+}
+
+/// Plays a blob from what it observes.
+pub trait Policy {
+    fn act(&mut self, observation: &Observation) -> Action;
+}
+
+/// How each episode is set up.
+#[derive(Clone, Debug)]
+pub struct EnvConfig {
+    /// RON source of the level to play in.
+    pub level: String,
+    /// Swinging bots besides the agent.
+    pub bots: usize,
+    /// Ticks until the episode ends, if the agent doesn't die first.
+    pub episode_ticks: u32,
+    /// Seed for where blobs spawn and what the bots do, so episodes can be
+    /// played again.
+    pub seed: u64,
+}
+
+impl Default for EnvConfig {
+    fn default() -> EnvConfig {
+        EnvConfig {
+            level: "()".to_string(),
+            bots: 3,
+            episode_ticks: 60 * 60,
+            seed: 0,
+        }
+    }
+}
+
+/// The result of a step.
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub observation: Observation,
+    /// Level widths the agent moved this tick, less the share of its full
+    /// health it lost.
+    pub reward: f32,
+    /// Whether the episode is over, because the agent died or the time ran
+    /// out. Call reset to start the next one.
+    pub done: bool,
+}
+
+pub struct Env {
+    config: EnvConfig,
+    world: World,
+    bots: Vec<(usize, Bot)>,
+    rng: StdRng,
+    tick: u32,
+}
+
+impl Env {
+    /// An environment ready for its first episode.
+    pub fn new(config: EnvConfig) -> Result<Env, ron::de::SpannedError> {
+        let level = Level::parse(&config.level)?;
+        let mut env = Env {
+            world: World::new(level),
+            bots: Vec::new(),
+            rng: StdRng::seed_from_u64(config.seed),
+            tick: 0,
+            config,
+        };
+        env.reset();
+        Ok(env)
+    }
+
+    /// Start a new episode, with every blob at a spawn point. Episodes
+    /// after the first play out differently, as the generator has moved on.
+    pub fn reset(&mut self) -> Observation {
+        self.world.reset();
+        self.world.blobs.clear();
+        self.bots.clear();
+        for id in AGENT..=self.config.bots {
+            let spawns: Vec<_> = self.world.level.spawn_points(None).collect();
+            let center = match spawns.len() {
+                0 => self.world.level.spawn_point(None),
+                n => spawns[self.rng.gen_range(0, n)],
+            };
+            self.world.blobs.insert(id, Blob::new(center, None));
+            if id != AGENT {
+                self.bots.push((id, Bot::new(&mut self.rng)));
+            }
+        }
+        self.tick = 0;
+        self.observe()
+    }
+
+    /// Apply the agent's action, let the bots play and step the world.
+    pub fn step(&mut self, action: Action) -> Step {
+        let agent = &self.world.blobs[&AGENT];
+        let (start, health) = (agent.center, agent.health);
+        action.apply(self.world.blobs.get_mut(&AGENT).unwrap());
+        for (id, bot) in &mut self.bots {
+            if let Some(blob) = self.world.blobs.get_mut(id) {
+                bot.update(blob, &mut self.rng);
+            }
+        }
+        self.world.step(RULES);
+        self.tick += 1;
+        // Bots that died come back right away.
+        let spawn = self.world.level.spawn_point(None);
+        for (id, _) in &self.bots {
+            if let Some(blob) = self.world.blobs.get_mut(id) {
+                if blob.health <= 0.0 {
+                    blob.respawn(spawn);
+                }
+            }
+        }
+        let agent = &self.world.blobs[&AGENT];
+        let moved = (agent.center - start).norm() / SCREEN_SIZE.0;
+        let hurt = (health - agent.health.max(0.0)) / MAX_HEALTH;
+        Step {
+            observation: self.observe(),
+            reward: moved - hurt,
+            done: agent.health <= 0.0 || self.tick >= self.config.episode_ticks,
+        }
+    }
+
+    pub fn observe(&self) -> Observation {
+        Observation::of(&self.world, AGENT)
+    }
+
+    /// The world the episode is played in, for showing it or looking closer
+    /// than an observation does.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn episode_ends_when_the_time_is_up() {
+        let config = EnvConfig {
+            episode_ticks: 100,
+            ..EnvConfig::default()
+        };
+        let bots = config.bots;
+        let mut env = Env::new(config).unwrap();
+        let observation = env.reset();
+        assert_eq!(observation.others.len(), bots);
+        assert_eq!(observation.features().len(), 9 * (bots + 1));
+        let swing = Action {
+            aim: -std::f32::consts::FRAC_PI_4,
+            fire: true,
+            release: false,
+        };
+        let mut step = env.step(swing);
+        let mut ticks = 1;
+        while !step.done {
+            step = env.step(Action::default());
+            ticks += 1;
+        }
+        assert_eq!(ticks, 100);
+        assert!(step.reward.is_finite());
+    }
+}
@@ -0,0 +1,100 @@
+use nalgebra::Point2;
+use std::collections::BTreeMap;
+
+use crate::level::Level;
+use crate::pickup::PickupKind;
+use crate::Blob;
+
+pub const MAX_HEALTH: f32 = 100.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageSource {
+    /// Hit by a projectile fired by the given blob.
+    Projectile(usize),
+    HostileBase,
+    /// Hit a wall too fast.
+    Impact,
+    /// Touched a hazard in the level.
+    Hazard,
+    /// The player asked to die, with /kill. Nothing protects against it.
+    Suicide,
+}
+
+impl DamageSource {
+    /// The blob responsible for the damage, if any.
+    pub fn attacker(self) -> Option<usize> {
+        match self {
+            DamageSource::Projectile(owner) => Some(owner),
+            DamageSource::HostileBase
+            | DamageSource::Impact
+            | DamageSource::Hazard
+            | DamageSource::Suicide => None,
+        }
+    }
+}
+
+/// Damage to be dealt to a blob. Collected during a tick and applied at the
+/// end of it.
+pub struct Damage {
+    pub target: usize,
+    pub amount: f32,
+    pub source: DamageSource,
+}
+
+/// Things that happened during a tick, for UI, audio and scoring to react to.
+#[derive(Clone, Copy, Debug)]
+pub enum GameEvent {
+    Damaged {
+        target: usize,
+        amount: f32,
+        source: DamageSource,
+    },
+    Died {
+        target: usize,
+        killer: Option<usize>,
+        pos: Point2<f32>,
+        /// Killed itself with /kill.
+        suicide: bool,
+    },
+}
+
+/// Apply collected damage to the blobs. Blobs inside their own base,
+/// recently respawned or shielded are protected.
+pub fn apply_damage(
+    damage: &mut Vec<Damage>,
+    blobs: &mut BTreeMap<usize, Blob>,
+    level: &Level,
+    events: &mut Vec<GameEvent>,
+) {
+    for Damage {
+        target,
+        amount,
+        source,
+    } in damage.drain(..)
+    {
+        let blob = match blobs.get_mut(&target) {
+            Some(blob) if blob.health > 0.0 => blob,
+            _ => continue,
+        };
+        let protected = blob.invulnerable > 0.0
+            || blob.effects.is_active(PickupKind::Shield)
+            || level.is_spawn_protected(blob.team, blob.center);
+        if protected && source != DamageSource::Suicide {
+            continue;
+        }
+        blob.health -= amount;
+        events.push(GameEvent::Damaged {
+            target,
+            amount,
+            source,
+        });
+        if blob.health <= 0.0 {
+            events.push(GameEvent::Died {
+                target,
+                killer: source.attacker().filter(|&attacker| attacker != target),
+                pos: blob.center,
+                suicide: source == DamageSource::Suicide,
+            });
+        }
+    }
+}
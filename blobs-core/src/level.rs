@@ -0,0 +1,216 @@
+use nalgebra::{Point2, Vector2};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::mode::ModeConfig;
+use crate::objective::ObjectiveSpawn;
+use crate::pickup::PickupSpawn;
+use crate::team::Team;
+use crate::SCREEN_SIZE;
+
+/// Axis aligned rectangle in world coordinates.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Region {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Region {
+    pub fn contains(&self, p: Point2<f32>) -> bool {
+        p.x >= self.x && p.x <= self.x + self.w && p.y >= self.y && p.y <= self.y + self.h
+    }
+
+    /// Look for collision between a blob and the region, treating the region
+    /// as solid. Returns the point of collision on the region's edge and the
+    /// outward normal vector, or None if the blob doesn't touch the region.
+    pub fn blob_collision(
+        &self,
+        center: Point2<f32>,
+        radius: f32,
+    ) -> Option<(Point2<f32>, Vector2<f32>)> {
+        let left = center.x + radius - self.x;
+        let right = self.x + self.w - (center.x - radius);
+        let top = center.y + radius - self.y;
+        let bottom = self.y + self.h - (center.y - radius);
+        if left <= 0.0 || right <= 0.0 || top <= 0.0 || bottom <= 0.0 {
+            return None;
+        }
+
+        // Push out through the side with the least penetration.
+        let clamped_x = center.x.max(self.x).min(self.x + self.w);
+        let clamped_y = center.y.max(self.y).min(self.y + self.h);
+        let min = left.min(right).min(top).min(bottom);
+        if min == left {
+            Some((Point2::new(self.x, clamped_y), -Vector2::x()))
+        } else if min == right {
+            Some((Point2::new(self.x + self.w, clamped_y), Vector2::x()))
+        } else if min == top {
+            Some((Point2::new(clamped_x, self.y), -Vector2::y()))
+        } else {
+            Some((Point2::new(clamped_x, self.y + self.h), Vector2::y()))
+        }
+    }
+}
+
+/// What happens to blobs entering a base owned by another team.
+// No mode damages intruders yet.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostileEntry {
+    /// The base acts as a wall for enemy blobs.
+    Block,
+    /// Enemy blobs may enter but take damage while inside.
+    Damage,
+    /// Enemy blobs may enter freely.
+    Allow,
+}
+
+/// A team owned region where the team's blobs spawn. Blobs inside their own
+/// team's base are protected.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Base {
+    pub team: Team,
+    pub region: Region,
+    pub spawn: (f32, f32),
+}
+
+impl Base {
+    pub fn spawn_point(&self) -> Point2<f32> {
+        Point2::new(self.spawn.0, self.spawn.1)
+    }
+}
+
+/// What touching a hazard does to a blob.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum HazardEffect {
+    /// Damage per second while touching, like a lava floor.
+    Damage(f32),
+    /// Instant death, like spikes.
+    Kill,
+}
+
+/// A region of the level that hurts blobs touching it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Hazard {
+    pub region: Region,
+    pub effect: HazardEffect,
+}
+
+/// Extra place to spawn besides the bases. Spawn points without a team are
+/// used by every team.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpawnPoint {
+    pub pos: (f32, f32),
+    #[serde(default)]
+    pub team: Option<Team>,
+}
+
+/// An image repeated behind the level, given by the level.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BackgroundLayer {
+    /// Path of the image in the resource directory.
+    pub image: String,
+    /// How much the layer moves along with the world when the view moves,
+    /// from 0 for infinitely far away to 1 for on the level itself.
+    pub parallax: f32,
+    /// Drift in world units per second, e.g. for clouds.
+    #[serde(default)]
+    pub scroll: (f32, f32),
+    #[serde(default = "opaque")]
+    pub alpha: f32,
+}
+
+fn opaque() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Level {
+    #[serde(default)]
+    pub bases: Vec<Base>,
+    #[serde(default)]
+    pub spawns: Vec<SpawnPoint>,
+    #[serde(default)]
+    pub objectives: Vec<ObjectiveSpawn>,
+    #[serde(default)]
+    pub pickups: Vec<PickupSpawn>,
+    #[serde(default)]
+    pub hazards: Vec<Hazard>,
+    #[serde(default)]
+    pub mode: ModeConfig,
+    /// Images behind the level, furthest first. They replace the floor.
+    #[serde(default)]
+    pub backgrounds: Vec<BackgroundLayer>,
+    /// The RON source the level was loaded from, for sharing it.
+    #[serde(skip)]
+    pub source: String,
+    /// Hash of the source, identifying the exact version of the level.
+    #[serde(skip)]
+    pub hash: String,
+}
+
+/// Hex encoded SHA-256 hash of a level's source.
+pub fn hash(source: &str) -> String {
+    Sha256::digest(source.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+impl Level {
+    /// Read a level from its RON source.
+    pub fn parse(source: &str) -> Result<Level, ron::de::SpannedError> {
+        let level: Level = ron::de::from_str(source)?;
+        Ok(Level {
+            hash: hash(source),
+            source: source.to_string(),
+            ..level
+        })
+    }
+
+    pub fn team_base(&self, team: Team) -> Option<&Base> {
+        self.bases.iter().find(|base| base.team == team)
+    }
+
+    /// Default place for blobs of the given team to spawn. Blobs without any
+    /// spawn point spawn in the middle of the level.
+    pub fn spawn_point(&self, team: Option<Team>) -> Point2<f32> {
+        self.spawn_points(team)
+            .next()
+            .unwrap_or_else(|| Point2::new(SCREEN_SIZE.0 / 2.0, SCREEN_SIZE.1 / 2.0))
+    }
+
+    /// All points where blobs of the given team may spawn.
+    pub fn spawn_points(&self, team: Option<Team>) -> impl Iterator<Item = Point2<f32>> + '_ {
+        let base_spawns = team
+            .and_then(|team| self.team_base(team))
+            .map(|base| base.spawn_point());
+        let spawns = self
+            .spawns
+            .iter()
+            .filter(move |spawn| spawn.team.is_none() || spawn.team == team)
+            .map(|spawn| Point2::new(spawn.pos.0, spawn.pos.1));
+        base_spawns.into_iter().chain(spawns)
+    }
+
+    /// Bases that the given team is not allowed in. Blobs without a team
+    /// are hostile to all bases.
+    pub fn hostile_bases(&self, team: Option<Team>) -> impl Iterator<Item = &Base> {
+        self.bases
+            .iter()
+            .filter(move |base| Some(base.team) != team)
+    }
+
+    /// Blobs inside their own team's base are protected from harm.
+    pub fn is_spawn_protected(&self, team: Option<Team>, p: Point2<f32>) -> bool {
+        match team {
+            Some(team) => self
+                .bases
+                .iter()
+                .any(|base| base.team == team && base.region.contains(p)),
+            None => false,
+        }
+    }
+}
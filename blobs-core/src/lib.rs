@@ -0,0 +1,289 @@
+//! The blobs simulation: the blobs, their hooks and what they bump into,
+//! stepped a tick at a time without a window, so it can be tested and run
+//! without the game client.
+//!
+//! Other tools use it the way the game does: load a [`level::Level`], put
+//! it in a [`world::World`] along with some [`Blob`]s, steer the blobs with
+//! [`bot::Input`] and call [`world::World::step`] once per tick. Between
+//! steps the world can be looked at directly, asked which blobs are where
+//! with [`world::World::blobs_near`], or turned into a
+//! [`snapshot::Snapshot`] that serializes to RON and can be restored later.
+//!
+//! ```
+//! use blobs_core::bot::Input;
+//! use blobs_core::level::{HostileEntry, Level};
+//! use blobs_core::snapshot::Snapshot;
+//! use blobs_core::world::{Rules, World};
+//! use blobs_core::Blob;
+//! use nalgebra::{Point2, Vector2};
+//!
+//! let mut world = World::new(Level::parse("()").unwrap());
+//! let mut blob = Blob::new(Point2::new(500.0, 500.0), None);
+//! Input::Hook(-Vector2::y()).apply(&mut blob);
+//! world.blobs.insert(0, blob);
+//! let rules = Rules {
+//!     hostile_entry: HostileEntry::Allow,
+//!     allows_damage: true,
+//! };
+//! for _ in 0..60 {
+//!     world.step(rules);
+//! }
+//! assert_eq!(world.blobs_near(world.blobs[&0].center, 1.0).next(), Some(0));
+//! let saved = Snapshot::of(&world).to_ron().unwrap();
+//! Snapshot::from_ron(&saved).unwrap().restore(&mut world);
+//! ```
+
+use nalgebra::{Point2, Vector2};
+
+pub mod attachment;
+pub mod balance;
+pub mod bot;
+pub mod collision;
+pub mod deform;
+pub mod entity;
+pub mod grid;
+#[cfg(feature = "gym")]
+pub mod gym;
+pub mod health;
+pub mod level;
+pub mod mode;
+pub mod objective;
+pub mod physics;
+pub mod pickup;
+pub mod projectile;
+pub mod respawn;
+pub mod snapshot;
+pub mod stuck;
+pub mod team;
+pub mod trail;
+pub mod tuning;
+pub mod world;
+
+use attachment::{Anchor, Attachment};
+use collision::{wall_blob_collision, wall_segment_collision};
+use deform::SoftBody;
+use health::{Damage, DamageSource, MAX_HEALTH};
+use level::{HazardEffect, HostileEntry, Level};
+use pickup::{Effects, PickupKind};
+use team::Team;
+use trail::Trail;
+
+pub const DT: f32 = 0.1;
+pub const TICKS_PER_SECOND: u32 = 60;
+// Real time in seconds per simulation tick, used for game timers.
+pub const TICK_TIME: f32 = 1.0 / TICKS_PER_SECOND as f32;
+// How far a blob hanging on a wall is pressed into it, flattening its
+// outline.
+const PERCH_SQUASH: f32 = 6.0;
+// Blobs need this much of their stamina to grab on to a wall again.
+const MIN_PERCH_STAMINA: f32 = 0.25;
+pub const SCREEN_SIZE: (f32, f32) = (1000.0, 1000.0);
+
+/// Where a blob's hook is.
+#[derive(Debug)]
+pub enum HookState {
+    /// Caught on a wall or on something to tow, with the rope pulling.
+    Attached(Attachment),
+    Traveling(Point2<f32>, Vector2<f32>),
+    None,
+}
+
+/// Where a blob hangs on a wall.
+#[derive(Clone, Copy, Debug)]
+pub struct Perch {
+    pub center: Point2<f32>,
+    /// Points out of the wall.
+    pub normal: Vector2<f32>,
+}
+
+/// A player's blob, or a bot's.
+pub struct Blob {
+    pub center: Point2<f32>,
+    pub vel: Vector2<f32>,
+    pub aim_vec: Vector2<f32>,
+    pub hook: HookState,
+    pub team: Option<Team>,
+    pub health: f32,
+    /// Seconds left until the blob can be hurt again.
+    pub invulnerable: f32,
+    pub effects: Effects,
+    pub outline: SoftBody,
+    pub trail: Trail,
+    /// Whether the player holds the key to hang on to walls.
+    pub holding_perch: bool,
+    pub perch: Option<Perch>,
+    /// Seconds the blob can still hang on.
+    pub stamina: f32,
+}
+
+impl Blob {
+    pub fn new(center: Point2<f32>, team: Option<Team>) -> Blob {
+        Blob {
+            center,
+            vel: Vector2::zeros(),
+            aim_vec: Vector2::x(),
+            hook: HookState::None,
+            team,
+            health: MAX_HEALTH,
+            invulnerable: 0.0,
+            effects: Effects::new(),
+            outline: SoftBody::new(),
+            trail: Trail::default(),
+            holding_perch: false,
+            perch: None,
+            stamina: balance::current().blob.perch_stamina,
+        }
+    }
+
+    pub fn respawn(&mut self, center: Point2<f32>) {
+        *self = Blob::new(center, self.team);
+    }
+
+    /// Shoot the hook the way the blob is aiming.
+    pub fn fire_hook(&mut self) {
+        let mut speed = tuning::current().hook_traveling_speed;
+        if self.effects.is_active(PickupKind::DoubleHook) {
+            speed *= 2.0;
+        }
+        self.hook = HookState::Traveling(self.center + self.aim_vec, speed * self.aim_vec);
+    }
+
+    fn update_timers(&mut self) {
+        self.invulnerable = (self.invulnerable - TICK_TIME).max(0.0);
+        self.effects.update();
+    }
+
+    /// Acceleration from the rope, gravity and damping. The blobs are moved
+    /// with it by the physics backend.
+    fn acceleration(&self) -> Vector2<f32> {
+        let mut acc_spring = match self.hook {
+            HookState::Attached(attachment) => attachment.acceleration(self.center),
+            _ => Vector2::zeros(),
+        };
+        if self.effects.is_active(PickupKind::SpeedBoost) {
+            acc_spring *= balance::current().pickups.speed_boost_factor;
+        }
+        let tuning = tuning::current();
+        let acc_damping = -tuning.damping_const * self.vel;
+        let acc_gravity = tuning.gravity * Vector2::y();
+        acc_spring + acc_gravity + acc_damping
+    }
+
+    /// Hang on to a wall the blob touches while the perch key is held, for
+    /// as long as stamina lasts. Letting go of the key kicks off the wall the
+    /// way the blob is aiming, or away from the wall if it's aiming into it.
+    fn update_perch(&mut self) {
+        let class = balance::current().blob;
+        match self.perch {
+            Some(perch) if self.holding_perch && self.stamina > 0.0 => {
+                self.stamina = (self.stamina - TICK_TIME).max(0.0);
+                self.center = perch.center;
+                self.vel = Vector2::zeros();
+            }
+            Some(perch) => {
+                self.perch = None;
+                if !self.holding_perch {
+                    let into_wall = self.aim_vec.dot(&perch.normal).min(0.0);
+                    let direction = self.aim_vec - 2.0 * into_wall * perch.normal;
+                    self.vel = class.perch_kick_speed * direction;
+                }
+            }
+            None => {
+                self.stamina =
+                    (self.stamina + class.stamina_regen * TICK_TIME).min(class.perch_stamina);
+                if !self.holding_perch || self.stamina < MIN_PERCH_STAMINA * class.perch_stamina {
+                    return;
+                }
+                if let Some((point, normal)) = wall_blob_collision(self.center) {
+                    let perch = Perch {
+                        center: point + (tuning::current().blob_radius - PERCH_SQUASH) * normal,
+                        normal,
+                    };
+                    self.center = perch.center;
+                    self.vel = Vector2::zeros();
+                    self.perch = Some(perch);
+                }
+            }
+        }
+    }
+
+    /// Move the hook, after the blob has been moved. Returns where the hook
+    /// caught on a wall, if it did.
+    pub fn update(&mut self) -> Option<Point2<f32>> {
+        self.update_perch();
+        self.outline.update(self.center, self.vel);
+        self.trail.update(self.center);
+
+        // Update hook position
+        if let HookState::Traveling(from, hook_vel) = self.hook {
+            let hook_point = from + hook_vel * DT;
+            if let Some(collision_point) = wall_segment_collision(from, hook_point) {
+                self.hook = HookState::Attached(Attachment::rope(Anchor::Wall, collision_point));
+                return Some(collision_point);
+            }
+            self.hook = HookState::Traveling(hook_point, hook_vel);
+        }
+        None
+    }
+}
+
+/// Apply the base entry rule to a blob inside a base owned by another team.
+pub fn enforce_hostile_entry(
+    level: &Level,
+    rule: HostileEntry,
+    id: usize,
+    blob: &mut Blob,
+    damage: &mut Vec<Damage>,
+) {
+    match rule {
+        HostileEntry::Block => {
+            let radius = tuning::current().blob_radius;
+            for base in level.hostile_bases(blob.team) {
+                if let Some((collision_point, collision_normal)) =
+                    base.region.blob_collision(blob.center, radius)
+                {
+                    blob.center = collision_point + radius * collision_normal;
+                    let normal_vel = blob.vel.dot(&collision_normal);
+                    if normal_vel < 0.0 {
+                        blob.vel -= 2.0 * normal_vel * collision_normal;
+                    }
+                }
+            }
+        }
+        HostileEntry::Damage => {
+            if level
+                .hostile_bases(blob.team)
+                .any(|base| base.region.contains(blob.center))
+            {
+                damage.push(Damage {
+                    target: id,
+                    amount: balance::current().blob.hostile_base_damage_per_second * TICK_TIME,
+                    source: DamageSource::HostileBase,
+                });
+            }
+        }
+        HostileEntry::Allow => {}
+    }
+}
+
+/// Hurt a blob for every hazard it touches.
+pub fn touch_hazards(level: &Level, id: usize, blob: &Blob, damage: &mut Vec<Damage>) {
+    for hazard in &level.hazards {
+        if hazard
+            .region
+            .blob_collision(blob.center, tuning::current().blob_radius)
+            .is_none()
+        {
+            continue;
+        }
+        let amount = match hazard.effect {
+            HazardEffect::Damage(per_second) => per_second * TICK_TIME,
+            HazardEffect::Kill => MAX_HEALTH,
+        };
+        damage.push(Damage {
+            target: id,
+            amount,
+            source: DamageSource::Hazard,
+        });
+    }
+}
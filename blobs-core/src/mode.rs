@@ -0,0 +1,222 @@
+//! Which mode a level is played in and the mode's settings, as the level
+//! file gives them.
+
+use nalgebra::Point2;
+use serde::{Deserialize, Serialize};
+
+use crate::team::Team;
+
+/// Which mode a level is played in, and its settings.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ModeConfig {
+    Deathmatch {
+        frag_limit: u32,
+        /// Match length in seconds.
+        time_limit: f32,
+    },
+    Duel {
+        best_of: u32,
+    },
+    CaptureTheFlag {
+        capture_limit: u32,
+        /// Match length in seconds.
+        time_limit: f32,
+    },
+    Escort(EscortConfig),
+    KingOfTheHill(KingOfTheHillConfig),
+    HookDuel(HookDuelConfig),
+    Domination(DominationConfig),
+    HideAndSeek(HideAndSeekConfig),
+}
+
+impl Default for ModeConfig {
+    fn default() -> ModeConfig {
+        ModeConfig::Deathmatch {
+            frag_limit: 10,
+            time_limit: 300.0,
+        }
+    }
+}
+
+/// Escort mode setup, given by the level.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EscortConfig {
+    pub attackers: Team,
+    pub track: Vec<(f32, f32)>,
+    /// Positions along the track, as fractions of its length, that give the
+    /// attackers extra time when the payload reaches them.
+    #[serde(default)]
+    pub checkpoints: Vec<f32>,
+    /// Seconds the attackers start with.
+    pub time_limit: f32,
+    /// Seconds added for each reached checkpoint.
+    pub checkpoint_bonus: f32,
+}
+
+/// King-of-the-hill setup, given by the level.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KingOfTheHillConfig {
+    pub zone: Zone,
+    /// Points needed to win.
+    pub score_limit: f32,
+    pub points_per_second: f32,
+    /// Match length in seconds.
+    pub time_limit: f32,
+}
+
+/// Hook duel setup, given by the level.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HookDuelConfig {
+    /// Areas along the walls to slam hooked blobs into.
+    pub zones: Vec<Zone>,
+    /// Slams needed to win.
+    pub score_limit: u32,
+    /// Match length in seconds.
+    pub time_limit: f32,
+}
+
+/// Domination setup, given by the level.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DominationConfig {
+    pub points: Vec<Zone>,
+    /// Seconds alone on a point to take it.
+    pub capture_time: f32,
+    /// Points needed to win.
+    pub score_limit: f32,
+    /// Points per second for each capture point owned.
+    pub points_per_second: f32,
+    /// Match length in seconds.
+    pub time_limit: f32,
+}
+
+/// Hide and seek setup, given by the level.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HideAndSeekConfig {
+    pub seekers: Team,
+    /// Seconds the hiders have to hold out.
+    pub time_limit: f32,
+}
+
+/// Scoring area of a king-of-the-hill or hook duel level.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Zone {
+    Circle { center: (f32, f32), radius: f32 },
+    Polygon(Vec<(f32, f32)>),
+}
+
+impl Zone {
+    /// Whether p is in the zone. A point on the edge between two polygons
+    /// is in exactly one of them.
+    pub fn contains(&self, p: Point2<f32>) -> bool {
+        match self {
+            Zone::Circle { center, radius } => {
+                (p - Point2::new(center.0, center.1)).norm() <= *radius
+            }
+            // Count crossings of a ray going right from p.
+            Zone::Polygon(points) => {
+                let mut inside = false;
+                for (i, &(x1, y1)) in points.iter().enumerate() {
+                    let (x2, y2) = points[(i + 1) % points.len()];
+                    if (y1 > p.y) != (y2 > p.y) && p.x < x1 + (p.y - y1) / (y2 - y1) * (x2 - x1) {
+                        inside = !inside;
+                    }
+                }
+                inside
+            }
+        }
+    }
+
+    /// Middle of the zone, roughly for polygons.
+    pub fn center(&self) -> Point2<f32> {
+        match self {
+            Zone::Circle { center, .. } => Point2::new(center.0, center.1),
+            Zone::Polygon(points) => {
+                let n = points.len().max(1) as f32;
+                let (x, y) = points
+                    .iter()
+                    .fold((0.0, 0.0), |(x, y), &(px, py)| (x + px, y + py));
+                Point2::new(x / n, y / n)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f32, y: f32, size: f32) -> Zone {
+        Zone::Polygon(vec![
+            (x, y),
+            (x + size, y),
+            (x + size, y + size),
+            (x, y + size),
+        ])
+    }
+
+    #[test]
+    fn polygon_inside_and_outside() {
+        let zone = square(0.0, 0.0, 10.0);
+        assert!(zone.contains(Point2::new(5.0, 5.0)));
+        assert!(zone.contains(Point2::new(0.1, 9.9)));
+        assert!(!zone.contains(Point2::new(-1.0, 5.0)));
+        assert!(!zone.contains(Point2::new(5.0, 11.0)));
+        assert!(!zone.contains(Point2::new(15.0, 15.0)));
+        assert!(!Zone::Polygon(Vec::new()).contains(Point2::new(0.0, 0.0)));
+    }
+
+    #[test]
+    fn shared_edges_and_corners_belong_to_one_zone() {
+        // A 2 by 2 block of squares, meeting at (10, 10).
+        let zones = [
+            square(0.0, 0.0, 10.0),
+            square(10.0, 0.0, 10.0),
+            square(0.0, 10.0, 10.0),
+            square(10.0, 10.0, 10.0),
+        ];
+        for &(x, y) in &[
+            (10.0, 5.0),
+            (5.0, 10.0),
+            (10.0, 15.0),
+            (15.0, 10.0),
+            (10.0, 10.0),
+        ] {
+            let p = Point2::new(x, y);
+            let count = zones.iter().filter(|zone| zone.contains(p)).count();
+            assert_eq!(count, 1, "({}, {}) is in {} zones", x, y, count);
+        }
+    }
+
+    #[test]
+    fn concave_polygon() {
+        // A U open at the bottom, with a notch from y 10 down.
+        let zone = Zone::Polygon(vec![
+            (0.0, 0.0),
+            (30.0, 0.0),
+            (30.0, 30.0),
+            (20.0, 30.0),
+            (20.0, 10.0),
+            (10.0, 10.0),
+            (10.0, 30.0),
+            (0.0, 30.0),
+        ]);
+        assert!(zone.contains(Point2::new(15.0, 5.0)));
+        assert!(zone.contains(Point2::new(5.0, 20.0)));
+        assert!(zone.contains(Point2::new(25.0, 20.0)));
+        assert!(!zone.contains(Point2::new(15.0, 20.0)));
+        // In line with the notch's corners, which a ray crosses twice.
+        assert!(zone.contains(Point2::new(5.0, 10.0)));
+        assert!(!zone.contains(Point2::new(15.0, 10.5)));
+    }
+
+    #[test]
+    fn circle() {
+        let zone = Zone::Circle {
+            center: (100.0, 100.0),
+            radius: 50.0,
+        };
+        assert!(zone.contains(Point2::new(100.0, 100.0)));
+        assert!(zone.contains(Point2::new(150.0, 100.0)));
+        assert!(!zone.contains(Point2::new(140.0, 140.0)));
+    }
+}
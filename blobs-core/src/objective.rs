@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use crate::team::Team;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ObjectiveKind {
+    /// Belongs to a team and can only be carried by blobs of other teams.
+    Flag(Team),
+    Bomb,
+}
+
+impl ObjectiveKind {
+    pub fn can_be_carried_by(self, team: Option<Team>) -> bool {
+        match self {
+            ObjectiveKind::Flag(owner) => team.is_some() && team != Some(owner),
+            ObjectiveKind::Bomb => true,
+        }
+    }
+}
+
+/// Where an objective is placed in a level.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectiveSpawn {
+    pub kind: ObjectiveKind,
+    pub pos: (f32, f32),
+}
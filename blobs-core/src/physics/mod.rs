@@ -0,0 +1,242 @@
+use nalgebra::{Point2, Vector2};
+use std::collections::BTreeMap;
+
+use crate::collision::{reflect, wall_blob_collision, wall_blob_collisions};
+use crate::tuning;
+use crate::{Blob, DT, SCREEN_SIZE};
+
+#[cfg(feature = "rapier")]
+pub mod rapier;
+mod scalar;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd;
+
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+use scalar as kernels;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+use simd as kernels;
+
+/// Something that moves the blobs a tick at a time and bounces them off the
+/// walls. Bodies is the home-grown integrator. With the rapier feature,
+/// RapierWorld hands the same job to rapier2d.
+pub trait PhysicsWorld {
+    /// Move every blob one step forward.
+    fn step(&mut self, blobs: &mut BTreeMap<usize, Blob>);
+
+    /// Speed with which the blob hit a wall in the last step, if it did.
+    fn impact_speed(&self, id: usize) -> Option<f32>;
+}
+
+/// The physics a world is stepped with: rapier2d when the rapier feature is
+/// on, the home-grown integrator otherwise.
+#[cfg(not(feature = "rapier"))]
+pub fn backend() -> Box<dyn PhysicsWorld> {
+    Box::new(Bodies::new())
+}
+
+#[cfg(feature = "rapier")]
+pub fn backend() -> Box<dyn PhysicsWorld> {
+    Box::new(rapier::RapierWorld::new())
+}
+
+/// Position, velocity and acceleration of every blob, each component in its
+/// own array, so the integrator and the wall checks run over contiguous
+/// memory. The blobs are copied in before each step and back out after, and
+/// the arrays are kept between ticks to avoid allocating.
+#[derive(Default)]
+pub struct Bodies {
+    pub ids: Vec<usize>,
+    pub pos_x: Vec<f32>,
+    pub pos_y: Vec<f32>,
+    pub vel_x: Vec<f32>,
+    pub vel_y: Vec<f32>,
+    pub acc_x: Vec<f32>,
+    pub acc_y: Vec<f32>,
+    /// Speed with which each body hit a wall this step, if it did.
+    pub impact_speed: Vec<Option<f32>>,
+    // Broad phase result: whether each body may touch a wall.
+    near_wall: Vec<bool>,
+}
+
+impl Bodies {
+    pub fn new() -> Bodies {
+        Bodies::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.ids.clear();
+        self.pos_x.clear();
+        self.pos_y.clear();
+        self.vel_x.clear();
+        self.vel_y.clear();
+        self.acc_x.clear();
+        self.acc_y.clear();
+        self.impact_speed.clear();
+        self.near_wall.clear();
+    }
+
+    fn push(&mut self, id: usize, center: Point2<f32>, vel: Vector2<f32>, acc: Vector2<f32>) {
+        self.ids.push(id);
+        self.pos_x.push(center.x);
+        self.pos_y.push(center.y);
+        self.vel_x.push(vel.x);
+        self.vel_y.push(vel.y);
+        self.acc_x.push(acc.x);
+        self.acc_y.push(acc.y);
+        self.impact_speed.push(None);
+        self.near_wall.push(false);
+    }
+
+    pub fn center(&self, i: usize) -> Point2<f32> {
+        Point2::new(self.pos_x[i], self.pos_y[i])
+    }
+
+    pub fn vel(&self, i: usize) -> Vector2<f32> {
+        Vector2::new(self.vel_x[i], self.vel_y[i])
+    }
+
+    /// Semi-implicit Euler: velocity first, then position with the new
+    /// velocity.
+    fn integrate(&mut self, dt: f32) {
+        kernels::add_scaled(&mut self.vel_x, &self.acc_x, dt);
+        kernels::add_scaled(&mut self.vel_y, &self.acc_y, dt);
+        kernels::add_scaled(&mut self.pos_x, &self.vel_x, dt);
+        kernels::add_scaled(&mut self.pos_y, &self.vel_y, dt);
+    }
+
+    /// Bounce bodies off the walls they overlap. A quick pass over all
+    /// bodies picks out those near a wall before the exact check.
+    fn collide_walls(&mut self) {
+        kernels::near_walls(
+            &self.pos_x,
+            &self.pos_y,
+            tuning::current().blob_radius,
+            SCREEN_SIZE,
+            &mut self.near_wall,
+        );
+        for i in 0..self.len() {
+            if !self.near_wall[i] {
+                continue;
+            }
+            // In a corner, bounce off both walls, or the blob keeps sinking
+            // into the second one.
+            let mut vel = self.vel(i);
+            for (_collision_point, collision_normal) in wall_blob_collisions(self.center(i)) {
+                let speed = -vel.dot(&collision_normal);
+                self.impact_speed[i] = Some(self.impact_speed[i].map_or(speed, |s| s.max(speed)));
+                log::trace!(
+                    "Blob {} hit a wall at {:.1} units per DT",
+                    self.ids[i],
+                    speed
+                );
+                vel = reflect(vel, collision_normal);
+                self.vel_x[i] = vel.x;
+                self.vel_y[i] = vel.y;
+
+                // TODO: Move center out of wall too. This is important for
+                // when the next turns forces (e.g. gravity) are strong so the
+                // flipped velocity is not enough to escape the wall. Try
+                // moving close to the ground with low vertical velocity to
+                // see an example of this.
+            }
+        }
+    }
+}
+
+impl PhysicsWorld for Bodies {
+    fn step(&mut self, blobs: &mut BTreeMap<usize, Blob>) {
+        step(blobs, self);
+    }
+
+    fn impact_speed(&self, id: usize) -> Option<f32> {
+        // The bodies are pushed in the order of the blob map.
+        let i = self.ids.binary_search(&id).ok()?;
+        self.impact_speed[i]
+    }
+}
+
+/// Move every blob one step forward. The bodies are left holding the
+/// result, including which blobs hit a wall.
+pub fn step(blobs: &mut BTreeMap<usize, Blob>, bodies: &mut Bodies) {
+    bodies.clear();
+    for (&id, blob) in blobs.iter_mut() {
+        blob.update_timers();
+        bodies.push(id, blob.center, blob.vel, blob.acceleration());
+    }
+    bodies.integrate(DT);
+    bodies.collide_walls();
+    for i in 0..bodies.len() {
+        if let Some(blob) = blobs.get_mut(&bodies.ids[i]) {
+            blob.center = bodies.center(i);
+            blob.vel = bodies.vel(i);
+            log::trace!(
+                "Blob {} at {:.1}, {:.1} moving {:.1}, {:.1}",
+                bodies.ids[i],
+                blob.center.x,
+                blob.center.y,
+                blob.vel.x,
+                blob.vel.y
+            );
+            if !blob
+                .center
+                .coords
+                .iter()
+                .chain(blob.vel.iter())
+                .all(|x| x.is_finite())
+            {
+                log::error!(
+                    "Blob {} left the simulation: center {:?}, velocity {:?}",
+                    bodies.ids[i],
+                    blob.center,
+                    blob.vel
+                );
+            } else if wall_blob_collision(blob.center).is_some() {
+                log::debug!("Blob {} is still in a wall after bouncing", bodies.ids[i]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collision::wall_point_collision;
+    use proptest::prelude::*;
+
+    proptest! {
+        /// A blob may dent itself against a wall, but never ends a step with
+        /// its center past one, as long as it moves less than its radius a
+        /// step.
+        #[test]
+        fn blob_never_ends_a_step_inside_a_wall(
+            x in 40.0f32..960.0,
+            y in 40.0f32..960.0,
+            vel_x in -300.0f32..300.0,
+            vel_y in -300.0f32..300.0,
+            steps in 1usize..50,
+        ) {
+            let mut blob = Blob::new(Point2::new(x, y), None);
+            blob.vel = Vector2::new(vel_x, vel_y);
+            let mut blobs = BTreeMap::new();
+            blobs.insert(0, blob);
+            let mut bodies = Bodies::new();
+            for _ in 0..steps {
+                step(&mut blobs, &mut bodies);
+                let center = blobs[&0].center;
+                prop_assert!(
+                    wall_point_collision(center).is_none(),
+                    "blob ended a step at {:?}",
+                    center
+                );
+            }
+        }
+    }
+}
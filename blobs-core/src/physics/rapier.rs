@@ -0,0 +1,233 @@
+//! The blobs stepped by rapier2d instead of the home-grown integrator, to
+//! compare how they feel and to get continuous collision detection against
+//! the walls.
+//!
+//! The blobs' own forces (rope, gravity and damping) are still worked out by
+//! the game and applied as a change of velocity before each step, so only
+//! the integration and the wall contacts are rapier's. The rope is a spring
+//! whose pull levels off with length, which none of rapier's joints model.
+
+use ::nalgebra::{Point2, Vector2};
+use rapier2d::prelude::*;
+use std::collections::BTreeMap;
+
+use super::PhysicsWorld;
+use crate::{tuning, Blob, DT, SCREEN_SIZE};
+
+// Thickness of the colliders around the level. Thick enough that nothing
+// gets through between two checks even without CCD.
+const WALL_THICKNESS: f32 = 1000.0;
+
+// Blobs only touch the walls, not each other.
+const BLOBS: Group = Group::GROUP_1;
+const WALLS: Group = Group::GROUP_2;
+
+struct BlobBody {
+    body: RigidBodyHandle,
+    collider: ColliderHandle,
+    // Velocity going into the step, to tell the impact speed from.
+    vel: Vector2<f32>,
+}
+
+/// The rapier world along with which body is which blob.
+pub struct RapierWorld {
+    pipeline: PhysicsPipeline,
+    params: IntegrationParameters,
+    islands: IslandManager,
+    broad_phase: DefaultBroadPhase,
+    narrow_phase: NarrowPhase,
+    bodies: RigidBodySet,
+    colliders: ColliderSet,
+    impulse_joints: ImpulseJointSet,
+    multibody_joints: MultibodyJointSet,
+    ccd: CCDSolver,
+    /// Each wall's collider and normal pointing into the level.
+    walls: Vec<(ColliderHandle, Vector2<f32>)>,
+    blobs: BTreeMap<usize, BlobBody>,
+    radius: f32,
+    impact_speed: BTreeMap<usize, f32>,
+}
+
+impl RapierWorld {
+    pub fn new() -> RapierWorld {
+        let radius = tuning::current().blob_radius;
+        let mut colliders = ColliderSet::new();
+        let (w, h) = SCREEN_SIZE;
+        let t = WALL_THICKNESS;
+        let walls = [
+            ((-t / 2.0, h / 2.0), (t / 2.0, h / 2.0 + t), Vector2::x()),
+            (
+                (w + t / 2.0, h / 2.0),
+                (t / 2.0, h / 2.0 + t),
+                -Vector2::x(),
+            ),
+            ((w / 2.0, -t / 2.0), (w / 2.0 + t, t / 2.0), Vector2::y()),
+            (
+                (w / 2.0, h + t / 2.0),
+                (w / 2.0 + t, t / 2.0),
+                -Vector2::y(),
+            ),
+        ]
+        .iter()
+        .map(|&((x, y), (hx, hy), normal)| {
+            let wall = ColliderBuilder::cuboid(hx, hy)
+                .translation(Vector::new(x, y))
+                .friction(0.0)
+                .restitution(1.0)
+                .collision_groups(InteractionGroups::new(
+                    WALLS,
+                    BLOBS,
+                    InteractionTestMode::And,
+                ));
+            (colliders.insert(wall), normal)
+        })
+        .collect();
+        RapierWorld {
+            pipeline: PhysicsPipeline::new(),
+            params: IntegrationParameters {
+                dt: DT,
+                // Rapier's tolerances are in units of this length.
+                length_unit: radius,
+                ..IntegrationParameters::default()
+            },
+            islands: IslandManager::new(),
+            broad_phase: DefaultBroadPhase::new(),
+            narrow_phase: NarrowPhase::new(),
+            bodies: RigidBodySet::new(),
+            colliders,
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            ccd: CCDSolver::new(),
+            walls,
+            blobs: BTreeMap::new(),
+            radius,
+            impact_speed: BTreeMap::new(),
+        }
+    }
+
+    fn add_body(&mut self, id: usize) {
+        let body = self.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .gravity_scale(0.0)
+                .ccd_enabled(true)
+                .can_sleep(false),
+        );
+        let collider = self.colliders.insert_with_parent(
+            ColliderBuilder::ball(self.radius)
+                .friction(0.0)
+                .restitution(1.0)
+                .restitution_combine_rule(CoefficientCombineRule::Max)
+                .collision_groups(InteractionGroups::new(
+                    BLOBS,
+                    WALLS,
+                    InteractionTestMode::And,
+                )),
+            body,
+            &mut self.bodies,
+        );
+        self.blobs.insert(
+            id,
+            BlobBody {
+                body,
+                collider,
+                vel: Vector2::zeros(),
+            },
+        );
+    }
+
+    /// Add and remove bodies to match the blobs, and follow changes to the
+    /// blob radius.
+    fn sync_bodies(&mut self, blobs: &BTreeMap<usize, Blob>) {
+        let gone: Vec<usize> = self
+            .blobs
+            .keys()
+            .filter(|id| !blobs.contains_key(id))
+            .copied()
+            .collect();
+        for id in gone {
+            let body = self.blobs.remove(&id).unwrap().body;
+            self.bodies.remove(
+                body,
+                &mut self.islands,
+                &mut self.colliders,
+                &mut self.impulse_joints,
+                &mut self.multibody_joints,
+                true,
+            );
+        }
+        for &id in blobs.keys() {
+            if !self.blobs.contains_key(&id) {
+                self.add_body(id);
+            }
+        }
+        let radius = tuning::current().blob_radius;
+        if radius != self.radius {
+            self.radius = radius;
+            self.params.length_unit = radius;
+            for blob in self.blobs.values() {
+                self.colliders[blob.collider].set_shape(SharedShape::ball(radius));
+            }
+        }
+    }
+}
+
+impl Default for RapierWorld {
+    fn default() -> RapierWorld {
+        RapierWorld::new()
+    }
+}
+
+impl PhysicsWorld for RapierWorld {
+    fn step(&mut self, blobs: &mut BTreeMap<usize, Blob>) {
+        self.sync_bodies(blobs);
+        // The game moves blobs around between steps, e.g. when they
+        // respawn or perch, so the bodies are put where the blobs are.
+        for (id, blob) in blobs.iter_mut() {
+            blob.update_timers();
+            let vel = blob.vel + blob.acceleration() * DT;
+            let body = self.blobs.get_mut(id).unwrap();
+            body.vel = vel;
+            let rigid_body = &mut self.bodies[body.body];
+            rigid_body.set_translation(Vector::new(blob.center.x, blob.center.y), true);
+            rigid_body.set_linvel(Vector::new(vel.x, vel.y), true);
+        }
+        self.pipeline.step(
+            Vector::ZERO,
+            &self.params,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd,
+            &(),
+            &(),
+        );
+        self.impact_speed.clear();
+        for (id, blob) in blobs.iter_mut() {
+            let body = &self.blobs[id];
+            let rigid_body = &self.bodies[body.body];
+            let center = rigid_body.translation();
+            let vel = rigid_body.linvel();
+            blob.center = Point2::new(center.x, center.y);
+            blob.vel = Vector2::new(vel.x, vel.y);
+            for &(wall, normal) in &self.walls {
+                let touching = self
+                    .narrow_phase
+                    .contact_pair(wall, body.collider)
+                    .is_some_and(|pair| pair.has_any_active_contact());
+                if touching {
+                    let speed = -body.vel.dot(&normal);
+                    let impact = self.impact_speed.entry(*id).or_insert(speed);
+                    *impact = impact.max(speed);
+                }
+            }
+        }
+    }
+
+    fn impact_speed(&self, id: usize) -> Option<f32> {
+        self.impact_speed.get(&id).copied()
+    }
+}
@@ -0,0 +1,17 @@
+//! Plain loops over the body arrays. Used when the simd feature is off, and
+//! for the leftover bodies that don't fill a whole SIMD lane.
+
+/// values[i] += rates[i] * dt
+pub fn add_scaled(values: &mut [f32], rates: &[f32], dt: f32) {
+    for (value, rate) in values.iter_mut().zip(rates) {
+        *value += rate * dt;
+    }
+}
+
+/// Flag the circles of the given radius that reach outside the area from
+/// (0, 0) to size.
+pub fn near_walls(xs: &[f32], ys: &[f32], radius: f32, size: (f32, f32), near: &mut [bool]) {
+    for ((x, y), near) in xs.iter().zip(ys).zip(near.iter_mut()) {
+        *near = *x < radius || *x > size.0 - radius || *y < radius || *y > size.1 - radius;
+    }
+}
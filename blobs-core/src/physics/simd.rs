@@ -0,0 +1,109 @@
+//! SSE versions of the scalar loops, four bodies at a time. SSE is part of
+//! every x86_64 CPU, so no runtime detection is needed. The results are the
+//! same as the scalar ones, which the tests below check.
+
+use std::arch::x86_64::*;
+
+use super::scalar;
+
+const LANES: usize = 4;
+
+pub fn add_scaled(values: &mut [f32], rates: &[f32], dt: f32) {
+    let len = values.len().min(rates.len());
+    let whole = len - len % LANES;
+    // Safety: SSE is always available on x86_64 and every load and store
+    // stays within the first `whole` elements of both slices.
+    unsafe {
+        let dt = _mm_set1_ps(dt);
+        for i in (0..whole).step_by(LANES) {
+            let value = _mm_loadu_ps(values.as_ptr().add(i));
+            let rate = _mm_loadu_ps(rates.as_ptr().add(i));
+            let sum = _mm_add_ps(value, _mm_mul_ps(rate, dt));
+            _mm_storeu_ps(values.as_mut_ptr().add(i), sum);
+        }
+    }
+    scalar::add_scaled(&mut values[whole..len], &rates[whole..len], dt);
+}
+
+pub fn near_walls(xs: &[f32], ys: &[f32], radius: f32, size: (f32, f32), near: &mut [bool]) {
+    let len = xs.len().min(ys.len()).min(near.len());
+    let whole = len - len % LANES;
+    // Safety: as above, loads stay within the first `whole` elements.
+    unsafe {
+        let low = _mm_set1_ps(radius);
+        let high_x = _mm_set1_ps(size.0 - radius);
+        let high_y = _mm_set1_ps(size.1 - radius);
+        for i in (0..whole).step_by(LANES) {
+            let x = _mm_loadu_ps(xs.as_ptr().add(i));
+            let y = _mm_loadu_ps(ys.as_ptr().add(i));
+            let outside = _mm_or_ps(
+                _mm_or_ps(_mm_cmplt_ps(x, low), _mm_cmpgt_ps(x, high_x)),
+                _mm_or_ps(_mm_cmplt_ps(y, low), _mm_cmpgt_ps(y, high_y)),
+            );
+            let mask = _mm_movemask_ps(outside);
+            for lane in 0..LANES {
+                near[i + lane] = mask & (1 << lane) != 0;
+            }
+        }
+    }
+    scalar::near_walls(
+        &xs[whole..len],
+        &ys[whole..len],
+        radius,
+        size,
+        &mut near[whole..len],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const SIZE: (f32, f32) = (1000.0, 800.0);
+    const RADIUS: f32 = 20.0;
+
+    /// Coordinates with plenty right on the edges of where a blob touches
+    /// a wall.
+    fn coordinate() -> impl Strategy<Value = f32> {
+        prop_oneof![
+            Just(RADIUS),
+            Just(SIZE.0 - RADIUS),
+            Just(SIZE.1 - RADIUS),
+            Just(f32::NAN),
+            -100.0f32..SIZE.0 + 100.0,
+        ]
+    }
+
+    fn bits(values: &[f32]) -> Vec<u32> {
+        values.iter().map(|value| value.to_bits()).collect()
+    }
+
+    proptest! {
+        // Lengths up to 4 lanes and a few over, so the leftover bodies that
+        // go through the scalar loop are covered too.
+        #[test]
+        fn add_scaled_matches_scalar(
+            pairs in prop::collection::vec((coordinate(), -1000.0f32..1000.0), 0..19),
+            dt in 0.0f32..1.0,
+        ) {
+            let (mut values, rates): (Vec<f32>, Vec<f32>) = pairs.into_iter().unzip();
+            let mut expected = values.clone();
+            scalar::add_scaled(&mut expected, &rates, dt);
+            add_scaled(&mut values, &rates, dt);
+            prop_assert_eq!(bits(&values), bits(&expected));
+        }
+
+        #[test]
+        fn near_walls_matches_scalar(
+            centers in prop::collection::vec((coordinate(), coordinate()), 0..19),
+        ) {
+            let (xs, ys): (Vec<f32>, Vec<f32>) = centers.into_iter().unzip();
+            let mut near = vec![false; xs.len()];
+            let mut expected = vec![true; xs.len()];
+            scalar::near_walls(&xs, &ys, RADIUS, SIZE, &mut expected);
+            near_walls(&xs, &ys, RADIUS, SIZE, &mut near);
+            prop_assert_eq!(near, expected);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use nalgebra::Point2;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::balance;
+use crate::grid::Grid;
+use crate::health::MAX_HEALTH;
+use crate::tuning;
+use crate::{Blob, TICK_TIME};
+
+pub const PICKUP_RADIUS: f32 = 15.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PickupKind {
+    /// Stronger pull from the rope.
+    SpeedBoost,
+    /// Hook travels twice as fast.
+    DoubleHook,
+    /// No damage is taken.
+    Shield,
+    /// Heals right away.
+    Health,
+}
+
+/// Where a pickup is placed in a level.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PickupSpawn {
+    pub kind: PickupKind,
+    pub pos: (f32, f32),
+    /// Seconds until the pickup comes back after being collected. Pickups
+    /// without it can only be collected once.
+    #[serde(default)]
+    pub respawn_time: Option<f32>,
+}
+
+/// Timed effects from pickups on a blob.
+#[derive(Default)]
+pub struct Effects {
+    time_left: HashMap<PickupKind, f32>,
+}
+
+impl Effects {
+    pub fn new() -> Effects {
+        Effects::default()
+    }
+
+    pub fn is_active(&self, kind: PickupKind) -> bool {
+        self.time_left.contains_key(&kind)
+    }
+
+    /// Active effects and their seconds left, for the HUD.
+    pub fn active(&self) -> Vec<(PickupKind, f32)> {
+        let mut active: Vec<_> = self.time_left.iter().map(|(&k, &t)| (k, t)).collect();
+        active.sort_by(|a, b| b.1.total_cmp(&a.1));
+        active
+    }
+
+    /// Call once per tick.
+    pub fn update(&mut self) {
+        for time_left in self.time_left.values_mut() {
+            *time_left -= TICK_TIME;
+        }
+        self.time_left.retain(|_, time_left| *time_left > 0.0);
+    }
+}
+
+/// A pickup placed in the level. Collected by touching it.
+struct Pickup {
+    kind: PickupKind,
+    pos: Point2<f32>,
+    respawn_time: Option<f32>,
+    // Seconds until the pickup is back, None while it can be collected.
+    cooldown: Option<f32>,
+}
+
+impl Pickup {
+    fn apply(&self, blob: &mut Blob) {
+        match self.kind {
+            PickupKind::Health => {
+                blob.health =
+                    (blob.health + balance::current().pickups.health_amount).min(MAX_HEALTH)
+            }
+            kind => {
+                blob.effects
+                    .time_left
+                    .insert(kind, balance::current().pickups.effect_time);
+            }
+        }
+    }
+}
+
+/// All pickups in the level.
+pub struct Pickups {
+    pickups: Vec<Pickup>,
+}
+
+impl Pickups {
+    pub fn new(spawns: &[PickupSpawn]) -> Pickups {
+        let mut pickups = Pickups {
+            pickups: Vec::new(),
+        };
+        for spawn in spawns {
+            pickups.add(spawn);
+        }
+        pickups
+    }
+
+    /// Place another pickup, until the round ends.
+    pub fn add(&mut self, spawn: &PickupSpawn) {
+        self.pickups.push(Pickup {
+            kind: spawn.kind,
+            pos: Point2::new(spawn.pos.0, spawn.pos.1),
+            respawn_time: spawn.respawn_time,
+            cooldown: None,
+        });
+    }
+
+    /// Let blobs collect the pickups they touch and bring back collected
+    /// pickups when their time has come. Call once per tick, with the grid
+    /// holding where the blobs are now.
+    pub fn update(&mut self, blobs: &mut BTreeMap<usize, Blob>, grid: &Grid) {
+        for pickup in &mut self.pickups {
+            match &mut pickup.cooldown {
+                Some(cooldown) => {
+                    *cooldown -= TICK_TIME;
+                    if *cooldown <= 0.0 && pickup.respawn_time.is_some() {
+                        pickup.cooldown = None;
+                    }
+                }
+                None => {
+                    // Of the blobs touching it, the lowest id gets it.
+                    let reach = tuning::current().blob_radius + PICKUP_RADIUS;
+                    let collector = grid.lowest_near(pickup.pos, reach, |_, center| {
+                        (center - pickup.pos).norm() < reach
+                    });
+                    if let Some(blob) = collector.and_then(|id| blobs.get_mut(&id)) {
+                        pickup.apply(blob);
+                        pickup.cooldown = Some(pickup.respawn_time.unwrap_or(0.0));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Kinds and places of the pickups that can be collected now.
+    pub fn available(&self) -> impl Iterator<Item = (PickupKind, Point2<f32>)> + '_ {
+        self.pickups
+            .iter()
+            .filter(|pickup| pickup.cooldown.is_none())
+            .map(|pickup| (pickup.kind, pickup.pos))
+    }
+}
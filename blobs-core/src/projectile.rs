@@ -0,0 +1,81 @@
+use nalgebra::{Point2, Vector2};
+use std::collections::BTreeMap;
+
+use crate::balance;
+use crate::collision::{segment_point_distance, wall_point_collision};
+use crate::grid::Grid;
+use crate::health::{Damage, DamageSource};
+use crate::tuning;
+use crate::{Blob, DT};
+
+pub struct Projectile {
+    pos: Point2<f32>,
+    vel: Vector2<f32>,
+    owner: usize,
+    time_left: f32,
+}
+
+impl Projectile {
+    pub fn new(owner: usize, pos: Point2<f32>, vel: Vector2<f32>) -> Projectile {
+        Projectile {
+            pos,
+            vel,
+            owner,
+            time_left: balance::current().weapon.lifetime,
+        }
+    }
+
+    /// Fire a projectile from the edge of the owner blob along its aim.
+    pub fn fire(owner: usize, blob: &Blob) -> Projectile {
+        Projectile::new(
+            owner,
+            blob.center + tuning::current().blob_radius * blob.aim_vec,
+            balance::current().weapon.speed * blob.aim_vec,
+        )
+    }
+
+    /// Returns false once the projectile has hit something or expired.
+    pub fn update(
+        &mut self,
+        blobs: &mut BTreeMap<usize, Blob>,
+        grid: &Grid,
+        damage: &mut Vec<Damage>,
+    ) -> bool {
+        let weapon = balance::current().weapon;
+        let start = self.pos;
+        self.pos += self.vel * DT;
+        self.time_left -= DT;
+
+        // Check the whole path travelled this step so fast projectiles can't
+        // skip past blobs. Of the blobs on the path, the lowest id is hit.
+        let owner = self.owner;
+        let reach = tuning::current().blob_radius + weapon.radius;
+        let hit = grid.lowest_near_segment(start, self.pos, reach, |id, center| {
+            id != owner && segment_point_distance(start, self.pos, center) < reach
+        });
+        if let Some((id, blob)) = hit.and_then(|id| Some((id, blobs.get_mut(&id)?))) {
+            blob.vel += weapon.knockback * self.vel.normalize();
+            damage.push(Damage {
+                target: id,
+                amount: weapon.damage,
+                source: DamageSource::Projectile(owner),
+            });
+            return false;
+        }
+
+        self.time_left > 0.0 && wall_point_collision(self.pos).is_none()
+    }
+
+    pub fn pos(&self) -> Point2<f32> {
+        self.pos
+    }
+
+    pub fn vel(&self) -> Vector2<f32> {
+        self.vel
+    }
+
+    /// The blob that fired the projectile.
+    pub fn owner(&self) -> usize {
+        self.owner
+    }
+}
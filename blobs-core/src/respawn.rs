@@ -0,0 +1,95 @@
+use nalgebra::Point2;
+use std::collections::BTreeMap;
+
+use crate::balance;
+use crate::health::GameEvent;
+use crate::level::Level;
+use crate::{Blob, TICK_TIME};
+
+/// Holds dead blobs until they are due to respawn.
+#[derive(Default)]
+pub struct Respawner {
+    dead: BTreeMap<usize, (Blob, f32)>,
+}
+
+impl Respawner {
+    pub fn new() -> Respawner {
+        Respawner::default()
+    }
+
+    /// Ids of the blobs waiting to respawn.
+    pub fn waiting(&self) -> impl Iterator<Item = &usize> {
+        self.dead.keys()
+    }
+
+    /// Blobs waiting to respawn, by id.
+    pub fn waiting_blobs(&self) -> impl Iterator<Item = (&usize, &Blob)> {
+        self.dead.iter().map(|(id, (blob, _time_left))| (id, blob))
+    }
+
+    /// Forget a dead blob, so it never respawns.
+    pub fn remove(&mut self, id: usize) {
+        self.dead.remove(&id);
+    }
+
+    /// Take blobs that died this tick out of play and put back the ones whose
+    /// respawn delay is over. Blobs that killed themselves wait the extra
+    /// suicide penalty. Blobs that the game mode already revived are left
+    /// alone.
+    pub fn update(
+        &mut self,
+        blobs: &mut BTreeMap<usize, Blob>,
+        level: &Level,
+        events: &[GameEvent],
+        suicide_penalty: f32,
+    ) {
+        for event in events {
+            if let GameEvent::Died {
+                target, suicide, ..
+            } = *event
+            {
+                if blobs.get(&target).is_some_and(|blob| blob.health <= 0.0) {
+                    let blob = blobs.remove(&target).unwrap();
+                    let mut delay = balance::current().blob.respawn_delay;
+                    if suicide {
+                        delay += suicide_penalty;
+                    }
+                    self.dead.insert(target, (blob, delay));
+                }
+            }
+        }
+
+        for (_blob, time_left) in self.dead.values_mut() {
+            *time_left -= TICK_TIME;
+        }
+        let ready: Vec<usize> = self
+            .dead
+            .iter()
+            .filter(|(_id, (_blob, time_left))| *time_left <= 0.0)
+            .map(|(&id, _)| id)
+            .collect();
+        for id in ready {
+            let (mut blob, _time_left) = self.dead.remove(&id).unwrap();
+            let spawn_point = farthest_from_enemies(level, &blob, blobs);
+            blob.respawn(spawn_point);
+            blob.invulnerable = balance::current().blob.spawn_invulnerability;
+            blobs.insert(id, blob);
+        }
+    }
+}
+
+/// Pick the spawn point available to the blob's team with the largest
+/// distance to the closest enemy.
+fn farthest_from_enemies(level: &Level, blob: &Blob, blobs: &BTreeMap<usize, Blob>) -> Point2<f32> {
+    let enemy_distance = |p: Point2<f32>| {
+        blobs
+            .values()
+            .filter(|other| other.team.is_none() || other.team != blob.team)
+            .map(|other| (other.center - p).norm())
+            .fold(f32::INFINITY, f32::min)
+    };
+    level
+        .spawn_points(blob.team)
+        .max_by(|&a, &b| enemy_distance(a).total_cmp(&enemy_distance(b)))
+        .unwrap_or_else(|| level.spawn_point(blob.team))
+}
@@ -0,0 +1,192 @@
+//! The state of a world as plain data, for tools that show or analyse a
+//! match outside the game, or start a simulation from a given state. Points
+//! and vectors are (x, y) pairs, like in levels.
+
+use nalgebra::{Point2, Vector2};
+use serde::{Deserialize, Serialize};
+
+use crate::attachment::{Anchor, Attachment};
+use crate::entity::Entity;
+use crate::pickup::PickupKind;
+use crate::projectile::Projectile;
+use crate::team::Team;
+use crate::world::World;
+use crate::{Blob, HookState};
+
+/// The blobs, projectiles and pickups of a world between two steps.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub blobs: Vec<BlobSnapshot>,
+    pub projectiles: Vec<ProjectileSnapshot>,
+    /// Pickups that can be collected.
+    pub pickups: Vec<(PickupKind, (f32, f32))>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlobSnapshot {
+    pub id: usize,
+    pub center: (f32, f32),
+    /// In world units per DT.
+    pub vel: (f32, f32),
+    pub aim: (f32, f32),
+    pub hook: HookSnapshot,
+    pub team: Option<Team>,
+    pub health: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum HookSnapshot {
+    None,
+    Traveling { point: (f32, f32), vel: (f32, f32) },
+    Attached { anchor: Anchor, point: (f32, f32) },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProjectileSnapshot {
+    pub owner: usize,
+    pub pos: (f32, f32),
+    pub vel: (f32, f32),
+}
+
+impl Snapshot {
+    pub fn of(world: &World) -> Snapshot {
+        Snapshot {
+            blobs: world
+                .blobs
+                .iter()
+                .map(|(&id, blob)| BlobSnapshot::of(id, blob))
+                .collect(),
+            projectiles: world
+                .entities
+                .iter()
+                .map(|entity| match entity {
+                    Entity::Projectile(projectile) => ProjectileSnapshot {
+                        owner: projectile.owner(),
+                        pos: pair(projectile.pos().coords),
+                        vel: pair(projectile.vel()),
+                    },
+                })
+                .collect(),
+            pickups: world
+                .pickups
+                .available()
+                .map(|(kind, pos)| (kind, pair(pos.coords)))
+                .collect(),
+        }
+    }
+
+    /// Put the blobs and projectiles back in the world, replacing the ones
+    /// in it. What a blob doesn't keep in the snapshot starts over, as after
+    /// a respawn, and so does the projectiles' lifetime. The pickups are
+    /// left as they are.
+    pub fn restore(&self, world: &mut World) {
+        world.blobs = self
+            .blobs
+            .iter()
+            .map(|blob| (blob.id, blob.to_blob()))
+            .collect();
+        world.entities = self
+            .projectiles
+            .iter()
+            .map(|projectile| {
+                Entity::Projectile(Projectile::new(
+                    projectile.owner,
+                    point(projectile.pos),
+                    vector(projectile.vel),
+                ))
+            })
+            .collect();
+        world.damage.clear();
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string(self)
+    }
+
+    pub fn from_ron(source: &str) -> Result<Snapshot, ron::de::SpannedError> {
+        ron::de::from_str(source)
+    }
+}
+
+impl BlobSnapshot {
+    pub fn of(id: usize, blob: &Blob) -> BlobSnapshot {
+        let hook = match blob.hook {
+            HookState::None => HookSnapshot::None,
+            HookState::Traveling(point, vel) => HookSnapshot::Traveling {
+                point: pair(point.coords),
+                vel: pair(vel),
+            },
+            HookState::Attached(attachment) => HookSnapshot::Attached {
+                anchor: attachment.anchor,
+                point: pair(attachment.point.coords),
+            },
+        };
+        BlobSnapshot {
+            id,
+            center: pair(blob.center.coords),
+            vel: pair(blob.vel),
+            aim: pair(blob.aim_vec),
+            hook,
+            team: blob.team,
+            health: blob.health,
+        }
+    }
+
+    pub fn to_blob(&self) -> Blob {
+        let mut blob = Blob::new(point(self.center), self.team);
+        blob.vel = vector(self.vel);
+        blob.aim_vec = vector(self.aim);
+        blob.health = self.health;
+        blob.hook = match self.hook {
+            HookSnapshot::None => HookState::None,
+            HookSnapshot::Traveling { point: p, vel } => {
+                HookState::Traveling(point(p), vector(vel))
+            }
+            HookSnapshot::Attached { anchor, point: p } => {
+                HookState::Attached(Attachment::rope(anchor, point(p)))
+            }
+        };
+        blob
+    }
+}
+
+fn pair(v: Vector2<f32>) -> (f32, f32) {
+    (v.x, v.y)
+}
+
+fn point((x, y): (f32, f32)) -> Point2<f32> {
+    Point2::new(x, y)
+}
+
+fn vector((x, y): (f32, f32)) -> Vector2<f32> {
+    Vector2::new(x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::Level;
+
+    #[test]
+    fn round_trips_through_ron() {
+        let mut world = World::new(Level::parse("()").unwrap());
+        let mut hooked = Blob::new(Point2::new(300.0, 400.0), Some(Team::Red));
+        hooked.vel = Vector2::new(3.0, -2.0);
+        hooked.hook = HookState::Attached(Attachment::rope(Anchor::Wall, Point2::new(0.0, 120.0)));
+        world.blobs.insert(4, hooked);
+        let mut flying = Blob::new(Point2::new(600.0, 500.0), None);
+        flying.fire_hook();
+        world
+            .entities
+            .push(Entity::Projectile(Projectile::fire(7, &flying)));
+        world.blobs.insert(7, flying);
+
+        let snapshot = Snapshot::of(&world);
+        let source = snapshot.to_ron().unwrap();
+        assert_eq!(Snapshot::from_ron(&source).unwrap(), snapshot);
+
+        let mut copy = World::new(Level::parse("()").unwrap());
+        snapshot.restore(&mut copy);
+        assert_eq!(Snapshot::of(&copy), snapshot);
+    }
+}
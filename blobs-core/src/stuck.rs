@@ -0,0 +1,120 @@
+use nalgebra::{Point2, Vector2};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::level::{HostileEntry, Level, Region};
+use crate::tuning;
+use crate::{Blob, SCREEN_SIZE};
+
+// A blob this far into a wall, as a part of its radius, for this many ticks
+// in a row is stuck.
+const STUCK_DEPTH: f32 = 0.5;
+pub const STUCK_TICKS: u32 = 30;
+// Free spots are looked for on rings this far apart around the blob, with
+// this many points on each.
+const SEARCH_STEP: f32 = 10.0;
+const SEARCH_DIRECTIONS: usize = 16;
+
+/// Whether a blob at center is further into a wall or blocking region than
+/// a bounce can take it.
+pub fn is_deep_in_wall(blocking: &[Region], center: Point2<f32>) -> bool {
+    let reach = (1.0 - STUCK_DEPTH) * tuning::current().blob_radius;
+    center.x < reach
+        || center.y < reach
+        || center.x > SCREEN_SIZE.0 - reach
+        || center.y > SCREEN_SIZE.1 - reach
+        || blocking
+            .iter()
+            .any(|region| region.blob_collision(center, reach).is_some())
+}
+
+/// Regions a blob of the given team can't enter under the mode's rule.
+pub fn blocking_regions(level: &Level, rule: HostileEntry, blob: &Blob) -> Vec<Region> {
+    match rule {
+        HostileEntry::Block => level
+            .hostile_bases(blob.team)
+            .map(|base| base.region)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The closest place to center where a blob touches neither the walls nor
+/// any of the blocking regions, if there is one in the level.
+pub fn nearest_free_spot(blocking: &[Region], center: Point2<f32>) -> Option<Point2<f32>> {
+    let radius = tuning::current().blob_radius;
+    let is_free = |p: Point2<f32>| {
+        p.x >= radius
+            && p.y >= radius
+            && p.x <= SCREEN_SIZE.0 - radius
+            && p.y <= SCREEN_SIZE.1 - radius
+            && blocking
+                .iter()
+                .all(|region| region.blob_collision(p, radius).is_none())
+    };
+    let max_distance = SCREEN_SIZE.0.hypot(SCREEN_SIZE.1);
+    let rings = (max_distance / SEARCH_STEP).ceil() as usize;
+    (1..=rings)
+        .flat_map(|ring| {
+            (0..SEARCH_DIRECTIONS).map(move |i| {
+                let angle = i as f32 * std::f32::consts::TAU / SEARCH_DIRECTIONS as f32;
+                center + ring as f32 * SEARCH_STEP * Vector2::new(angle.cos(), angle.sin())
+            })
+        })
+        .find(|&p| is_free(p))
+}
+
+/// Blobs stuck in a wall for a while are moved out to the nearest free
+/// spot, so players don't have to kill themselves to get out. Every rescue
+/// is logged, as it points at a problem with the map or the physics.
+#[derive(Default)]
+pub struct StuckWatch {
+    // Ticks in a row each blob has been deep in a wall.
+    ticks: HashMap<usize, u32>,
+}
+
+impl StuckWatch {
+    pub fn new() -> StuckWatch {
+        StuckWatch::default()
+    }
+
+    /// Call once per tick, after the blobs have moved.
+    pub fn update(&mut self, blobs: &mut BTreeMap<usize, Blob>, level: &Level, rule: HostileEntry) {
+        self.ticks.retain(|id, _| blobs.contains_key(id));
+        for (&id, blob) in blobs.iter_mut() {
+            let blocking = blocking_regions(level, rule, blob);
+            if !is_deep_in_wall(&blocking, blob.center) {
+                self.ticks.remove(&id);
+                continue;
+            }
+            let ticks = self.ticks.entry(id).or_insert(0);
+            *ticks += 1;
+            if *ticks < STUCK_TICKS {
+                continue;
+            }
+            *ticks = 0;
+            match nearest_free_spot(&blocking, blob.center) {
+                Some(spot) => {
+                    log::warn!(
+                        "Blob {} was stuck in a wall at ({:.0}, {:.0}) on map {}, moved it to \
+                         ({:.0}, {:.0})",
+                        id,
+                        blob.center.x,
+                        blob.center.y,
+                        level.hash,
+                        spot.x,
+                        spot.y
+                    );
+                    blob.center = spot;
+                    blob.vel = Vector2::zeros();
+                }
+                None => log::warn!(
+                    "Blob {} is stuck in a wall at ({:.0}, {:.0}) on map {} with nowhere to go",
+                    id,
+                    blob.center.x,
+                    blob.center.y,
+                    level.hash
+                ),
+            }
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use nalgebra::Point2;
+use std::collections::VecDeque;
+
+// Ticks of positions kept.
+const LENGTH: usize = 12;
+
+/// Where a blob has recently been, drawn as a streak behind fast blobs.
+#[derive(Default)]
+pub struct Trail {
+    // Newest first.
+    points: VecDeque<Point2<f32>>,
+}
+
+impl Trail {
+    /// Call once per tick with where the blob is now.
+    pub fn update(&mut self, center: Point2<f32>) {
+        self.points.push_front(center);
+        self.points.truncate(LENGTH);
+    }
+
+    /// Where the blob has been, newest first.
+    pub fn points(&self) -> &VecDeque<Point2<f32>> {
+        &self.points
+    }
+}
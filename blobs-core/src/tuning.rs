@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+
+/// The physics constants that decide how the game feels, read from a file
+/// at startup and whenever it changes during a match, so they can be tried
+/// out without building the game again.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tuning {
+    /// Physical spring constant of the rope divided by blob mass.
+    pub spring_const: f32,
+    /// Length of the rope when it pulls with no force.
+    pub spring_eq_len: f32,
+    pub damping_const: f32,
+    pub gravity: f32,
+    pub hook_traveling_speed: f32,
+    pub blob_radius: f32,
+}
+
+impl Default for Tuning {
+    fn default() -> Tuning {
+        Tuning {
+            spring_const: 20.0,
+            spring_eq_len: 40.0,
+            damping_const: 0.01,
+            gravity: 10.0,
+            hook_traveling_speed: 150.0,
+            blob_radius: 40.0,
+        }
+    }
+}
+
+impl Tuning {
+    /// Describe every value that would break the game.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut positive = |name: &str, value: f32| {
+            if value.is_nan() || value <= 0.0 {
+                errors.push(format!("{} must be positive, not {}", name, value));
+            }
+        };
+        positive("spring_const", self.spring_const);
+        positive("hook_traveling_speed", self.hook_traveling_speed);
+        positive("blob_radius", self.blob_radius);
+        let mut not_negative = |name: &str, value: f32| {
+            if value.is_nan() || value < 0.0 {
+                errors.push(format!("{} can't be negative, not {}", name, value));
+            }
+        };
+        not_negative("spring_eq_len", self.spring_eq_len);
+        not_negative("damping_const", self.damping_const);
+        not_negative("gravity", self.gravity);
+        errors
+    }
+}
+
+thread_local! {
+    static CURRENT: Cell<Tuning> = Cell::new(Tuning::default());
+}
+
+pub fn current() -> Tuning {
+    CURRENT.with(|current| current.get())
+}
+
+/// Make the tuning current until the file is read again, for changing single
+/// values from the console.
+pub fn set_current(tuning: Tuning) {
+    CURRENT.with(|current| current.set(tuning));
+}
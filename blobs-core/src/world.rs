@@ -0,0 +1,150 @@
+use nalgebra::{Point2, Vector2};
+use std::collections::BTreeMap;
+
+use crate::balance;
+use crate::collision::wall_blob_collision;
+use crate::entity::Entity;
+use crate::grid::Grid;
+use crate::health::{self, Damage, DamageSource, GameEvent};
+use crate::level::{HostileEntry, Level};
+use crate::physics::{self, PhysicsWorld};
+use crate::pickup::Pickups;
+use crate::stuck::StuckWatch;
+use crate::{enforce_hostile_entry, touch_hazards, Blob};
+
+/// What the match rules decide about a step.
+#[derive(Clone, Copy, Debug)]
+pub struct Rules {
+    pub hostile_entry: HostileEntry,
+    /// Without damage, only suicides hurt.
+    pub allows_damage: bool,
+}
+
+/// Something hitting a wall during a step, for the client to show.
+#[derive(Clone, Copy, Debug)]
+pub enum Impact {
+    /// A hook caught on a wall at the point. Back points from there to the
+    /// hook's blob.
+    Hook {
+        point: Point2<f32>,
+        back: Vector2<f32>,
+    },
+    /// A blob bounced off a wall at the given speed in world units per DT.
+    /// Wall is where it still touches the wall after the step, and the
+    /// wall's normal.
+    Blob {
+        id: usize,
+        speed: f32,
+        wall: Option<(Point2<f32>, Vector2<f32>)>,
+    },
+}
+
+/// The blobs and everything they touch, stepped a tick at a time. Input is
+/// applied to the blobs before each step and the match rules react to the
+/// events after it.
+pub struct World {
+    pub blobs: BTreeMap<usize, Blob>,
+    pub level: Level,
+    pub pickups: Pickups,
+    pub entities: Vec<Entity>,
+    /// Damage to deal in the next step, along with what the step causes.
+    pub damage: Vec<Damage>,
+    /// What happened during the last step.
+    pub events: Vec<GameEvent>,
+    /// Walls hit during the last step.
+    pub impacts: Vec<Impact>,
+    pub physics: Box<dyn PhysicsWorld>,
+    /// Where the blobs were once they had moved in the last step.
+    pub grid: Grid,
+    stuck: StuckWatch,
+}
+
+impl World {
+    /// A level without any blobs in it yet.
+    pub fn new(level: Level) -> World {
+        World {
+            blobs: BTreeMap::new(),
+            pickups: Pickups::new(&level.pickups),
+            level,
+            entities: Vec::new(),
+            damage: Vec::new(),
+            events: Vec::new(),
+            impacts: Vec::new(),
+            physics: physics::backend(),
+            grid: Grid::new(),
+            stuck: StuckWatch::new(),
+        }
+    }
+
+    /// Put the pickups back and clear out the entities and damage, for a new
+    /// round. The blobs are left to the caller.
+    pub fn reset(&mut self) {
+        self.pickups = Pickups::new(&self.level.pickups);
+        self.entities.clear();
+        self.damage.clear();
+    }
+
+    /// Ids of the blobs within radius of p, by where they were at the end
+    /// of the last step. Blobs that died in it are left out.
+    pub fn blobs_near(&self, p: Point2<f32>, radius: f32) -> impl Iterator<Item = usize> + '_ {
+        self.grid
+            .near(p, radius)
+            .filter(move |&(id, center)| {
+                (center - p).norm() < radius && self.blobs.contains_key(&id)
+            })
+            .map(|(id, _)| id)
+    }
+
+    /// Move everything one tick forward and deal the damage.
+    pub fn step(&mut self, rules: Rules) {
+        self.events.clear();
+        self.impacts.clear();
+        self.physics.step(&mut self.blobs);
+        for (&id, blob) in self.blobs.iter_mut() {
+            if let Some(point) = blob.update() {
+                self.impacts.push(Impact::Hook {
+                    point,
+                    back: blob.center - point,
+                });
+            }
+            if let Some(speed) = self.physics.impact_speed(id) {
+                self.impacts.push(Impact::Blob {
+                    id,
+                    speed,
+                    wall: wall_blob_collision(blob.center),
+                });
+                let class = balance::current().blob;
+                if speed > class.impact_damage_speed {
+                    self.damage.push(Damage {
+                        target: id,
+                        amount: (speed - class.impact_damage_speed) * class.impact_damage_per_speed,
+                        source: DamageSource::Impact,
+                    });
+                }
+            }
+            enforce_hostile_entry(&self.level, rules.hostile_entry, id, blob, &mut self.damage);
+            touch_hazards(&self.level, id, blob, &mut self.damage);
+        }
+        self.stuck
+            .update(&mut self.blobs, &self.level, rules.hostile_entry);
+        // Projectiles and pickups only push blobs, so the grid stays right
+        // for both.
+        self.grid.rebuild(&self.blobs);
+        let blobs = &mut self.blobs;
+        let grid = &self.grid;
+        let damage = &mut self.damage;
+        self.entities
+            .retain_mut(|entity| entity.update(blobs, grid, damage));
+        self.pickups.update(&mut self.blobs, &self.grid);
+        if !rules.allows_damage {
+            self.damage
+                .retain(|damage| damage.source == DamageSource::Suicide);
+        }
+        health::apply_damage(
+            &mut self.damage,
+            &mut self.blobs,
+            &self.level,
+            &mut self.events,
+        );
+    }
+}